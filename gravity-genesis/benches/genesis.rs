@@ -0,0 +1,163 @@
+//! Wall time of a full generation, `compute_genesis_state`, for growing validator
+//! sets, followed by the time of each initialization transaction of one run.
+//!
+//! Like the end-to-end test it needs the contracts compiled by `forge build` at the
+//! repository root, or a Foundry `out` directory in `GRAVITY_GENESIS_BYTE_CODE_DIR`:
+//!
+//! ```bash
+//! forge build && cargo bench --bench genesis
+//! ```
+//!
+//! `GRAVITY_GENESIS_BENCH_LATENCY_US` sleeps that many microseconds on every state
+//! read of the initialization, as `--sim-latency-us` does.
+//!
+//! `GRAVITY_GENESIS_BENCH_PEAK_RSS` skips the timing and instead runs one
+//! `genesis_generate` of the largest set, printing the peak RSS of the process.
+//! Set it to `low-memory` to generate with `--low-memory`, to anything else for
+//! the default mode; each mode needs its own process:
+//!
+//! ```bash
+//! GRAVITY_GENESIS_BENCH_PEAK_RSS=default cargo bench --bench genesis
+//! GRAVITY_GENESIS_BENCH_PEAK_RSS=low-memory cargo bench --bench genesis
+//! ```
+
+use std::{path::PathBuf, time::Duration};
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use gravity_genesis::{
+    execute::{self, GenerateOptions},
+    genesis::{GenesisConfig, ValidatorSpec},
+    utils::peak_rss_kb,
+};
+use revm_primitives::{Address, hex, keccak256};
+
+const VALIDATOR_COUNTS: [usize; 4] = [1, 10, 100, 1000];
+
+fn byte_code_dir() -> String {
+    std::env::var("GRAVITY_GENESIS_BYTE_CODE_DIR").unwrap_or_else(|_| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../out")
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+fn options() -> GenerateOptions {
+    let latency_us = std::env::var("GRAVITY_GENESIS_BENCH_LATENCY_US")
+        .ok()
+        .and_then(|latency| latency.parse().ok())
+        .unwrap_or(0);
+    GenerateOptions {
+        sim_latency: Duration::from_micros(latency_us),
+        ..Default::default()
+    }
+}
+
+/// `count` validators with distinct addresses, keys and network addresses. The keys
+/// only have the length of a BLS public key, which is all initialization checks
+fn config(count: usize) -> GenesisConfig {
+    let validators = (0..count as u64)
+        .map(|index| {
+            let seed = keccak256(index.to_be_bytes());
+            let address = Address::from_slice(&seed[12..]);
+            let network_address = format!(
+                "/ip4/10.0.{}.{}/tcp/2024/noise-ik/{}/handshake/0",
+                index / 256,
+                index % 256,
+                hex::encode(seed)
+            );
+            ValidatorSpec {
+                address: address.to_string(),
+                consensus_public_key: hex::encode([seed.as_slice(), &seed[..16]].concat()),
+                voting_power: "20000".to_string(),
+                validator_network_address: network_address.clone(),
+                fullnode_network_address: network_address,
+                aptos_address: hex::encode(address.into_word()),
+                commission_rate: None,
+            }
+        })
+        .collect();
+    GenesisConfig {
+        validators,
+        chain_id: Some(1337),
+        // A thousand validators do not fit in `DEFAULT_GAS_LIMIT`
+        genesis_gas_limit: Some(execute::UNBOUNDED_GAS_LIMIT),
+        genesis_timestamp: Some(1_700_000_000),
+        ..Default::default()
+    }
+}
+
+/// Generate the largest set once into a scratch directory and print the peak RSS
+fn report_peak_rss(byte_code_dir: &str, options: &GenerateOptions, low_memory: bool) {
+    let count = VALIDATOR_COUNTS[VALIDATOR_COUNTS.len() - 1];
+    let output_dir = std::env::temp_dir().join(format!("genesis_bench_{}", std::process::id()));
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let options = GenerateOptions {
+        low_memory,
+        ..options.clone()
+    };
+    execute::genesis_generate(
+        byte_code_dir,
+        output_dir.to_str().unwrap(),
+        &config(count),
+        None,
+        None,
+        &options,
+    )
+    .unwrap_or_else(|e| panic!("{}", e));
+    println!(
+        "{} validators{}: peak RSS {} kB",
+        count,
+        if low_memory { " with --low-memory" } else { "" },
+        peak_rss_kb().unwrap_or_default()
+    );
+    std::fs::remove_dir_all(&output_dir).unwrap();
+}
+
+fn bench_compute_genesis_state(c: &mut Criterion) {
+    let byte_code_dir = byte_code_dir();
+    let options = options();
+    if let Err(e) =
+        execute::preflight_check(&byte_code_dir, options.deploy_mode, options.artifact_format)
+    {
+        eprintln!("Skipping the genesis benchmark: {}", e);
+        return;
+    }
+    if let Ok(mode) = std::env::var("GRAVITY_GENESIS_BENCH_PEAK_RSS") {
+        report_peak_rss(&byte_code_dir, &options, mode == "low-memory");
+        return;
+    }
+
+    let mut group = c.benchmark_group("compute_genesis_state");
+    group.sample_size(10);
+    for count in VALIDATOR_COUNTS {
+        let config = config(count);
+        config.validate().unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(count), &config, |b, config| {
+            b.iter(|| {
+                execute::compute_genesis_state(&byte_code_dir, config, None, None, &options)
+                    .unwrap_or_else(|report| panic!("{}", report))
+            })
+        });
+    }
+    group.finish();
+
+    // Where the time of one run goes, per initialization transaction
+    for count in VALIDATOR_COUNTS {
+        let summary =
+            execute::genesis_dry_run(&byte_code_dir, &config(count), None, None, &options)
+                .unwrap_or_else(|report| panic!("{}", report));
+        println!("{} validators:", count);
+        for transaction in &summary.transactions {
+            if let Some(elapsed) = transaction.elapsed {
+                println!(
+                    "  transaction {}: {:?}, {} gas",
+                    transaction.index, elapsed, transaction.gas_used
+                );
+            }
+        }
+    }
+}
+
+criterion_group!(benches, bench_compute_genesis_state);
+criterion_main!(benches);
@@ -0,0 +1,191 @@
+use alloy_dyn_abi::{DynSolType, DynSolValue, JsonAbiExt, Specifier};
+use alloy_json_abi::{Function, JsonAbi};
+use revm_primitives::{Address, TxEnv};
+use serde_json::Value;
+
+use crate::{error::GenesisError, utils::new_system_call_txn};
+
+/// A system call to `contract` of `function`, encoded from the ABI in `abi_json`
+/// without a `sol!` definition
+///
+/// `abi_json` is a bare ABI array or an artifact carrying an `abi` field. `function`
+/// is a name, or a full signature such as `transfer(address,uint256)` to pick among
+/// overloads. Every argument is a JSON value: strings, numbers and booleans are
+/// parsed as the parameter type, and arrays give the elements of array and tuple
+/// parameters. Amounts beyond 2^53 must be strings to keep their precision.
+pub fn encode_system_call(
+    abi_json: &str,
+    contract: Address,
+    function: &str,
+    args: &[Value],
+) -> Result<TxEnv, GenesisError> {
+    let failed = |reason: String| GenesisError::AbiEncode {
+        function: function.to_string(),
+        reason,
+    };
+    let abi = parse_abi(abi_json).map_err(failed)?;
+    let function = find_function(&abi, function, args.len()).map_err(failed)?;
+    let failed = |reason: String| GenesisError::AbiEncode {
+        function: function.signature(),
+        reason,
+    };
+    if function.inputs.len() != args.len() {
+        return Err(failed(format!(
+            "{} arguments given for {} parameters",
+            args.len(),
+            function.inputs.len()
+        )));
+    }
+
+    let values = function
+        .inputs
+        .iter()
+        .zip(args)
+        .enumerate()
+        .map(|(index, (param, arg))| {
+            param
+                .resolve()
+                .map_err(|e| e.to_string())
+                .and_then(|ty| json_to_sol_value(&ty, arg))
+                .map_err(|e| failed(format!("argument {}: {}", index, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let data = function
+        .abi_encode_input(&values)
+        .map_err(|e| failed(e.to_string()))?;
+    Ok(new_system_call_txn(contract, data.into()))
+}
+
+fn parse_abi(abi_json: &str) -> Result<JsonAbi, String> {
+    let value: Value =
+        serde_json::from_str(abi_json).map_err(|e| format!("ABI is not JSON: {}", e))?;
+    let abi = match value {
+        Value::Array(_) => value,
+        Value::Object(mut object) => object.remove("abi").ok_or("artifact has no abi field")?,
+        _ => return Err("ABI is neither an array nor an artifact".to_string()),
+    };
+    serde_json::from_value(abi).map_err(|e| format!("invalid ABI: {}", e))
+}
+
+/// The function named `function`, or with that signature. A bare name must leave a
+/// single overload once those not taking `arg_count` arguments are dropped
+fn find_function<'a>(
+    abi: &'a JsonAbi,
+    function: &str,
+    arg_count: usize,
+) -> Result<&'a Function, String> {
+    let candidates: Vec<&Function> = if function.contains('(') {
+        let signature: String = function.split_whitespace().collect();
+        abi.functions()
+            .filter(|candidate| candidate.signature() == signature)
+            .collect()
+    } else {
+        abi.function(function)
+            .into_iter()
+            .flatten()
+            .filter(|candidate| candidate.inputs.len() == arg_count)
+            .collect()
+    };
+    match candidates.as_slice() {
+        [function] => Ok(function),
+        [] => Err(format!(
+            "no such function taking {} arguments in the ABI",
+            arg_count
+        )),
+        _ => Err(format!(
+            "{} overloads take {} arguments, name one by its signature",
+            candidates.len(),
+            arg_count
+        )),
+    }
+}
+
+fn json_to_sol_value(ty: &DynSolType, value: &Value) -> Result<DynSolValue, String> {
+    let elements = |inner: &DynSolType, items: &[Value]| {
+        items
+            .iter()
+            .map(|item| json_to_sol_value(inner, item))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    match (ty, value) {
+        (DynSolType::Array(inner), Value::Array(items)) => {
+            elements(inner, items).map(DynSolValue::Array)
+        }
+        (DynSolType::FixedArray(inner, len), Value::Array(items)) if items.len() == *len => {
+            elements(inner, items).map(DynSolValue::FixedArray)
+        }
+        (DynSolType::Tuple(types), Value::Array(items)) if items.len() == types.len() => types
+            .iter()
+            .zip(items)
+            .map(|(ty, item)| json_to_sol_value(ty, item))
+            .collect::<Result<Vec<_>, _>>()
+            .map(DynSolValue::Tuple),
+        (_, Value::String(s)) => ty.coerce_str(s).map_err(|e| e.to_string()),
+        (_, Value::Number(_) | Value::Bool(_)) => {
+            ty.coerce_str(&value.to_string()).map_err(|e| e.to_string())
+        }
+        _ => Err(format!("{} is not a {}", value, ty.sol_type_name())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{GENESIS_ADDR, SYSTEM_CALLER};
+    use revm_primitives::{TxKind, U256, hex};
+    use serde_json::json;
+
+    const ABI: &str = r#"[
+        {"type": "function", "name": "set", "stateMutability": "nonpayable", "outputs": [],
+         "inputs": [{"name": "value", "type": "uint256"}]},
+        {"type": "function", "name": "set", "stateMutability": "nonpayable", "outputs": [],
+         "inputs": [{"name": "flag", "type": "bool"}]},
+        {"type": "function", "name": "register", "stateMutability": "nonpayable", "outputs": [],
+         "inputs": [
+            {"name": "powers", "type": "uint256[]"},
+            {"name": "entry", "type": "tuple", "components": [
+                {"name": "account", "type": "address"},
+                {"name": "active", "type": "bool"}
+            ]}
+         ]}
+    ]"#;
+
+    #[test]
+    fn test_encode_system_call() {
+        let tx = encode_system_call(ABI, GENESIS_ADDR, "set(uint256)", &[json!(7)]).unwrap();
+        assert_eq!(tx.caller, SYSTEM_CALLER);
+        assert_eq!(tx.transact_to, TxKind::Call(GENESIS_ADDR));
+        let mut expected = hex::decode("60fe47b1").unwrap();
+        expected.extend(U256::from(7).to_be_bytes::<32>());
+        assert_eq!(tx.data.to_vec(), expected);
+
+        let artifact = json!({ "abi": serde_json::from_str::<Value>(ABI).unwrap() });
+        let tx = encode_system_call(
+            &artifact.to_string(),
+            GENESIS_ADDR,
+            "register",
+            &[
+                json!(["1", 2]),
+                json!(["0x6e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f", true]),
+            ],
+        )
+        .unwrap();
+        let abi = parse_abi(ABI).unwrap();
+        let decoded = abi.function("register").unwrap()[0]
+            .abi_decode_input(&tx.data[4..], true)
+            .unwrap();
+        assert_eq!(
+            decoded[0],
+            DynSolValue::Array(vec![
+                DynSolValue::Uint(U256::from(1), 256),
+                DynSolValue::Uint(U256::from(2), 256),
+            ])
+        );
+
+        // `set` is overloaded, so its name alone does not pick one
+        let error = encode_system_call(ABI, GENESIS_ADDR, "set", &[json!(7)]).unwrap_err();
+        assert!(error.to_string().contains("overloads"), "{}", error);
+        assert!(encode_system_call(ABI, GENESIS_ADDR, "set(bool)", &[json!(7)]).is_err());
+        assert!(encode_system_call(ABI, GENESIS_ADDR, "set(bool)", &[]).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use revm::db::PlainAccount;
+use revm_primitives::{Address, B256, U256};
+
+use crate::utils::{CONTRACTS, ChecksumAddress};
+
+/// A storage slot whose value differs between two runs. Zero and absent slots are
+/// the same, so `None` is a slot that is unset on that side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotDiff {
+    pub slot: U256,
+    pub old: Option<U256>,
+    pub new: Option<U256>,
+}
+
+/// How one address differs between two `genesis_accounts.json` files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub address: Address,
+    /// Code hash in the old and the new run, `None` where the account does not exist
+    pub code_hash: (Option<B256>, Option<B256>),
+    /// Differing slots in slot order
+    pub storage: Vec<SlotDiff>,
+}
+
+impl AccountDiff {
+    pub fn is_added(&self) -> bool {
+        self.code_hash.0.is_none()
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.code_hash.1.is_none()
+    }
+}
+
+/// Parse a `genesis_accounts.json` in address order
+pub fn read_accounts(path: &str) -> Result<BTreeMap<Address, PlainAccount>, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("failed to parse {}: {}", path, e))
+}
+
+/// Every address whose code hash or storage differs between `old` and `new`, in
+/// address order. Accounts present on one side only are listed with all their slots
+pub fn diff_accounts(
+    old: &BTreeMap<Address, PlainAccount>,
+    new: &BTreeMap<Address, PlainAccount>,
+) -> Vec<AccountDiff> {
+    let addresses: BTreeSet<&Address> = old.keys().chain(new.keys()).collect();
+    addresses
+        .into_iter()
+        .filter_map(|address| {
+            let (old, new) = (old.get(address), new.get(address));
+            let old_storage = old.map(non_zero_storage).unwrap_or_default();
+            let new_storage = new.map(non_zero_storage).unwrap_or_default();
+            let storage: Vec<SlotDiff> = old_storage
+                .keys()
+                .chain(new_storage.keys())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .map(|slot| SlotDiff {
+                    slot: *slot,
+                    old: old_storage.get(slot).copied(),
+                    new: new_storage.get(slot).copied(),
+                })
+                .filter(|slot| slot.old != slot.new)
+                .collect();
+            let code_hash = (old.map(code_hash), new.map(code_hash));
+            let changed =
+                old.is_none() || new.is_none() || code_hash.0 != code_hash.1 || !storage.is_empty();
+            changed.then_some(AccountDiff {
+                address: *address,
+                code_hash,
+                storage,
+            })
+        })
+        .collect()
+}
+
+/// Hash of the account's code. The code itself is hashed, since the `code_hash`
+/// written next to it is not kept up to date for deployed contracts
+fn code_hash(account: &PlainAccount) -> B256 {
+    account
+        .info
+        .code
+        .as_ref()
+        .map_or(account.info.code_hash, |code| code.hash_slow())
+}
+
+fn non_zero_storage(account: &PlainAccount) -> BTreeMap<U256, U256> {
+    account
+        .storage
+        .iter()
+        .filter(|(_, value)| !value.is_zero())
+        .map(|(slot, value)| (*slot, *value))
+        .collect()
+}
+
+/// The diffs as plain text, one section per address. The same inputs always give
+/// the same text, so it can be pasted into a review or committed
+pub fn render_diff(diffs: &[AccountDiff]) -> String {
+    if diffs.is_empty() {
+        return "No differences\n".to_string();
+    }
+    let added = diffs.iter().filter(|diff| diff.is_added()).count();
+    let removed = diffs.iter().filter(|diff| diff.is_removed()).count();
+    let mut out = format!(
+        "{} accounts differ: {} added, {} removed, {} changed\n",
+        diffs.len(),
+        added,
+        removed,
+        diffs.len() - added - removed
+    );
+    for diff in diffs {
+        let status = if diff.is_added() {
+            "added"
+        } else if diff.is_removed() {
+            "removed"
+        } else {
+            "changed"
+        };
+        let name = CONTRACTS
+            .iter()
+            .find(|(_, address)| *address == diff.address)
+            .map_or(String::new(), |(name, _)| format!(" {}", name));
+        out.push_str(&format!(
+            "\n{}{} ({})\n",
+            ChecksumAddress(diff.address),
+            name,
+            status
+        ));
+        match diff.code_hash {
+            (Some(old), Some(new)) if old != new => {
+                out.push_str(&format!("  code hash {:?} -> {:?}\n", old, new));
+            }
+            (None, Some(hash)) | (Some(hash), None) => {
+                out.push_str(&format!("  code hash {:?}\n", hash));
+            }
+            _ => {}
+        }
+        for slot in &diff.storage {
+            let line = match (slot.old, slot.new) {
+                (Some(old), Some(new)) => format!("~ {:#x}: {:#x} -> {:#x}", slot.slot, old, new),
+                (None, Some(new)) => format!("+ {:#x}: {:#x}", slot.slot, new),
+                (Some(old), None) => format!("- {:#x}: {:#x}", slot.slot, old),
+                (None, None) => continue,
+            };
+            out.push_str(&format!("  {}\n", line));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::GENESIS_ADDR;
+    use revm_primitives::{AccountInfo, Bytecode, Bytes, address};
+
+    fn account(code: u8, storage: &[(u64, u64)]) -> PlainAccount {
+        PlainAccount {
+            info: AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from(vec![code]))),
+                ..AccountInfo::default()
+            },
+            storage: storage
+                .iter()
+                .map(|(slot, value)| (U256::from(*slot), U256::from(*value)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff_accounts() {
+        let faucet = address!("000000000000000000000000000000000000fa01");
+        let old = BTreeMap::from([
+            (GENESIS_ADDR, account(0x00, &[(1, 1), (2, 2), (3, 3)])),
+            (faucet, account(0x00, &[(1, 1)])),
+        ]);
+        let mut new = old.clone();
+        assert!(diff_accounts(&old, &new).is_empty());
+        assert_eq!(render_diff(&[]), "No differences\n");
+
+        // Slot 2 changes, slot 3 is zeroed and slot 4 is set
+        new.insert(
+            GENESIS_ADDR,
+            account(0x01, &[(1, 1), (2, 5), (3, 0), (4, 4)]),
+        );
+        new.remove(&faucet);
+        let diffs = diff_accounts(&old, &new);
+        assert_eq!(diffs.len(), 2);
+        assert!(diffs[1].is_removed());
+        let genesis = &diffs[0];
+        assert_ne!(genesis.code_hash.0, genesis.code_hash.1);
+        let slots: Vec<_> = genesis
+            .storage
+            .iter()
+            .map(|slot| (slot.slot.to::<u64>(), slot.old, slot.new))
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                (2, Some(U256::from(2)), Some(U256::from(5))),
+                (3, Some(U256::from(3)), None),
+                (4, None, Some(U256::from(4))),
+            ]
+        );
+
+        let report = render_diff(&diffs);
+        assert!(report.starts_with("2 accounts differ: 0 added, 1 removed, 1 changed\n"));
+        assert!(report.contains("Genesis (changed)"), "{}", report);
+        assert!(report.contains("  ~ 0x2: 0x2 -> 0x5\n  - 0x3: 0x3\n  + 0x4: 0x4\n"));
+        assert_eq!(report, render_diff(&diff_accounts(&old, &new)));
+    }
+}
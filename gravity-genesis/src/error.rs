@@ -0,0 +1,66 @@
+use revm_primitives::{EVMError, hex};
+
+use crate::{failure::FailureReport, genesis::GenesisConfigError};
+
+/// Failures of the JWK and post-genesis steps, typed so library callers can tell
+/// a missing input file from a reverted transaction
+#[derive(Debug, thiserror::Error)]
+pub enum GenesisError {
+    /// An input file could not be read
+    #[error("failed to read {path}: {source}")]
+    FileRead {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// An output file could not be written
+    #[error("failed to write {path}: {source}")]
+    FileWrite {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// An input file is not the expected JSON
+    #[error("failed to parse {path}: {source}")]
+    JsonParse {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// A hex encoded field could not be decoded
+    #[error("failed to decode hex {field}: {source}")]
+    HexDecode {
+        field: String,
+        #[source]
+        source: hex::FromHexError,
+    },
+    /// The EVM failed before producing a result
+    #[error("EVM error: {0}")]
+    Revm(String),
+    /// A transaction reverted or halted, `index` counting within its batch
+    #[error("transaction {index} failed: {reason}")]
+    TxReverted { index: usize, reason: String },
+    /// A call result could not be decoded or does not match the config
+    #[error("{0}")]
+    Verify(String),
+    /// A call could not be ABI encoded from its arguments
+    #[error("failed to encode a call of {function}: {reason}")]
+    AbiEncode { function: String, reason: String },
+    /// Bytecode files the deployment needs are absent or empty
+    #[error("{} bytecode files missing or empty in {dir}: {}", files.len(), files.join(", "))]
+    MissingBytecode { dir: String, files: Vec<String> },
+    /// The genesis config does not pass validation
+    #[error("invalid genesis config: {0}")]
+    Config(#[from] GenesisConfigError),
+    /// Generation stopped, as also described by the `failure.json` written for it
+    #[error("{0}")]
+    Failed(#[from] FailureReport),
+}
+
+impl GenesisError {
+    /// Wrap an EVM error; database errors are not `Debug` for every `DatabaseRef`,
+    /// so only their presence is kept
+    pub fn revm<E>(error: EVMError<E>) -> Self {
+        GenesisError::Revm(format!("{:?}", error.map_db_err(|_| "database error")))
+    }
+}
@@ -0,0 +1,193 @@
+use std::{collections::BTreeMap, path::Path};
+
+use alloy_json_abi::JsonAbi;
+use revm_primitives::hex;
+use serde::Serialize;
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+/// A custom error definition, deduplicated across every contract that declares it
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorEntry {
+    pub selector: String,
+    pub signature: String,
+    pub contracts: Vec<String>,
+}
+
+/// Two or more distinct error signatures that hash to the same 4-byte selector
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectorCollision {
+    pub selector: String,
+    pub signatures: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ErrorCatalog {
+    pub errors: Vec<ErrorEntry>,
+    pub collisions: Vec<SelectorCollision>,
+}
+
+/// Extract the ABI from a JSON file, accepting both a bare ABI array and a
+/// Foundry/Hardhat artifact object carrying an `abi` field
+fn load_abi(path: &Path) -> Option<JsonAbi> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let abi_value = match value {
+        serde_json::Value::Array(_) => value,
+        serde_json::Value::Object(mut object) => object.remove("abi")?,
+        _ => return None,
+    };
+    match serde_json::from_value::<JsonAbi>(abi_value) {
+        Ok(abi) => Some(abi),
+        Err(e) => {
+            debug!("Skipping {}: not a valid ABI ({})", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Collect every custom `error` defined across the contract ABIs found under `abi_dir`
+///
+/// Errors are keyed by their canonical signature, so an error inherited by many
+/// contracts (e.g. `OnlySystemCaller(address)` from `System`) is reported once with
+/// the full list of contracts exposing it. Distinct signatures sharing a selector are
+/// reported separately as collisions.
+pub fn collect_errors(abi_dir: &str) -> Result<ErrorCatalog, String> {
+    if !Path::new(abi_dir).is_dir() {
+        return Err(format!("ABI directory not found: {}", abi_dir));
+    }
+
+    let mut by_signature: BTreeMap<String, ErrorEntry> = BTreeMap::new();
+    let mut abi_count = 0;
+
+    for entry in WalkDir::new(abi_dir).sort_by_file_name() {
+        let entry = entry.map_err(|e| format!("Failed to walk {}: {}", abi_dir, e))?;
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let Some(abi) = load_abi(path) else {
+            continue;
+        };
+        abi_count += 1;
+
+        let contract_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        for error in abi.errors() {
+            let signature = error.signature();
+            let entry = by_signature
+                .entry(signature.clone())
+                .or_insert_with(|| ErrorEntry {
+                    selector: format!("0x{}", hex::encode(error.selector())),
+                    signature,
+                    contracts: Vec::new(),
+                });
+            if !entry.contracts.contains(&contract_name) {
+                entry.contracts.push(contract_name.clone());
+            }
+        }
+    }
+
+    info!(
+        "Loaded {} ABIs from {}, found {} distinct custom errors",
+        abi_count,
+        abi_dir,
+        by_signature.len()
+    );
+
+    let mut by_selector: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for entry in by_signature.values() {
+        by_selector
+            .entry(entry.selector.clone())
+            .or_default()
+            .push(entry.signature.clone());
+    }
+
+    let collisions: Vec<SelectorCollision> = by_selector
+        .into_iter()
+        .filter(|(_, signatures)| signatures.len() > 1)
+        .map(|(selector, signatures)| {
+            warn!(
+                "Selector collision on {}: {}",
+                selector,
+                signatures.join(", ")
+            );
+            SelectorCollision {
+                selector,
+                signatures,
+            }
+        })
+        .collect();
+
+    let mut errors: Vec<ErrorEntry> = by_signature.into_values().collect();
+    errors.sort_by(|a, b| a.selector.cmp(&b.selector));
+
+    Ok(ErrorCatalog { errors, collisions })
+}
+
+/// Print the catalog as a human readable table
+pub fn print_error_catalog(catalog: &ErrorCatalog) {
+    for entry in &catalog.errors {
+        println!(
+            "{}  {}  [{}]",
+            entry.selector,
+            entry.signature,
+            entry.contracts.join(", ")
+        );
+    }
+    println!("Total custom errors: {}", catalog.errors.len());
+
+    if catalog.collisions.is_empty() {
+        println!("No selector collisions found");
+    } else {
+        for collision in &catalog.collisions {
+            println!(
+                "⚠️  Selector collision {}: {}",
+                collision.selector,
+                collision.signatures.join(", ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_errors_dedup_and_collisions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        std::fs::create_dir_all(dir.join("A.sol")).unwrap();
+        std::fs::create_dir_all(dir.join("B.sol")).unwrap();
+
+        // Foundry artifact layout: out/Name.sol/Name.json
+        std::fs::write(
+            dir.join("A.sol/A.json"),
+            r#"{"abi":[{"type":"error","name":"OnlyCoinbase","inputs":[]},
+                {"type":"error","name":"OnlySystemCaller","inputs":[{"name":"a","type":"address"}]}]}"#,
+        )
+        .unwrap();
+        // Bare ABI array, re-declaring a shared error
+        std::fs::write(
+            dir.join("B.sol/B.json"),
+            r#"[{"type":"error","name":"OnlyCoinbase","inputs":[]}]"#,
+        )
+        .unwrap();
+
+        let catalog = collect_errors(&dir.to_string_lossy()).unwrap();
+
+        assert_eq!(catalog.errors.len(), 2);
+        let only_coinbase = catalog
+            .errors
+            .iter()
+            .find(|e| e.signature == "OnlyCoinbase()")
+            .unwrap();
+        assert_eq!(only_coinbase.selector, "0x116c64a8");
+        assert_eq!(only_coinbase.contracts, vec!["A", "B"]);
+        assert!(catalog.collisions.is_empty());
+    }
+}
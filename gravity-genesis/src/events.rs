@@ -0,0 +1,213 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use alloy_sol_macro::sol;
+use alloy_sol_types::SolEvent;
+use revm_primitives::{Address, B256, Bytes, ExecutionResult, Log, LogData};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::ChecksumAddress;
+
+pub const EVENTS_FILE: &str = "genesis_events.json";
+
+type EventDecoder = fn(&LogData) -> Option<String>;
+
+fn decode_event<E: SolEvent + std::fmt::Debug>(data: &LogData) -> Option<String> {
+    E::decode_log_data(data, true)
+        .ok()
+        .map(|event| format!("{:?}", event))
+}
+
+macro_rules! system_events {
+    ($($(#[$meta:meta])* $name:ident($($params:tt)*);)*) => {
+        sol! {
+            #[derive(Debug)]
+            interface SystemEvents {
+                $($(#[$meta])* event $name($($params)*);)*
+            }
+        }
+
+        fn system_event_registry() -> HashMap<B256, (&'static str, EventDecoder)> {
+            HashMap::from([$((
+                SystemEvents::$name::SIGNATURE_HASH,
+                (
+                    stringify!($name),
+                    decode_event::<SystemEvents::$name> as EventDecoder,
+                ),
+            )),*])
+        }
+    };
+}
+
+// The events of `src/` that genesis initialization emits. `ConfigParamUpdated` is
+// left out, as its declarations share a signature but differ in which key is indexed
+system_events! {
+    // ValidatorManager
+    ValidatorRegistered(
+        address indexed validator,
+        address indexed operator,
+        bytes consensusPublicKey,
+        string moniker
+    );
+    StakeCreditDeployed(address indexed validator, address stakeCreditAddress);
+    ValidatorJoinRequested(address indexed validator, uint256 votingPower, uint64 epoch);
+    ValidatorStatusChanged(address indexed validator, uint8 oldStatus, uint8 newStatus, uint64 epoch);
+    ValidatorSetUpdated(
+        uint64 indexed epoch,
+        uint256 activeCount,
+        uint256 pendingActiveCount,
+        uint256 pendingInactiveCount,
+        uint256 totalVotingPower
+    );
+    CommissionRateEdited(address indexed operatorAddress, uint64 newCommissionRate);
+    OperatorUpdated(address indexed validator, address indexed oldOperator, address indexed newOperator);
+    // StakeCredit
+    Initialized(address validator, string moniker, address beneficiary);
+    StakeAdded(address indexed delegator, uint256 shares, uint256 gAmount);
+    // ValidatorPerformanceTracker
+    ActiveValidatorSetUpdated(uint256 indexed epoch, address[] validators);
+    PerformanceReset(uint256 indexed newEpoch, uint256 validatorCount);
+    // EpochManager
+    EpochTransitioned(uint256 indexed newEpoch, uint256 transitionTime);
+    EpochDurationUpdated(uint256 oldDuration, uint256 newDuration);
+    // JWKManager
+    OIDCProviderAdded(string indexed name, string configUrl);
+    OIDCProviderUpdated(string indexed name, string newConfigUrl);
+    // KeylessAccount
+    VerifierContractUpdated(address newVerifier);
+    ConfigurationUpdated(bytes32 configHash);
+    // GovToken
+    Transfer(address indexed from, address indexed to, uint256 value);
+    DelegateChanged(address indexed delegator, address indexed fromDelegate, address indexed toDelegate);
+    DelegateVotesChanged(address indexed delegate, uint256 previousBalance, uint256 newBalance);
+    // System
+    ParamChange(string key, bytes value);
+}
+
+static SYSTEM_EVENTS: LazyLock<HashMap<B256, (&'static str, EventDecoder)>> =
+    LazyLock::new(system_event_registry);
+
+/// Name and decoder of the system contract event `log` is, by its first topic
+fn known_event(log: &Log) -> Option<&'static (&'static str, EventDecoder)> {
+    log.data
+        .topics()
+        .first()
+        .and_then(|topic| SYSTEM_EVENTS.get(topic))
+}
+
+/// `log` with its decoded fields if it is a known system contract event, or else
+/// its address and first topic, so unknown events still show up in the logs
+pub fn describe_log(log: &Log) -> String {
+    match known_event(log) {
+        Some((name, decode)) => decode(&log.data).unwrap_or_else(|| {
+            format!(
+                "{} from {}, undecodable data",
+                name,
+                ChecksumAddress(log.address)
+            )
+        }),
+        None => match log.data.topics().first() {
+            Some(topic) => format!(
+                "unknown event {} from {}",
+                topic,
+                ChecksumAddress(log.address)
+            ),
+            None => format!("anonymous event from {}", ChecksumAddress(log.address)),
+        },
+    }
+}
+
+/// A log emitted during genesis, an entry of `genesis_events.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisEvent {
+    /// Index of the emitting transaction, numbered as in `genesis_txns.json`
+    pub transaction: usize,
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+    /// Name of the system contract event, if the first topic is a known one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The event with its decoded fields, if it decodes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<String>,
+}
+
+impl GenesisEvent {
+    pub fn new(transaction: usize, log: &Log) -> Self {
+        let known = known_event(log);
+        Self {
+            transaction,
+            address: log.address,
+            topics: log.data.topics().to_vec(),
+            data: log.data.data.clone(),
+            name: known.map(|(name, _)| name.to_string()),
+            decoded: known.and_then(|(_, decode)| decode(&log.data)),
+        }
+    }
+}
+
+/// Logs of the successful transactions in `results`, each paired with its
+/// transaction index counted from `first_index`
+pub fn collect_logs(first_index: usize, results: &[ExecutionResult]) -> Vec<(usize, Log)> {
+    results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| result.is_success())
+        .flat_map(|(i, result)| {
+            result
+                .logs()
+                .iter()
+                .map(move |log| (first_index + i, log.clone()))
+        })
+        .collect()
+}
+
+/// `genesis_events.json` entries of the collected logs, in emission order
+pub fn genesis_events(logs: &[(usize, Log)]) -> Vec<GenesisEvent> {
+    logs.iter()
+        .map(|(transaction, log)| GenesisEvent::new(*transaction, log))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::VALIDATOR_MANAGER_ADDR;
+    use revm_primitives::address;
+
+    #[test]
+    fn test_genesis_event_decodes_known_events() {
+        let validator = address!("6e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f");
+        let registered = SystemEvents::ValidatorRegistered {
+            validator,
+            operator: validator,
+            consensusPublicKey: Bytes::from(vec![0xab; 48]),
+            moniker: "validator-0".to_string(),
+        };
+        let log = Log {
+            address: VALIDATOR_MANAGER_ADDR,
+            data: registered.encode_log_data(),
+        };
+
+        let event = GenesisEvent::new(3, &log);
+        assert_eq!(event.transaction, 3);
+        assert_eq!(event.address, VALIDATOR_MANAGER_ADDR);
+        assert_eq!(event.name.as_deref(), Some("ValidatorRegistered"));
+        let decoded = event.decoded.unwrap();
+        assert!(decoded.contains("validator-0"), "{}", decoded);
+
+        let described = describe_log(&log);
+        assert!(
+            described.starts_with("ValidatorRegistered"),
+            "{}",
+            described
+        );
+
+        let unknown = Log::new_unchecked(VALIDATOR_MANAGER_ADDR, vec![B256::ZERO], Bytes::new());
+        let event = GenesisEvent::new(0, &unknown);
+        assert_eq!(event.name, None);
+        assert_eq!(event.decoded, None);
+        assert!(describe_log(&unknown).starts_with("unknown event 0x0000"));
+    }
+}
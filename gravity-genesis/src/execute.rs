@@ -1,123 +1,891 @@
 use crate::{
-    genesis::{GenesisConfig, call_genesis_initialize},
+    error::GenesisError,
+    events::{EVENTS_FILE, collect_logs, genesis_events},
+    failure::{FailurePhase, FailureReport, write_failure_report},
+    genesis::{
+        ExtraContractDeployment, GenesisConfig, GenesisConfigError, call_genesis_initialize,
+    },
     jwks::{upsert_observed_jwks, upsert_oidc_providers},
+    metrics::{
+        CONTRACT_METRICS_FILE, ContractMetrics, GAS_REPORT_FILE, TxReport, contract_metrics_csv,
+    },
+    profile::LatencyDB,
+    progress::{DeployReporter, DeployStatus, ProgressCallback},
+    reverts::{REVERTS_FILE, TransactionReverts, collect_reverts},
     utils::{
-        CONTRACTS, GENESIS_ADDR, SYSTEM_ACCOUNT_INFO, SYSTEM_CALLER, analyze_txn_result,
-        execute_revm_sequential, read_hex_from_file,
+        CONTRACTS, CREATE2_DEPLOYER, CREATE2_DEPLOYER_CODE, ChecksumAddress, DEAD_ADDRESS,
+        GENESIS_ADDR, RESERVED_ADDRESSES, SYSTEM_ACCOUNT_INFO, SYSTEM_CALLER, StagedFiles,
+        analyze_txn_result, artifact_path, check_unique_addresses, derive_access_lists,
+        execute_revm_sequential, execute_revm_sequential_timed, link_system_libraries,
+        new_system_call_txn, new_system_create_txn, new_system_create_txn_from_bytes, peak_rss_kb,
+        read_artifact, read_bytecode_from_artifact, read_deployed_bytecode_from_artifact,
+        read_hex_bytes_from_file, referenced_system_addresses, with_caller,
+        with_contract_addresses, with_gas_limit,
     },
 };
 
+use alloy_json_abi::JsonAbi;
+
 use alloy_chains::NamedChain;
 
+use clap::ValueEnum;
+use rayon::prelude::*;
 use revm::{
     InMemoryDB,
-    db::{BundleState, PlainAccount},
-    primitives::{AccountInfo, Env, SpecId, U256},
+    db::{AccountStatus, BundleAccount, BundleState, PlainAccount},
+    primitives::{AccountInfo, Address, Env, SpecId, U256},
+};
+use revm_primitives::{
+    AccessListItem, B256, Bytecode, Bytes, ExecutionResult, Log, Output, TxEnv, hex, keccak256,
+    uint,
+};
+use serde::{Deserialize, Serialize, Serializer, ser::SerializeMap};
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{BufWriter, Write},
+    time::Duration,
 };
-use revm_primitives::{Bytecode, Bytes, TxEnv, hex};
-use std::{collections::HashMap, fs::File, io::BufWriter};
 use tracing::{debug, error, info, warn};
 
+/// How each system contract's code ends up at its fixed address
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DeployMode {
+    /// Place the runtime bytecode, read as `ArtifactFormat` selects, directly at the
+    /// system address (BSC style). Constructors are never executed.
+    Inject,
+    /// Execute each contract's creation bytecode from its Foundry artifact and place
+    /// the runtime code returned by the constructor at the system address
+    #[default]
+    Construct,
+}
+
+/// Where inject mode reads each system contract's runtime bytecode from. Construct
+/// mode always runs the creation bytecode of the Foundry artifacts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ArtifactFormat {
+    /// Pre-extracted `<Name>.hex` files
+    #[default]
+    Hex,
+    /// `deployedBytecode.object` of the Foundry artifacts, `<Name>.sol/<Name>.json`
+    /// as found in Foundry's `out` directory
+    Foundry,
+}
+
+/// State formats written besides the native `genesis_accounts.json`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Only the native files
+    #[default]
+    Native,
+    /// Also `genesis_alloc.json`, the `alloc` section of a geth style genesis.json
+    Geth,
+}
+
+pub const GETH_ALLOC_FILE: &str = "genesis_alloc.json";
+pub const COMBINED_GENESIS_FILE: &str = "genesis.json";
+
+/// Which build produced a combined `genesis.json`, and for which chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisMetadata {
+    pub chain_id: u64,
+    /// The configured `genesisTimestamp` in Unix seconds, left out when unset so the
+    /// file stays the same between otherwise identical runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Version of the gravity-genesis crate that generated the file
+    pub tool_version: String,
+}
+
+impl GenesisMetadata {
+    pub fn new(chain_id: u64, timestamp: Option<u64>) -> Self {
+        Self {
+            chain_id,
+            timestamp,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Contents of a combined `genesis.json`: the config it was generated from, the
+/// accounts and contracts of `genesis_accounts.json` and `genesis_contracts.json`,
+/// and its metadata
+#[derive(Serialize)]
+struct CombinedGenesis<'a, A, C> {
+    config: &'a GenesisConfig,
+    accounts: A,
+    contracts: C,
+    metadata: GenesisMetadata,
+}
+
+/// Balance a system contract starts with at genesis
+fn genesis_balance(contract_name: &str) -> U256 {
+    // Set large balance for JWK Manager and Validator Manager
+    if contract_name == "JwkManager"
+        || contract_name == "ValidatorManager"
+        || contract_name == "Genesis"
+    {
+        // Set 1 million ETH balance (1e6 * 1e18 wei)
+        U256::from(1_000_000) * U256::from(10).pow(U256::from(18))
+    } else {
+        U256::ZERO
+    }
+}
+
+fn insert_system_contract(
+    db: &mut InMemoryDB,
+    contract_name: &str,
+    target_address: Address,
+    runtime_bytecode: Bytes,
+) {
+    let balance = genesis_balance(contract_name);
+
+    db.insert_account_info(
+        target_address,
+        AccountInfo {
+            code: Some(Bytecode::new_raw(runtime_bytecode)),
+            balance,
+            ..AccountInfo::default()
+        },
+    );
+
+    if balance > U256::ZERO {
+        info!(
+            "Deployed {} runtime bytecode to {} with balance {} ETH",
+            contract_name,
+            ChecksumAddress(target_address),
+            balance / U256::from(10).pow(U256::from(18))
+        );
+    } else {
+        info!(
+            "Deployed {} runtime bytecode to {}",
+            contract_name,
+            ChecksumAddress(target_address)
+        );
+    }
+}
+
 // Alternative approach: Use BSC-style direct bytecode deployment
-fn deploy_bsc_style(byte_code_dir: &str) -> InMemoryDB {
+fn deploy_bsc_style(
+    byte_code_dir: &str,
+    system_caller: Address,
+    config: &GenesisConfig,
+    artifact_format: ArtifactFormat,
+    progress: DeployReporter,
+) -> Result<InMemoryDB, FailureReport> {
     let mut db = InMemoryDB::default();
+    let contracts = config.contract_addresses();
 
     // Add system address with balance
-    db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+    db.insert_account_info(system_caller, config.system_account_info());
+
+    // Reading and decoding is independent per contract; `collect` keeps CONTRACTS order,
+    // and failures are reported from this thread, the first one ending the deployment
+    let results: Vec<Result<Bytes, String>> = contracts
+        .par_iter()
+        .map(|(contract_name, _)| {
+            if artifact_format == ArtifactFormat::Foundry {
+                let hex_code =
+                    read_deployed_bytecode_from_artifact(byte_code_dir, contract_name, &contracts)
+                        .map_err(|e| format!("Failed to read {}: {}", contract_name, e))?;
+                let runtime_bytecode = hex::decode(&hex_code)
+                    .map_err(|e| format!("Invalid hex in artifact of {}: {}", contract_name, e))?;
+                return Ok(Bytes::from(runtime_bytecode));
+            }
+            // The `.hex` files hold the artifacts' `deployedBytecode`, i.e. runtime code
+            let hex_path = format!("{}/{}.hex", byte_code_dir, contract_name);
+            if let Ok(runtime_bytecode) = read_hex_bytes_from_file(&hex_path) {
+                return Ok(Bytes::from(runtime_bytecode));
+            }
+            // Unlinked library placeholders are not hex, so link on the text instead
+            let mut hex_code = std::fs::read_to_string(&hex_path)
+                .map_err(|e| format!("Failed to read {}: {}", hex_path, e))?;
+            if hex_code.contains("__$") {
+                hex_code = read_artifact(byte_code_dir, contract_name)
+                    .and_then(|artifact| {
+                        link_system_libraries(
+                            &artifact,
+                            "deployedBytecode",
+                            contract_name,
+                            &hex_code,
+                            &contracts,
+                        )
+                    })
+                    .map_err(|e| format!("Failed to link {}: {}", hex_path, e))?;
+            }
+            let runtime_bytecode = hex::decode(hex_code.trim())
+                .map_err(|e| format!("Invalid hex in {}: {}", hex_path, e))?;
+            Ok(Bytes::from(runtime_bytecode))
+        })
+        .collect();
+    let mut runtime_bytecodes = Vec::with_capacity(results.len());
+    for (index, ((contract_name, _), result)) in contracts.iter().zip(results).enumerate() {
+        match result {
+            Ok(runtime_bytecode) => runtime_bytecodes.push(runtime_bytecode),
+            Err(reason) => {
+                progress.report(
+                    index + 1,
+                    contract_name,
+                    DeployStatus::Failed(reason.clone()),
+                );
+                return Err(
+                    FailureReport::new(FailurePhase::Deploy, reason).with_contract(contract_name)
+                );
+            }
+        }
+    }
+    for (index, ((contract_name, target_address), runtime_bytecode)) in
+        contracts.into_iter().zip(runtime_bytecodes).enumerate()
+    {
+        insert_system_contract(&mut db, contract_name, target_address, runtime_bytecode);
+        progress.report(index + 1, contract_name, DeployStatus::Placed);
+    }
+
+    let skipped = contracts_declaring_constructor(byte_code_dir);
+    if !skipped.is_empty() {
+        warn!(
+            "   [!] Warning: {} declare a constructor that is not executed in inject mode; \
+             use --deploy-mode construct to run them",
+            skipped.join(", ")
+        );
+    }
 
-    for (contract_name, target_address) in CONTRACTS {
-        let hex_path = format!("{}/{}.hex", byte_code_dir, contract_name);
-        let bytecode_hex = read_hex_from_file(&hex_path);
+    Ok(db)
+}
 
-        // For BSC style, we need to extract runtime bytecode from constructor bytecode
-        // This is a simplified approach - in reality, we'd need to execute the constructor
-        // and extract the returned bytecode
-        let runtime_bytecode = extract_runtime_bytecode(&bytecode_hex);
+/// System contracts whose Foundry artifact ABI declares a constructor
+///
+/// Contracts without an artifact next to their `.hex` file are skipped, since
+/// there is nothing to inspect.
+pub fn contracts_declaring_constructor(byte_code_dir: &str) -> Vec<&'static str> {
+    CONTRACTS
+        .iter()
+        .filter(|(contract_name, _)| {
+            read_artifact(byte_code_dir, contract_name)
+                .ok()
+                .and_then(|artifact| artifact.get("abi").cloned())
+                .and_then(|abi| serde_json::from_value::<JsonAbi>(abi).ok())
+                .is_some_and(|abi| abi.constructor.is_some())
+        })
+        .map(|(contract_name, _)| *contract_name)
+        .collect()
+}
 
-        // Set large balance for JWK Manager and Validator Manager
-        let balance = if contract_name == "JwkManager" || contract_name == "ValidatorManager" || contract_name == "Genesis" {
-            // Set 1 million ETH balance (1e6 * 1e18 wei)
-            U256::from(1_000_000) * U256::from(10).pow(U256::from(18))
-        } else {
-            U256::ZERO
-        };
+/// Deploy by running every contract's constructor through the EVM
+///
+/// Each creation transaction runs from the system caller of `env`; the runtime code it returns
+/// is then placed at the contract's system address, as `config` lays them out. Storage written by a
+/// constructor lives at the CREATE address and is not carried over: the system
+/// contracts' constructors only call `_disableInitializers()`, which would make the
+/// subsequent `initialize()` calls from `Genesis` revert.
+///
+/// The system caller starts at the nonce of `config.system_account_info()`, which with
+/// plain CREATE fixes the address each constructor runs at.
+///
+/// With `create2_salts`, one salt per entry of `CONTRACTS`, each contract is instead
+/// created through `CREATE2_DEPLOYER` by `deploy_contract_create2`, at an address
+/// that no longer depends on how many contracts were created before it.
+///
+/// Returns the deployment DB together with the executed creation transactions, the
+/// gas each of them used and the storage the constructors wrote. With
+/// `continue_on_failure` every failed constructor is logged before the first one is
+/// returned, listing all of them.
+fn deploy_constructed(
+    byte_code_dir: &str,
+    env: &Env,
+    spec_id: SpecId,
+    config: &GenesisConfig,
+    create2_salts: Option<&[B256]>,
+    continue_on_failure: bool,
+    progress: DeployReporter,
+) -> Result<(InMemoryDB, Vec<TxEnv>, Vec<u64>, ConstructorStorage), FailureReport> {
+    let mut db = InMemoryDB::default();
+    let contracts = config.contract_addresses();
+    let mut constructor_storage = ConstructorStorage::new();
 
+    // Add system address with balance
+    db.insert_account_info(env.tx.caller, config.system_account_info());
+    if create2_salts.is_some() {
         db.insert_account_info(
-            target_address,
+            CREATE2_DEPLOYER,
             AccountInfo {
-                code: Some(Bytecode::new_raw(Bytes::from(runtime_bytecode))),
-                balance,
+                code: Some(Bytecode::new_raw(Bytes::from_static(CREATE2_DEPLOYER_CODE))),
                 ..AccountInfo::default()
             },
         );
+    }
 
-        if balance > U256::ZERO {
-            info!(
-                "Deployed {} runtime bytecode to {:?} with balance {} ETH",
-                contract_name, target_address, balance / U256::from(10).pow(U256::from(18))
+    // Artifacts are read and the creation transactions built in parallel, but
+    // `collect` keeps them in CONTRACTS order, which fixes every CREATE address and
+    // makes the first failing contract the one reported. Each is paired with its
+    // CREATE2 address, if created that way
+    let create_txs: Vec<Result<(TxEnv, Option<Address>), FailureReport>> = contracts
+        .par_iter()
+        .enumerate()
+        .map(|(index, (contract_name, _))| {
+            let failed =
+                |e| FailureReport::new(FailurePhase::Deploy, e).with_contract(contract_name);
+            let with_env = |tx| with_caller(with_gas_limit(tx, env.tx.gas_limit), env.tx.caller);
+            if let Some(salts) = create2_salts {
+                let (tx, created_address) =
+                    deploy_contract_create2(byte_code_dir, contract_name, salts[index], &contracts)
+                        .map_err(failed)?;
+                return Ok((with_env(tx), Some(created_address)));
+            }
+            let creation_bytecode =
+                read_bytecode_from_artifact(byte_code_dir, contract_name, &contracts)
+                    .map_err(failed)?;
+            let tx = new_system_create_txn(&creation_bytecode, Bytes::new());
+            Ok((with_env(tx), None))
+        })
+        .collect();
+    let (create_txs, create2_addresses): (Vec<TxEnv>, Vec<Option<Address>>) = create_txs
+        .into_iter()
+        .collect::<Result<Vec<_>, FailureReport>>()?
+        .into_iter()
+        .unzip();
+
+    let (results, bundle_state) =
+        execute_revm_sequential(&db, spec_id, env.clone(), &create_txs, None).map_err(|e| {
+            FailureReport::new(
+                FailurePhase::Deploy,
+                format!("{:?}", e.map_db_err(|_| "Database error".to_string())),
+            )
+        })?;
+
+    let with_constructor = contracts_declaring_constructor(byte_code_dir);
+    let mut failures = Vec::new();
+    for (index, (((contract_name, target_address), result), create2_address)) in contracts
+        .iter()
+        .zip(&results)
+        .zip(&create2_addresses)
+        .enumerate()
+    {
+        let deployed = match (result, create2_address) {
+            (
+                ExecutionResult::Success {
+                    output: Output::Create(runtime_bytecode, created_address),
+                    ..
+                },
+                None,
+            ) => Some((runtime_bytecode.clone(), *created_address)),
+            // The deployer returns the created address, whose code is in the bundle
+            (
+                ExecutionResult::Success {
+                    output: Output::Call(returned),
+                    ..
+                },
+                Some(created_address),
+            ) if returned.as_ref() == created_address.as_slice() => {
+                deployed_code(&bundle_state, created_address)
+                    .map(|code| (code, Some(*created_address)))
+            }
+            _ => None,
+        };
+        let (runtime_bytecode, created_address) = match deployed {
+            Some(deployed) => deployed,
+            None => {
+                error!(
+                    "Constructor of {} failed: {}",
+                    contract_name,
+                    analyze_txn_result(result)
+                );
+                let failure = FailureReport::from_result(FailurePhase::Deploy, index, result)
+                    .with_contract(contract_name);
+                progress.report(
+                    index + 1,
+                    contract_name,
+                    DeployStatus::Failed(failure.reason.clone()),
+                );
+                if !continue_on_failure {
+                    return Err(failure);
+                }
+                failures.push(failure);
+                continue;
+            }
+        };
+        if with_constructor.contains(contract_name) {
+            info!("Executed constructor of {}", contract_name);
+        }
+
+        let constructor_slots: BTreeMap<U256, U256> = created_address
+            .and_then(|address| bundle_state.state.get(&address))
+            .iter()
+            .flat_map(|account| account.storage.iter())
+            .map(|(slot, value)| (*slot, value.present_value()))
+            .collect();
+        if !constructor_slots.is_empty() {
+            debug!(
+                "{} constructor wrote {} storage slots at {:?}, not carried over to {}",
+                contract_name,
+                constructor_slots.len(),
+                created_address,
+                ChecksumAddress(*target_address)
             );
-        } else {
-            info!(
-                "Deployed {} runtime bytecode to {:?}",
-                contract_name, target_address
+            constructor_storage.insert(*target_address, constructor_slots);
+        }
+
+        insert_system_contract(&mut db, contract_name, *target_address, runtime_bytecode);
+        progress.report(index + 1, contract_name, DeployStatus::Constructed);
+    }
+    FailureReport::collect(failures)?;
+
+    let deploy_gas = results.iter().map(|result| result.gas_used()).collect();
+    Ok((db, create_txs, deploy_gas, constructor_storage))
+}
+
+/// Storage each system contract's constructor wrote at its CREATE address, keyed by
+/// the system address its runtime code is placed at
+type ConstructorStorage = BTreeMap<Address, BTreeMap<U256, U256>>;
+
+/// Runtime code the transactions behind `bundle_state` created at `address`
+fn deployed_code(bundle_state: &BundleState, address: &Address) -> Option<Bytes> {
+    let info = bundle_state.state.get(address)?.info.as_ref()?;
+    let code = info
+        .code
+        .clone()
+        .or_else(|| bundle_state.contracts.get(&info.code_hash).cloned())?;
+    Some(code.original_bytes())
+}
+
+/// Creation transaction of a system contract through `CREATE2_DEPLOYER` with `salt`,
+/// together with the address it creates the contract at, which depends only on the
+/// salt and the contract's creation bytecode, linked against `contracts`
+pub fn deploy_contract_create2(
+    byte_code_dir: &str,
+    contract_name: &str,
+    salt: B256,
+    contracts: &[(&str, Address)],
+) -> Result<(TxEnv, Address), String> {
+    let creation_bytecode = read_bytecode_from_artifact(byte_code_dir, contract_name, contracts)?;
+    let creation_bytecode = hex::decode(&creation_bytecode)
+        .map_err(|e| format!("Invalid creation bytecode of {}: {}", contract_name, e))?;
+    let created_address = CREATE2_DEPLOYER.create2_from_code(salt, &creation_bytecode);
+    let mut data = salt.to_vec();
+    data.extend_from_slice(&creation_bytecode);
+    Ok((
+        new_system_call_txn(CREATE2_DEPLOYER, data.into()),
+        created_address,
+    ))
+}
+
+/// Creation bytecode of an extra contract, from a Foundry artifact if the path ends
+/// in `.json` and from a hex file, decoded as it is read, otherwise
+fn read_extra_contract_bytecode(bytecode_path: &str) -> Result<Vec<u8>, String> {
+    if !bytecode_path.ends_with(".json") {
+        return read_hex_bytes_from_file(bytecode_path);
+    }
+    let content = std::fs::read_to_string(bytecode_path)
+        .map_err(|e| format!("Failed to read {}: {}", bytecode_path, e))?;
+    let artifact: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", bytecode_path, e))?;
+    let bytecode = artifact
+        .pointer("/bytecode/object")
+        .and_then(|object| object.as_str())
+        .ok_or_else(|| format!("No bytecode.object in {}", bytecode_path))?;
+    hex::decode(bytecode.trim().trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid hex in {}: {}", bytecode_path, e))
+}
+
+/// Deploy the configured extra contracts by running their constructors, then place
+/// each one's runtime code, constructor storage and balance at its configured address
+///
+/// Unlike the system contracts these are not initialized afterwards, so the storage
+/// their constructor wrote (an owner, say) is moved along with the code.
+/// `first_index` numbers the creation transactions in failure reports, and
+/// `continue_on_failure` behaves as for `deploy_constructed`. Progress steps follow
+/// those of the system contracts.
+fn deploy_extra_contracts(
+    db: &mut InMemoryDB,
+    env: &Env,
+    spec_id: SpecId,
+    extra_contracts: &[ExtraContractDeployment],
+    first_index: usize,
+    continue_on_failure: bool,
+    progress: DeployReporter,
+) -> Result<(Vec<TxEnv>, Vec<u64>), FailureReport> {
+    if extra_contracts.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let create_txs = extra_contracts
+        .iter()
+        .map(|contract| {
+            let creation_bytecode =
+                read_extra_contract_bytecode(&contract.bytecode_path).map_err(|e| {
+                    FailureReport::new(FailurePhase::Deploy, e).with_contract(&contract.name)
+                })?;
+            let tx = new_system_create_txn_from_bytes(
+                creation_bytecode,
+                contract.constructor_args.clone(),
             );
+            Ok(with_caller(
+                with_gas_limit(tx, env.tx.gas_limit),
+                env.tx.caller,
+            ))
+        })
+        .collect::<Result<Vec<TxEnv>, FailureReport>>()?;
+
+    let (results, bundle_state) =
+        execute_revm_sequential(&*db, spec_id, env.clone(), &create_txs, None).map_err(|e| {
+            FailureReport::new(
+                FailurePhase::Deploy,
+                format!("{:?}", e.map_db_err(|_| "Database error".to_string())),
+            )
+        })?;
+
+    let mut failures = Vec::new();
+    for (index, (contract, result)) in extra_contracts.iter().zip(&results).enumerate() {
+        let (runtime_bytecode, created_address) = match result {
+            ExecutionResult::Success {
+                output: Output::Create(runtime_bytecode, created_address),
+                ..
+            } => (runtime_bytecode.clone(), *created_address),
+            _ => {
+                error!(
+                    "Constructor of extra contract {} failed: {}",
+                    contract.name,
+                    analyze_txn_result(result)
+                );
+                let failure =
+                    FailureReport::from_result(FailurePhase::Deploy, first_index + index, result)
+                        .with_contract(&contract.name);
+                progress.report(
+                    CONTRACTS.len() + index + 1,
+                    &contract.name,
+                    DeployStatus::Failed(failure.reason.clone()),
+                );
+                if !continue_on_failure {
+                    return Err(failure);
+                }
+                failures.push(failure);
+                continue;
+            }
+        };
+        db.insert_account_info(
+            contract.address,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(runtime_bytecode)),
+                balance: contract.balance,
+                ..AccountInfo::default()
+            },
+        );
+        let constructor_storage = created_address
+            .and_then(|address| bundle_state.state.get(&address))
+            .map(|account| &account.storage);
+        for (slot, value) in constructor_storage.into_iter().flatten() {
+            db.insert_account_storage(contract.address, *slot, value.present_value())
+                .expect("InMemoryDB storage insert is infallible");
         }
+        info!(
+            "Deployed extra contract {} to {} with balance {} wei",
+            contract.name,
+            ChecksumAddress(contract.address),
+            contract.balance
+        );
+        progress.report(
+            CONTRACTS.len() + index + 1,
+            &contract.name,
+            DeployStatus::Constructed,
+        );
     }
+    FailureReport::collect(failures)?;
 
-    db
+    let deploy_gas = results.iter().map(|result| result.gas_used()).collect();
+    Ok((create_txs, deploy_gas))
 }
 
-// Extract runtime bytecode from constructor bytecode
-// This is a simplified implementation - in reality, we'd need to execute the constructor
-fn extract_runtime_bytecode(constructor_bytecode: &str) -> Vec<u8> {
-    // For now, we'll try to detect if this is constructor bytecode or runtime bytecode
-    let bytes = hex::decode(constructor_bytecode).unwrap_or_default();
+/// Options controlling how `genesis_generate` runs and writes its artifacts
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    /// Stream the final state straight to disk instead of assembling an owned
+    /// `genesis_state` map, and skip cloning the bundle state. Intended for
+    /// memory constrained CI runners.
+    pub low_memory: bool,
+    /// Custom system calls executed after the standard genesis initialization,
+    /// sharing its DB and bundle. Each must succeed like the init call does.
+    pub extra_transactions: Vec<TxEnv>,
+    /// Write the ordered genesis transactions to `genesis_txns.json` so clients
+    /// can rebuild the state by replay instead of loading the state dump
+    pub export_transactions: bool,
+    /// How contract code is placed at the system addresses
+    pub deploy_mode: DeployMode,
+    /// Where inject mode reads the runtime bytecode from
+    pub artifact_format: ArtifactFormat,
+    /// Attach to each genesis transaction the EIP-2930 access list of the accounts
+    /// and slots it touches, found by a dry run, so replay is charged the same gas
+    pub access_lists: bool,
+    /// Fail when initialization overwrites a deploy-time value in an EIP-1967 code
+    /// slot instead of only logging it
+    pub strict: bool,
+    /// Largest runtime code size accepted, `DEFAULT_MAX_CODE_SIZE` if unset. Also
+    /// applied to contract creation in the EVM
+    pub max_code_size: Option<usize>,
+    /// Only log contracts whose runtime code exceeds `max_code_size` instead of
+    /// failing generation
+    pub allow_oversized_code: bool,
+    /// Extra state format to write next to the native files
+    pub format: OutputFormat,
+    /// Keep checking the remaining transactions of a batch after one fails, then
+    /// report the first failure together with the indices of all failed ones
+    pub continue_on_failure: bool,
+    /// Also write `genesis.json`, combining the config, accounts, contracts and
+    /// metadata of the generation into one file to pass around
+    pub combined: bool,
+    /// Sleep this long on every account, storage and code read of the genesis
+    /// initialization, to benchmark it against slow storage. Zero disables it
+    pub sim_latency: Duration,
+    /// Called as each system and extra contract is deployed
+    pub progress: Option<ProgressCallback>,
+    /// Write `reverts.json`, what undoing each initialization transaction would
+    /// restore, to see which call overwrote the state of an earlier one
+    pub export_reverts: bool,
+}
 
-    // Simple heuristic: if the bytecode starts with typical constructor patterns,
-    // we need to extract the runtime part
-    if bytes.len() > 100 && (bytes[0] == 0x60 || bytes[0] == 0x61) {
-        // This looks like constructor bytecode
-        // For now, we'll use a simplified approach and return the original bytecode
-        // In a real implementation, we'd execute the constructor and extract the returned bytecode
-        warn!("   [!] Warning: Using constructor bytecode as runtime bytecode");
-        bytes
-    } else {
-        // This looks like runtime bytecode already
-        bytes
+impl GenerateOptions {
+    pub fn max_code_size(&self) -> usize {
+        self.max_code_size.unwrap_or(DEFAULT_MAX_CODE_SIZE)
+    }
+}
+
+/// A genesis transaction in replayable form, as written to `genesis_txns.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayTransaction {
+    pub index: usize,
+    pub caller: Address,
+    /// `None` for contract creation
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas_limit: u64,
+    pub gas_price: U256,
+    pub data: Bytes,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub access_list: Vec<AccessListItem>,
+    /// For creation transactions, including calls to `CREATE2_DEPLOYER`, the address
+    /// the created runtime code is placed at instead of the CREATE or CREATE2
+    /// address. Constructor storage of system contracts is dropped, that of
+    /// `extraContracts` moves along with the code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deploy_to: Option<Address>,
+}
+
+impl ReplayTransaction {
+    pub fn from_tx_env(index: usize, tx: &TxEnv) -> Self {
+        Self {
+            index,
+            caller: tx.caller,
+            to: tx.transact_to.to().copied(),
+            value: tx.value,
+            gas_limit: tx.gas_limit,
+            gas_price: tx.gas_price,
+            data: tx.data.clone(),
+            access_list: tx.access_list.to_vec(),
+            deploy_to: None,
+        }
+    }
+}
+
+/// A contract whose runtime code is placed directly into genesis state, and which
+/// therefore has no creation transaction to replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Predeploy {
+    pub name: String,
+    pub address: Address,
+    /// Runtime code to place at `address` before replaying the transactions
+    pub code: Bytes,
+}
+
+impl Predeploy {
+    /// `name` at `address`, with the runtime code the deployment DB holds there
+    fn from_db(db: &InMemoryDB, name: &str, address: Address) -> Self {
+        let code = db
+            .accounts
+            .get(&address)
+            .and_then(|account| account.info.code.as_ref())
+            .map_or_else(Bytes::new, |code| code.original_bytes());
+        Self {
+            name: name.to_string(),
+            address,
+            code,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisTransactions {
+    /// Chain id of the EVM env the transactions were executed under
+    pub chain_id: u64,
+    /// Runtime code size limit the genesis was generated under, when it is not the
+    /// EIP-170 one. Clients replaying it must accept code up to this size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_code_size: Option<usize>,
+    /// Nonce the system caller starts at, when it is not 1. Clients replaying the
+    /// creations must give the caller this nonce for them to land at the same address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployer_nonce: Option<u64>,
+    pub predeploys: Vec<Predeploy>,
+    pub transactions: Vec<ReplayTransaction>,
+}
+
+impl GenesisTransactions {
+    /// The predeployed contracts followed by the ordered transactions that, replayed
+    /// on top of them, reproduce the generated state
+    ///
+    /// `create_txs` pairs each creation transaction with the address its code is placed
+    /// at. System contracts of `contracts` without one, i.e. all of them with injected
+    /// deployment, are predeploys, carrying the runtime code `db` holds for them.
+    pub fn new(
+        chain_id: u64,
+        db: &InMemoryDB,
+        contracts: &[(&str, Address)],
+        create_txs: &[(TxEnv, Address)],
+        txs: &[TxEnv],
+    ) -> Self {
+        let predeploys = contracts
+            .iter()
+            .filter(|(_, address)| !create_txs.iter().any(|(_, target)| target == address))
+            .map(|(name, address)| Predeploy::from_db(db, name, *address))
+            .collect();
+        let creations = create_txs.iter().map(|(tx, address)| (tx, Some(*address)));
+        let calls = txs.iter().map(|tx| (tx, None));
+        Self {
+            chain_id,
+            max_code_size: None,
+            deployer_nonce: None,
+            predeploys,
+            transactions: creations
+                .chain(calls)
+                .enumerate()
+                .map(|(index, (tx, deploy_to))| ReplayTransaction {
+                    deploy_to,
+                    ..ReplayTransaction::from_tx_env(index, tx)
+                })
+                .collect(),
+        }
     }
 }
 
-pub fn prepare_env() -> Env {
+fn write_genesis_transactions(
+    files: &mut StagedFiles,
+    output_dir: &str,
+    genesis_txns: &GenesisTransactions,
+) -> Result<(), GenesisError> {
+    write_json(
+        files,
+        format!("{output_dir}/genesis_txns.json"),
+        genesis_txns,
+    )?;
+    info!(
+        "Exported {} genesis transactions to {}/genesis_txns.json",
+        genesis_txns.transactions.len(),
+        output_dir
+    );
+    Ok(())
+}
+
+/// Stream `value` as pretty JSON into a file staged for `path`
+fn write_json<T: Serialize + ?Sized>(
+    files: &mut StagedFiles,
+    path: String,
+    value: &T,
+) -> Result<(), GenesisError> {
+    let written = files.create(path.clone()).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, value)?;
+        writer.flush()
+    });
+    written.map_err(|source| GenesisError::FileWrite { path, source })
+}
+
+/// Chain id used when the config does not set `chainId`
+pub const DEFAULT_CHAIN_ID: u64 = NamedChain::Mainnet as u64;
+
+/// EIP-170 runtime code size limit
+pub const DEFAULT_MAX_CODE_SIZE: usize = 24_576;
+
+/// Gas limit of every genesis transaction when the config sets no
+/// `genesisGasLimit`, a mainnet sized block, so a transaction that would not fit in
+/// one is warned about or fails
+pub const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
+
+/// `genesisGasLimit` that lifts the limit altogether, for validator sets too large
+/// for `DEFAULT_GAS_LIMIT`. Nothing comes near it, so it is never warned about
+pub const UNBOUNDED_GAS_LIMIT: u64 = u64::MAX;
+
+/// Share of the gas limit, in percent, above which a genesis transaction is logged
+/// as approaching it
+pub const GAS_LIMIT_WARN_PERCENT: u64 = 80;
+
+pub fn prepare_env(chain_id: u64) -> Env {
     let mut env = Env::default();
-    env.cfg.chain_id = NamedChain::Mainnet.into();
-    env.tx.gas_limit = 30_000_000;
+    env.cfg.chain_id = chain_id;
+    env.tx.caller = SYSTEM_CALLER;
+    env.tx.gas_limit = DEFAULT_GAS_LIMIT;
     env
 }
 
+/// Set the block fields the genesis transactions may read from `config`: the block
+/// number, the `coinbase` and the `baseFee`. System transactions pay no gas, so the
+/// base fee is only what `block.basefee` returns and is not checked against their gas
+/// price
+pub fn prepare_block_env(env: &mut Env, config: &GenesisConfig) -> Result<(), GenesisConfigError> {
+    env.block.number = U256::from(config.parsed_block_number()?);
+    env.block.coinbase = config.parsed_coinbase()?;
+    env.block.basefee = U256::from(config.base_fee.unwrap_or_default());
+    env.cfg.disable_base_fee = true;
+    Ok(())
+}
+
+/// Report a config error `GenesisConfig::validate` should have caught as a failure
+/// of `phase`
+fn invalid_config(phase: FailurePhase) -> impl Fn(GenesisConfigError) -> FailureReport {
+    move |e| FailureReport::new(phase, format!("Invalid genesis config: {}", e))
+}
+
+/// Genesis transactions that used more than `GAS_LIMIT_WARN_PERCENT` of `gas_limit`
+fn transactions_near_gas_limit(tx_reports: &[TxReport], gas_limit: u64) -> Vec<&TxReport> {
+    let threshold = gas_limit / 100 * GAS_LIMIT_WARN_PERCENT;
+    tx_reports
+        .iter()
+        .filter(|report| report.gas_used > threshold)
+        .collect()
+}
+
 /// Transaction builder for genesis initialization
 struct GenesisTransactionBuilder {
     transactions: Vec<TxEnv>,
+    gas_limit: u64,
+    system_caller: Address,
+    contracts: Vec<(&'static str, Address)>,
 }
 
 impl GenesisTransactionBuilder {
-    fn new(config: &GenesisConfig) -> Self {
-        let transactions = vec![call_genesis_initialize(GENESIS_ADDR, config)];
-        Self { transactions }
+    fn new(config: &GenesisConfig) -> Result<Self, GenesisError> {
+        let transactions = vec![call_genesis_initialize(GENESIS_ADDR, config)?];
+        Ok(Self {
+            transactions,
+            gas_limit: config.gas_limit(),
+            system_caller: config.system_caller(),
+            contracts: config.contract_addresses(),
+        })
     }
 
-    fn with_jwks(mut self, jwks_file: Option<String>) -> Self {
+    fn with_jwks(mut self, jwks_file: Option<String>) -> Result<Self, GenesisError> {
         if let Some(jwks_file) = jwks_file {
-            let jwks_tx = upsert_observed_jwks(&jwks_file).expect("Failed to upsert observed JWKs");
+            let jwks_tx = upsert_observed_jwks(&jwks_file)?;
             self.transactions.push(jwks_tx);
             info!("Added JWKs transaction from file: {}", jwks_file);
         }
-        self
+        Ok(self)
     }
 
-    fn with_oidc_providers(mut self, oidc_providers_file: Option<String>) -> Self {
+    fn with_oidc_providers(
+        mut self,
+        oidc_providers_file: Option<String>,
+    ) -> Result<Self, GenesisError> {
         if let Some(oidc_providers_file) = oidc_providers_file {
-            let oidc_txs = upsert_oidc_providers(&oidc_providers_file)
-                .expect("Failed to upsert OIDC providers");
+            let oidc_txs = upsert_oidc_providers(&oidc_providers_file)?;
             let oidc_txs_count = oidc_txs.len();
             self.transactions.extend(oidc_txs);
             info!(
@@ -125,15 +893,40 @@ impl GenesisTransactionBuilder {
                 oidc_txs_count, oidc_providers_file
             );
         }
+        Ok(self)
+    }
+
+    fn with_extra_transactions(mut self, extra_transactions: &[TxEnv]) -> Self {
+        if !extra_transactions.is_empty() {
+            self.transactions.extend_from_slice(extra_transactions);
+            info!(
+                "Added {} custom post-init transactions",
+                extra_transactions.len()
+            );
+        }
         self
     }
 
+    /// The transactions, each capped at the genesis gas limit. Those built as system
+    /// calls are sent from the configured system caller to the configured address of
+    /// the system contract they call
     fn build(self) -> Vec<TxEnv> {
         info!(
             "Built {} total genesis transactions",
             self.transactions.len()
         );
         self.transactions
+            .into_iter()
+            .map(|tx| {
+                let gas_limit = tx.gas_limit.min(self.gas_limit);
+                let tx = with_gas_limit(tx, gas_limit);
+                if tx.caller == SYSTEM_CALLER {
+                    with_contract_addresses(with_caller(tx, self.system_caller), &self.contracts)
+                } else {
+                    tx
+                }
+            })
+            .collect()
     }
 }
 
@@ -142,138 +935,2340 @@ fn build_genesis_transactions(
     config: &GenesisConfig,
     jwks_file: Option<String>,
     oidc_providers_file: Option<String>,
-) -> Vec<TxEnv> {
-    GenesisTransactionBuilder::new(config)
-        .with_jwks(jwks_file)
-        .with_oidc_providers(oidc_providers_file)
-        .build()
+    extra_transactions: &[TxEnv],
+) -> Result<Vec<TxEnv>, GenesisError> {
+    Ok(GenesisTransactionBuilder::new(config)?
+        .with_jwks(jwks_file)?
+        .with_oidc_providers(oidc_providers_file)?
+        .with_extra_transactions(extra_transactions)
+        .build())
 }
 
-pub fn genesis_generate(
+/// Outcome of deploying and initializing, before anything is serialized
+struct GenesisExecution {
+    db: InMemoryDB,
+    bundle_state: BundleState,
+    /// Replayable transaction list, when `options.export_transactions` is set
+    genesis_txns: Option<GenesisTransactions>,
+    /// Chain id of the EVM env the transactions ran under
+    chain_id: u64,
+    contract_metrics: Vec<ContractMetrics>,
+    /// Outcome and gas of every creation and initialization transaction
+    tx_reports: Vec<TxReport>,
+    /// Logs of the successful initialization transactions with their index
+    logs: Vec<(usize, Log)>,
+    /// Per transaction reverts of the initialization, when `options.export_reverts`
+    /// is set
+    reverts: Option<Vec<TransactionReverts>>,
+}
+
+/// Check that `byte_code_dir` holds a non-empty file for every entry of `CONTRACTS`,
+/// libraries included: the `.hex` runtime bytecode in inject mode, the Foundry
+/// artifact in construct mode. Every missing file is listed in the one error
+pub fn preflight_check(
+    byte_code_dir: &str,
+    deploy_mode: DeployMode,
+    artifact_format: ArtifactFormat,
+) -> Result<(), GenesisError> {
+    let missing: Vec<String> = CONTRACTS
+        .iter()
+        .map(|(name, _)| match (deploy_mode, artifact_format) {
+            (DeployMode::Inject, ArtifactFormat::Hex) => format!("{}/{}.hex", byte_code_dir, name),
+            _ => artifact_path(byte_code_dir, name),
+        })
+        .filter(|path| std::fs::metadata(path).map_or(true, |metadata| metadata.len() == 0))
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(GenesisError::MissingBytecode {
+            dir: byte_code_dir.to_string(),
+            files: missing,
+        })
+    }
+}
+
+/// Deploy the system contracts and run `Genesis.initialize` followed by the optional
+/// JWK/OIDC upserts and any `options.extra_transactions`
+fn execute_genesis(
     byte_code_dir: &str,
-    output_dir: &str,
     config: &GenesisConfig,
     jwks_file: Option<String>,
     oidc_providers_file: Option<String>,
-) -> (InMemoryDB, BundleState) {
+    options: &GenerateOptions,
+) -> Result<GenesisExecution, FailureReport> {
     info!("=== Starting Genesis deployment and initialization ===");
+    preflight_check(byte_code_dir, options.deploy_mode, options.artifact_format)
+        .map_err(|e| FailureReport::new(FailurePhase::Deploy, e.to_string()))?;
 
-    let db = deploy_bsc_style(byte_code_dir);
+    let max_code_size = options.max_code_size();
+    let mut env = prepare_env(config.chain_id());
+    env.cfg.limit_contract_code_size = Some(max_code_size);
+    env.tx.gas_limit = config.gas_limit();
+    env.tx.caller = config.system_caller();
+    if env.tx.caller != SYSTEM_CALLER {
+        info!("System caller: {}", ChecksumAddress(env.tx.caller));
+    }
+    prepare_block_env(&mut env, config).map_err(invalid_config(FailurePhase::Deploy))?;
+    if env.block.number != U256::ZERO {
+        info!("Genesis block number: {}", env.block.number);
+    }
+    if config.coinbase.is_some() || config.base_fee.is_some() {
+        info!(
+            "Coinbase: {}, base fee: {} wei",
+            ChecksumAddress(env.block.coinbase),
+            env.block.basefee
+        );
+    }
+    // Only what `block.timestamp` reads during genesis. `Genesis.initialize` sets the
+    // Timestamp contract's own clock to 0 regardless, through `Block.initialize` and
+    // `Timestamp.initialize`, and the first block prologue advances it
+    let timestamp = config.block_timestamp();
+    env.block.timestamp = U256::from(timestamp);
+    if config.genesis_timestamp.is_some() {
+        info!("Genesis timestamp: {}", timestamp);
+    } else {
+        info!("Genesis timestamp: {} (current time)", timestamp);
+    }
+    let chain_id = env.cfg.chain_id;
+    let spec_id = config.spec_id();
 
-    let env = prepare_env();
+    let contracts = config.contract_addresses();
+    check_unique_addresses(&contracts).map_err(|e| FailureReport::new(FailurePhase::Deploy, e))?;
+    let create2_salts = config
+        .create2
+        .then(|| config.create2_salts())
+        .transpose()
+        .map_err(invalid_config(FailurePhase::Deploy))?;
+    let extra_contracts = config
+        .parsed_extra_contracts()
+        .map_err(invalid_config(FailurePhase::Deploy))?;
+    let progress = DeployReporter {
+        callback: options.progress.as_ref(),
+        total: CONTRACTS.len() + extra_contracts.len(),
+    };
+    let (mut db, system_create_txs, deploy_gas, constructor_storage) = match options.deploy_mode {
+        DeployMode::Inject => {
+            if create2_salts.is_some() {
+                warn!("create2 has no effect in inject mode, which runs no constructors");
+            }
+            if !config.expected_create_addresses.is_empty() {
+                warn!(
+                    "expectedCreateAddresses has no effect in inject mode, which runs no constructors"
+                );
+            }
+            (
+                deploy_bsc_style(
+                    byte_code_dir,
+                    env.tx.caller,
+                    config,
+                    options.artifact_format,
+                    progress,
+                )?,
+                Vec::new(),
+                Vec::new(),
+                ConstructorStorage::new(),
+            )
+        }
+        DeployMode::Construct => deploy_constructed(
+            byte_code_dir,
+            &env,
+            spec_id,
+            config,
+            create2_salts.as_deref(),
+            options.continue_on_failure,
+            progress,
+        )?,
+    };
+    // Kept in the genesis state so replaying `genesis_txns.json` can call it
+    let create2_deployer = db
+        .accounts
+        .contains_key(&CREATE2_DEPLOYER)
+        .then_some(CREATE2_DEPLOYER);
+    let (extra_create_txs, extra_deploy_gas) = deploy_extra_contracts(
+        &mut db,
+        &env,
+        spec_id,
+        &extra_contracts,
+        system_create_txs.len(),
+        options.continue_on_failure,
+        progress,
+    )?;
+    let deployed = contracts
+        .iter()
+        .map(|(name, address)| (*name, *address))
+        .chain(
+            extra_contracts
+                .iter()
+                .map(|contract| (contract.name.as_str(), contract.address)),
+        );
+    check_address_map(&db, deployed.clone(), create2_deployer)?;
+    check_code_sizes(&db, deployed, max_code_size, options.allow_oversized_code)?;
+    if options.deploy_mode == DeployMode::Inject {
+        check_runtime_code(
+            &db,
+            byte_code_dir,
+            &contracts,
+            &env,
+            spec_id,
+            options.strict,
+        )?;
+    }
+    // Creation transactions in replay order, each with the address its code lands at
+    let create_txs: Vec<(TxEnv, Address)> = system_create_txs
+        .into_iter()
+        .zip(contracts.iter().map(|(_, address)| *address))
+        .chain(
+            extra_create_txs
+                .into_iter()
+                .zip(extra_contracts.iter().map(|contract| contract.address)),
+        )
+        .collect();
+    let premine = config
+        .genesis_balances()
+        .map_err(invalid_config(FailurePhase::Init))?;
+    apply_premine(&mut db, &premine);
+    seed_block_hashes(
+        &mut db,
+        &config
+            .parsed_block_hashes()
+            .map_err(invalid_config(FailurePhase::Init))?,
+        &env,
+    );
 
-    let txs = build_genesis_transactions(config, jwks_file, oidc_providers_file);
+    let mut txs = build_genesis_transactions(
+        config,
+        jwks_file,
+        oidc_providers_file,
+        &options.extra_transactions,
+    )
+    .map_err(|e| FailureReport::new(FailurePhase::Init, e.to_string()))?;
+    if options.access_lists {
+        let access_lists = derive_access_lists(&db, spec_id, env.clone(), &txs).map_err(|e| {
+            FailureReport::new(
+                FailurePhase::Init,
+                format!("{:?}", e.map_db_err(|_| "Database error".to_string())),
+            )
+        })?;
+        for (tx, access_list) in txs.iter_mut().zip(access_lists) {
+            tx.access_list = access_list;
+        }
+        info!(
+            "Attached access lists to {} genesis transactions",
+            txs.len()
+        );
+    }
+    let genesis_txns = options.export_transactions.then(|| {
+        let mut genesis_txns = GenesisTransactions {
+            max_code_size: (max_code_size != DEFAULT_MAX_CODE_SIZE).then_some(max_code_size),
+            deployer_nonce: config
+                .deployer_nonce
+                .filter(|nonce| *nonce != SYSTEM_ACCOUNT_INFO.nonce),
+            ..GenesisTransactions::new(chain_id, &db, &contracts, &create_txs, &txs)
+        };
+        if let Some(address) = create2_deployer {
+            genesis_txns
+                .predeploys
+                .push(Predeploy::from_db(&db, "Create2Deployer", address));
+        }
+        genesis_txns
+    });
 
-    let r = execute_revm_sequential(db.clone(), SpecId::LATEST, env.clone(), &txs, None);
-    let (result, mut bundle_state) = match r {
-        Ok((result, bundle_state)) => {
+    let r = execute_revm_sequential_timed(
+        LatencyDB::new(&db, options.sim_latency),
+        spec_id,
+        env,
+        &txs,
+        None,
+    );
+    // Number the init transactions after the creations, as in `genesis_txns.json`
+    let first_index = create_txs.len();
+    let tx_targets: Vec<Option<Address>> =
+        txs.iter().map(|tx| tx.transact_to.to().copied()).collect();
+    drop(txs);
+    drop(create_txs);
+    let (result, timings, mut bundle_state) = match r {
+        Ok((result, timings, bundle_state)) => {
             info!("=== Genesis initialization successful ===");
-            (result, bundle_state)
+            (result, timings, bundle_state)
         }
         Err(e) => {
-            panic!(
-                "Error: {}",
-                format!("{:?}", e.map_db_err(|_| "Database error".to_string()))
-            );
+            return Err(FailureReport::new(
+                FailurePhase::Init,
+                format!("{:?}", e.map_db_err(|_| "Database error".to_string())),
+            ));
         }
     };
     debug!("the bundle state is {:?}", bundle_state);
-    let ret = (db, bundle_state.clone());
+    let reverts = options
+        .export_reverts
+        .then(|| collect_reverts(first_index, &bundle_state, config.system_caller()));
 
+    let mut failures = Vec::new();
     for (i, r) in result.iter().enumerate() {
         if !r.is_success() {
             error!("=== Transaction {} failed ===", i + 1);
-            println!("Detailed analysis: {}", analyze_txn_result(r));
-            panic!("Genesis transaction {} failed", i + 1);
+            error!("Detailed analysis: {}", analyze_txn_result(r));
+            let failure = FailureReport::from_result(FailurePhase::Init, first_index + i, r);
+            if !options.continue_on_failure {
+                return Err(failure);
+            }
+            failures.push(failure);
         } else {
             info!("Detailed analysis: {}", analyze_txn_result(r));
         }
     }
+    FailureReport::collect(failures)?;
     info!(
         "=== All {} transactions completed successfully ===",
         result.len()
     );
 
-    // Add deployed contracts to the final state
-    let mut genesis_state = HashMap::new();
-
-    for (contract_name, contract_address) in CONTRACTS {
-        let hex_path = format!("{}/{}.hex", byte_code_dir, contract_name);
-        let bytecode_hex = read_hex_from_file(&hex_path);
-        let runtime_bytecode = extract_runtime_bytecode(&bytecode_hex);
-
-        genesis_state.insert(
-            contract_address,
-            PlainAccount {
-                info: AccountInfo {
-                    code: Some(Bytecode::new_raw(Bytes::from(runtime_bytecode))),
-                    ..AccountInfo::default()
-                },
-                storage: Default::default(),
-            },
-        );
+    check_storage_overwrites(&db, &constructor_storage, &bundle_state, options.strict)?;
 
-        info!(
-            "Added {} to genesis state at {:?}",
-            contract_name, contract_address
-        );
+    // Premined and burn accounts the transactions never touched are not in the bundle
+    // yet, and neither is the CREATE2 deployer
+    for address in premine
+        .iter()
+        .map(|(address, _)| address)
+        .chain(&create2_deployer)
+    {
+        bundle_account_mut(&mut bundle_state, &db, *address);
+    }
+    // Nor are extra contracts, whose constructor storage only lives in the DB
+    for contract in &extra_contracts {
+        let constructor_storage = db
+            .accounts
+            .get(&contract.address)
+            .map(|account| account.storage.clone())
+            .unwrap_or_default();
+        let account = bundle_account_mut(&mut bundle_state, &db, contract.address);
+        for (slot, value) in constructor_storage {
+            account.storage.entry(slot).or_default().present_value = value;
+        }
     }
 
-    // Add any state changes from the bundle_state (from the initialize transaction)
-    bundle_state.state.remove(&SYSTEM_CALLER);
-    // write bundle state into one json file named bundle_state.json
-    serde_json::to_writer_pretty(
-        BufWriter::new(File::create(format!("{output_dir}/bundle_state.json")).unwrap()),
-        &bundle_state,
-    )
-    .unwrap();
-
+    // Burned wei is part of the minted supply, it just sits where nobody can spend it
+    let total_supply = bundle_state
+        .state
+        .values()
+        .filter_map(|account| account.info.as_ref())
+        .fold(U256::ZERO, |total, info| total.saturating_add(info.balance));
+    let burned = premine
+        .iter()
+        .find(|(address, _)| *address == DEAD_ADDRESS)
+        .map_or(U256::ZERO, |(_, balance)| *balance);
     info!(
-        "bundle state size is {:?}, contracts size {:?}",
-        bundle_state.state.len(),
-        CONTRACTS.len()
+        "Genesis supply: {} wei, of which {} wei burned at {}",
+        total_supply,
+        burned,
+        ChecksumAddress(DEAD_ADDRESS)
     );
-    for (address, account) in bundle_state.state.into_iter() {
-        debug!("Address: {:?}, account: {:?}", address, account);
-        if let Some(info) = account.info {
-            let storage = account
-                .storage
-                .into_iter()
-                .map(|(k, v)| (k, v.present_value()))
-                .collect();
 
-            // If this address already exists in genesis_state, merge the storage
-            if let Some(existing) = genesis_state.get_mut(&address) {
-                existing.storage.extend(storage);
-                existing.info = info;
-            } else {
-                genesis_state.insert(address, PlainAccount { info, storage });
-            }
+    let storage_overrides = config
+        .parsed_storage_overrides()
+        .map_err(invalid_config(FailurePhase::Init))?;
+    for (address, slot, value) in storage_overrides {
+        if let Some((name, _)) = contracts.iter().find(|(_, contract)| *contract == address) {
+            warn!(
+                "   [!] Warning: overriding slot {:#x} of system contract {} with {:#x}",
+                slot, name, value
+            );
         }
+        bundle_account_mut(&mut bundle_state, &db, address)
+            .storage
+            .entry(slot)
+            .or_default()
+            .present_value = value;
     }
 
-    serde_json::to_writer_pretty(
-        BufWriter::new(File::create(format!("{output_dir}/genesis_accounts.json")).unwrap()),
-        &genesis_state,
-    )
-    .unwrap();
+    let extra_metrics = extra_contracts
+        .iter()
+        .zip(&extra_deploy_gas)
+        .map(|(contract, gas)| (contract.name.as_str(), contract.address, Some(*gas)));
+    let contract_metrics = contracts
+        .iter()
+        .enumerate()
+        .map(|(index, (name, address))| (*name, *address, deploy_gas.get(index).copied()))
+        .chain(extra_metrics)
+        .map(|(name, address, deploy_gas)| ContractMetrics {
+            name: name.to_string(),
+            address,
+            deploy_gas,
+            init_gas: tx_targets
+                .iter()
+                .zip(&result)
+                .filter(|(target, _)| **target == Some(address))
+                .map(|(_, r)| r.gas_used())
+                .sum(),
+            runtime_code_size: db
+                .accounts
+                .get(&address)
+                .and_then(|account| account.info.code.as_ref())
+                .map_or(0, |code| code.original_bytes().len()),
+            storage_slots: bundle_state
+                .state
+                .get(&address)
+                .map_or(0, |account| account.storage.len()),
+        })
+        .collect();
 
-    // Create contracts JSON with bytecode
-    let contracts_json: HashMap<_, _> = genesis_state
+    // Creations that failed have aborted deployment already
+    let tx_reports: Vec<TxReport> = deploy_gas
         .iter()
-        .filter_map(|(addr, account)| {
-            account
-                .info
-                .code
-                .as_ref()
-                .map(|code| (*addr, code.bytecode()))
+        .chain(&extra_deploy_gas)
+        .enumerate()
+        .map(|(index, gas_used)| TxReport {
+            index,
+            success: true,
+            gas_used: *gas_used,
+            revert_selector: None,
+            revert_reason: None,
+            elapsed: None,
         })
+        .chain(
+            result
+                .iter()
+                .zip(&timings)
+                .enumerate()
+                .map(|(i, (r, elapsed))| TxReport {
+                    elapsed: Some(*elapsed),
+                    ..TxReport::from_result(first_index + i, r)
+                }),
+        )
         .collect();
+    for report in transactions_near_gas_limit(&tx_reports, config.gas_limit()) {
+        warn!(
+            "Genesis transaction {} used {} gas, over {}% of the gas limit of {}",
+            report.index,
+            report.gas_used,
+            GAS_LIMIT_WARN_PERCENT,
+            config.gas_limit()
+        );
+    }
 
-    serde_json::to_writer_pretty(
-        BufWriter::new(File::create(format!("{output_dir}/genesis_contracts.json")).unwrap()),
-        &contracts_json,
-    )
-    .unwrap();
-    ret
+    Ok(GenesisExecution {
+        db,
+        bundle_state,
+        genesis_txns,
+        chain_id,
+        contract_metrics,
+        tx_reports,
+        logs: collect_logs(first_index, &result),
+        reverts,
+    })
+}
+
+/// Chain id a serialized JSON artifact records at the JSON `pointer`, such as
+/// `/metadata/chainId`
+fn json_chain_id(json: &str, pointer: &str) -> Option<u64> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value.pointer(pointer)?.as_u64()
+}
+
+/// Fail if the chain id any output artifact recorded differs from the configured one
+fn check_chain_id(expected: u64, recorded: &[(&str, Option<u64>)]) -> Result<(), FailureReport> {
+    for (source, chain_id) in recorded {
+        if let Some(chain_id) = chain_id.filter(|chain_id| *chain_id != expected) {
+            return Err(FailureReport::new(
+                FailurePhase::Verify,
+                format!(
+                    "{} records chain id {}, but the config uses {}",
+                    source, chain_id, expected
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The bundle entry of `address`, added from the deployment DB if no transaction
+/// touched the account
+fn bundle_account_mut<'a>(
+    bundle_state: &'a mut BundleState,
+    db: &InMemoryDB,
+    address: Address,
+) -> &'a mut BundleAccount {
+    let account = bundle_state.state.entry(address).or_insert_with(|| {
+        BundleAccount::new(
+            None,
+            None,
+            Default::default(),
+            AccountStatus::InMemoryChange,
+        )
+    });
+    if account.info.is_none() {
+        account.info = Some(
+            db.accounts
+                .get(&address)
+                .map(|account| account.info.clone())
+                .unwrap_or_default(),
+        );
+    }
+    account
+}
+
+/// Set the starting balance of every premined account, keeping any code already
+/// placed at the address
+fn apply_premine(db: &mut InMemoryDB, premine: &[(Address, U256)]) {
+    for (address, balance) in premine {
+        let mut info = db
+            .accounts
+            .get(address)
+            .map(|account| account.info.clone())
+            .unwrap_or_default();
+        info.balance = *balance;
+        db.insert_account_info(*address, info);
+        info!("Premined {} wei to {}", balance, ChecksumAddress(*address));
+    }
+}
+
+/// EIP-1967 proxy slots, whose value decides which code an account runs
+const CODE_SLOTS: [(&str, U256); 3] = [
+    (
+        "implementation",
+        uint!(0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc_U256),
+    ),
+    (
+        "admin",
+        uint!(0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103_U256),
+    ),
+    (
+        "beacon",
+        uint!(0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50_U256),
+    ),
+];
+
+/// Report every slot written at deploy time that initialization overwrote with a
+/// different value
+///
+/// A system contract's deploy time storage is what its constructor wrote at the
+/// CREATE address, `constructor_storage`; an extra contract's is its storage in the
+/// deployment DB. The init value is what ends up in genesis; disagreements are
+/// logged at debug so surprising overwrites are visible. With `strict`, overwriting
+/// a code slot fails.
+fn check_storage_overwrites(
+    db: &InMemoryDB,
+    constructor_storage: &ConstructorStorage,
+    bundle_state: &BundleState,
+    strict: bool,
+) -> Result<(), FailureReport> {
+    for (address, account) in &bundle_state.state {
+        let deployed_value = |slot: &U256| {
+            constructor_storage
+                .get(address)
+                .and_then(|storage| storage.get(slot))
+                .or_else(|| db.accounts.get(address)?.storage.get(slot))
+        };
+        for (slot, value) in &account.storage {
+            let Some(deployed_value) = deployed_value(slot) else {
+                continue;
+            };
+            let init_value = value.present_value();
+            if *deployed_value == init_value {
+                continue;
+            }
+            debug!(
+                "Slot {:#x} of {} written as {:#x} at deploy, overwritten with {:#x} by init",
+                slot,
+                ChecksumAddress(*address),
+                deployed_value,
+                init_value
+            );
+            let code_slot = CODE_SLOTS.iter().find(|(_, code_slot)| code_slot == slot);
+            if let Some((name, _)) = code_slot.filter(|_| strict) {
+                return Err(FailureReport::new(
+                    FailurePhase::Init,
+                    format!(
+                        "initialization overwrote the EIP-1967 {} slot of {}: {:#x} -> {:#x}",
+                        name,
+                        ChecksumAddress(*address),
+                        deployed_value,
+                        init_value
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Make `BLOCKHASH` return the configured hashes instead of the `EmptyDB` fallback
+///
+/// The EVM only consults the DB for the 256 blocks below the env's block number, so
+/// hashes outside that window are stored but never read.
+fn seed_block_hashes(db: &mut InMemoryDB, block_hashes: &[(u64, B256)], env: &Env) {
+    let current = env.block.number;
+    for (number, hash) in block_hashes {
+        let number = U256::from(*number);
+        if number >= current || current - number > U256::from(256) {
+            warn!(
+                "   [!] Warning: block hash of block {} is outside the BLOCKHASH window of block {}",
+                number, current
+            );
+        }
+        db.block_hashes.insert(number, *hash);
+    }
+    if !block_hashes.is_empty() {
+        info!("Seeded {} genesis block hashes", block_hashes.len());
+    }
+}
+
+/// Reconcile the deployment DB with the address map: every system and extra
+/// contract must have code at its address, and no other account may have code
+/// except the CREATE2 deployer, so nothing is left at a CREATE address
+fn check_address_map<'a>(
+    db: &InMemoryDB,
+    contracts: impl Iterator<Item = (&'a str, Address)>,
+    create2_deployer: Option<Address>,
+) -> Result<(), FailureReport> {
+    let mut expected: Vec<Address> = create2_deployer.into_iter().collect();
+    for (name, address) in contracts {
+        let has_code = db
+            .accounts
+            .get(&address)
+            .and_then(|account| account.info.code.as_ref())
+            .is_some_and(|code| !code.is_empty());
+        if !has_code {
+            return Err(FailureReport::new(
+                FailurePhase::Deploy,
+                format!(
+                    "no code at the address {} of {}",
+                    ChecksumAddress(address),
+                    name
+                ),
+            )
+            .with_contract(name));
+        }
+        expected.push(address);
+    }
+    let mut unexpected: Vec<&Address> = db
+        .accounts
+        .iter()
+        .filter(|(address, account)| {
+            account
+                .info
+                .code
+                .as_ref()
+                .is_some_and(|code| !code.is_empty())
+                && !expected.contains(address)
+        })
+        .map(|(address, _)| address)
+        .collect();
+    unexpected.sort();
+    if let Some(address) = unexpected.first() {
+        return Err(FailureReport::new(
+            FailurePhase::Deploy,
+            format!(
+                "code at {} is neither a system contract nor an extra contract ({} unexpected)",
+                ChecksumAddress(**address),
+                unexpected.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Fail on the first contract whose runtime code exceeds `max_code_size`, or with
+/// `warn_only` log every one of them
+fn check_code_sizes<'a>(
+    db: &InMemoryDB,
+    contracts: impl Iterator<Item = (&'a str, Address)>,
+    max_code_size: usize,
+    warn_only: bool,
+) -> Result<(), FailureReport> {
+    for (name, address) in contracts {
+        let code_size = db
+            .accounts
+            .get(&address)
+            .and_then(|account| account.info.code.as_ref())
+            .map_or(0, |code| code.original_bytes().len());
+        if code_size <= max_code_size {
+            continue;
+        }
+        let message = format!(
+            "runtime code of {} at {} is {} bytes, over the {} byte limit",
+            name,
+            ChecksumAddress(address),
+            code_size,
+            max_code_size
+        );
+        if !warn_only {
+            return Err(FailureReport::new(FailurePhase::Deploy, message).with_contract(name));
+        }
+        warn!("   [!] Warning: {}", message);
+    }
+    Ok(())
+}
+
+/// Compare the injected runtime code of every system contract with the code its
+/// constructor returns, failing on the first mismatch when `strict`
+///
+/// Contracts without a Foundry artifact are skipped. Immutables set from
+/// `address(this)` legitimately differ, since the constructor runs at the CREATE
+/// address rather than at the system address.
+fn check_runtime_code(
+    db: &InMemoryDB,
+    byte_code_dir: &str,
+    system_contracts: &[(&str, Address)],
+    env: &Env,
+    spec_id: SpecId,
+    strict: bool,
+) -> Result<(), FailureReport> {
+    let contracts: Vec<(&str, Address, String)> = system_contracts
+        .iter()
+        .filter_map(|(name, address)| {
+            let creation_bytecode =
+                read_bytecode_from_artifact(byte_code_dir, name, system_contracts).ok()?;
+            Some((*name, *address, creation_bytecode))
+        })
+        .collect();
+    if contracts.is_empty() {
+        return Ok(());
+    }
+
+    let mut constructor_db = InMemoryDB::default();
+    constructor_db.insert_account_info(env.tx.caller, SYSTEM_ACCOUNT_INFO);
+    let create_txs: Vec<TxEnv> = contracts
+        .iter()
+        .map(|(_, _, creation_bytecode)| {
+            let tx = with_gas_limit(
+                new_system_create_txn(creation_bytecode, Bytes::new()),
+                env.tx.gas_limit,
+            );
+            with_caller(tx, env.tx.caller)
+        })
+        .collect();
+    let (results, _) =
+        execute_revm_sequential(&constructor_db, spec_id, env.clone(), &create_txs, None).map_err(
+            |e| {
+                FailureReport::new(
+                    FailurePhase::Deploy,
+                    format!("{:?}", e.map_db_err(|_| "Database error".to_string())),
+                )
+            },
+        )?;
+
+    for ((name, address, _), result) in contracts.iter().zip(&results) {
+        let ExecutionResult::Success {
+            output: Output::Create(constructed, _),
+            ..
+        } = result
+        else {
+            warn!(
+                "   [!] Warning: constructor of {} failed, its runtime code is not checked: {}",
+                name,
+                analyze_txn_result(result)
+            );
+            continue;
+        };
+        let injected = db
+            .accounts
+            .get(address)
+            .and_then(|account| account.info.code.as_ref())
+            .map(|code| code.original_bytes())
+            .unwrap_or_default();
+        if injected == *constructed {
+            debug!("Runtime code of {} matches its constructor output", name);
+            continue;
+        }
+        let message = format!(
+            "runtime code of {} at {} has hash {:?}, but its constructor returns code with hash {:?}",
+            name,
+            ChecksumAddress(*address),
+            keccak256(&injected),
+            keccak256(constructed)
+        );
+        if strict {
+            return Err(FailureReport::new(FailurePhase::Deploy, message).with_contract(name));
+        }
+        warn!("   [!] Warning: {}", message);
+    }
+    Ok(())
+}
+
+/// Merge the deployed contract code with the post-initialization bundle, keyed in
+/// address order so the written state does not depend on hash map iteration
+fn build_genesis_state(
+    db: &InMemoryDB,
+    bundle_state: BundleState,
+    contracts: &[(&str, Address)],
+) -> BTreeMap<Address, PlainAccount> {
+    // Add deployed contracts to the final state
+    let mut genesis_state = BTreeMap::new();
+
+    for &(contract_name, contract_address) in contracts {
+        // The deployment DB holds the runtime code placed by whichever deploy mode ran
+        let code = db
+            .accounts
+            .get(&contract_address)
+            .and_then(|account| account.info.code.clone());
+
+        genesis_state.insert(
+            contract_address,
+            PlainAccount {
+                info: AccountInfo {
+                    code,
+                    ..AccountInfo::default()
+                },
+                storage: Default::default(),
+            },
+        );
+
+        info!(
+            "Added {} to genesis state at {}",
+            contract_name,
+            ChecksumAddress(contract_address)
+        );
+    }
+
+    info!(
+        "bundle state size is {:?}, contracts size {:?}",
+        bundle_state.state.len(),
+        contracts.len()
+    );
+    for (address, account) in bundle_state.state.into_iter() {
+        debug!(
+            "Address: {}, account: {:?}",
+            ChecksumAddress(address),
+            account
+        );
+        if let Some(info) = account.info {
+            let storage = account
+                .storage
+                .into_iter()
+                .map(|(k, v)| (k, v.present_value()))
+                .collect();
+
+            // If this address already exists in genesis_state, merge the storage
+            if let Some(existing) = genesis_state.get_mut(&address) {
+                existing.storage.extend(storage);
+                existing.info = info;
+            } else {
+                genesis_state.insert(address, PlainAccount { info, storage });
+            }
+        }
+    }
+
+    let accounts: Vec<(Address, &AccountInfo)> = genesis_state
+        .iter()
+        .map(|(address, account)| (*address, &account.info))
+        .collect();
+    check_referenced_code(&accounts, contracts);
+    genesis_state
+}
+
+/// Warn about every system address the runtime code in the genesis state pushes but
+/// that has no code in it, such as a library left out of `CONTRACTS`. Calls to it
+/// would only revert once the chain runs. Returns each referencing contract with
+/// the address it references; `contracts` are the system contracts as the config
+/// places them, looked for in the code and named in the warnings
+fn check_referenced_code(
+    accounts: &[(Address, &AccountInfo)],
+    contracts: &[(&str, Address)],
+) -> Vec<(Address, Address)> {
+    let has_code = |info: &AccountInfo| info.code.as_ref().is_some_and(|code| !code.is_empty());
+    let deployed: HashSet<Address> = accounts
+        .iter()
+        .filter(|(_, info)| has_code(info))
+        .map(|(address, _)| *address)
+        .collect();
+    let name = |address: &Address| {
+        contracts
+            .iter()
+            .chain(&RESERVED_ADDRESSES)
+            .find(|(_, known)| known == address)
+            .map_or(String::new(), |(name, _)| format!("{} ", name))
+    };
+
+    let mut missing = Vec::new();
+    for (address, info) in accounts {
+        let Some(code) = info.code.as_ref() else {
+            continue;
+        };
+        for referenced in referenced_system_addresses(code.original_byte_slice(), contracts) {
+            if !deployed.contains(&referenced) {
+                warn!(
+                    "{}at {} references {}at {}, which has no code in the genesis state",
+                    name(address),
+                    ChecksumAddress(*address),
+                    name(&referenced),
+                    ChecksumAddress(referenced)
+                );
+                missing.push((*address, referenced));
+            }
+        }
+    }
+    missing
+}
+
+/// The generated genesis files, as pretty printed JSON except for the gas report
+#[derive(Debug, Clone)]
+pub struct GenesisArtifacts {
+    /// `bundle_state.json`
+    pub bundle_state: String,
+    /// `genesis_accounts.json`
+    pub accounts: String,
+    /// `genesis_contracts.json`
+    pub contracts: String,
+    /// `genesis_txns.json`, only present when `export_transactions` is set
+    pub transactions: Option<String>,
+    /// `contract_metrics.csv`
+    pub contract_metrics: String,
+    /// `genesis_alloc.json`, only present with `OutputFormat::Geth`
+    pub geth_alloc: Option<String>,
+    /// `genesis.json`, only present when `combined` is set
+    pub combined: Option<String>,
+    /// `gas_report.json`, kept structured for callers tracking gas between builds
+    pub tx_reports: Vec<TxReport>,
+    /// `genesis_events.json`
+    pub events: String,
+    /// `reverts.json`, only present when `export_reverts` is set
+    pub reverts: Option<String>,
+    /// Raw logs of the successful initialization transactions, each with the index
+    /// of its transaction as in `genesis_txns.json`
+    pub logs: Vec<(usize, Log)>,
+}
+
+impl GenesisArtifacts {
+    /// Write every artifact into `output_dir` under its usual file name. Each file is
+    /// replaced atomically, so none is ever seen half-written, but the renames happen
+    /// one by one and a failure among them can leave old and new files side by side
+    pub fn write_to(&self, output_dir: &str) -> std::io::Result<()> {
+        let mut files = StagedFiles::default();
+        files.write(
+            format!("{output_dir}/bundle_state.json"),
+            &self.bundle_state,
+        )?;
+        files.write(
+            format!("{output_dir}/genesis_accounts.json"),
+            &self.accounts,
+        )?;
+        files.write(
+            format!("{output_dir}/genesis_contracts.json"),
+            &self.contracts,
+        )?;
+        if let Some(transactions) = &self.transactions {
+            files.write(format!("{output_dir}/genesis_txns.json"), transactions)?;
+        }
+        files.write(
+            format!("{output_dir}/{CONTRACT_METRICS_FILE}"),
+            &self.contract_metrics,
+        )?;
+        if let Some(geth_alloc) = &self.geth_alloc {
+            files.write(format!("{output_dir}/{GETH_ALLOC_FILE}"), geth_alloc)?;
+        }
+        if let Some(combined) = &self.combined {
+            files.write(format!("{output_dir}/{COMBINED_GENESIS_FILE}"), combined)?;
+        }
+        files.write(format!("{output_dir}/{EVENTS_FILE}"), &self.events)?;
+        if let Some(reverts) = &self.reverts {
+            files.write(format!("{output_dir}/{REVERTS_FILE}"), reverts)?;
+        }
+        write_gas_report(&mut files, output_dir, &self.tx_reports)?;
+        files.commit()
+    }
+}
+
+/// Stage `gas_report.json` in `files`
+pub fn write_gas_report(
+    files: &mut StagedFiles,
+    output_dir: &str,
+    tx_reports: &[TxReport],
+) -> std::io::Result<()> {
+    files.write(
+        format!("{output_dir}/{GAS_REPORT_FILE}"),
+        serde_json::to_string_pretty(tx_reports)?,
+    )
+}
+
+/// Run the full generation and return the final genesis state, without serializing
+/// or writing anything, for callers that embed the generator
+///
+/// The map holds the same accounts `genesis_accounts.json` would.
+pub fn compute_genesis_state(
+    byte_code_dir: &str,
+    config: &GenesisConfig,
+    jwks_file: Option<String>,
+    oidc_providers_file: Option<String>,
+    options: &GenerateOptions,
+) -> Result<BTreeMap<Address, PlainAccount>, GenesisError> {
+    let GenesisExecution {
+        db,
+        mut bundle_state,
+        ..
+    } = execute_genesis(
+        byte_code_dir,
+        config,
+        jwks_file,
+        oidc_providers_file,
+        options,
+    )?;
+
+    bundle_state.state.remove(&config.system_caller());
+    Ok(build_genesis_state(
+        &db,
+        bundle_state,
+        &config.contract_addresses(),
+    ))
+}
+
+/// Run the full generation without writing any output file, returning the
+/// artifacts as JSON strings for callers that embed the generator
+pub fn genesis_generate_in_memory(
+    byte_code_dir: &str,
+    config: &GenesisConfig,
+    jwks_file: Option<String>,
+    oidc_providers_file: Option<String>,
+    options: &GenerateOptions,
+) -> Result<(InMemoryDB, BundleState, GenesisArtifacts), GenesisError> {
+    let GenesisExecution {
+        db,
+        mut bundle_state,
+        genesis_txns,
+        chain_id,
+        contract_metrics,
+        tx_reports,
+        logs,
+        reverts,
+    } = execute_genesis(
+        byte_code_dir,
+        config,
+        jwks_file,
+        oidc_providers_file,
+        options,
+    )?;
+    let ret_bundle_state = bundle_state.clone();
+
+    // Add any state changes from the bundle_state (from the initialize transaction)
+    bundle_state.state.remove(&config.system_caller());
+    let bundle_state_json =
+        bundle_state_json(&mut bundle_state).map_err(output_failure("bundle_state.json"))?;
+
+    let genesis_state = build_genesis_state(&db, bundle_state, &config.contract_addresses());
+    let sorted_accounts = sorted_accounts(&genesis_state);
+    let accounts = artifact_json("genesis_accounts.json", &sorted_accounts)?;
+
+    // Create contracts JSON with bytecode
+    let contracts_json: BTreeMap<_, _> = genesis_state
+        .iter()
+        .filter_map(|(addr, account)| {
+            account
+                .info
+                .code
+                .as_ref()
+                .map(|code| (ChecksumAddress(*addr), code.bytecode()))
+        })
+        .collect();
+    let contracts = artifact_json("genesis_contracts.json", &contracts_json)?;
+    let geth_alloc = (options.format == OutputFormat::Geth)
+        .then(|| artifact_json(GETH_ALLOC_FILE, &geth_alloc(&genesis_state)))
+        .transpose()?;
+    let combined = options
+        .combined
+        .then(|| {
+            artifact_json(
+                COMBINED_GENESIS_FILE,
+                &CombinedGenesis {
+                    config,
+                    accounts: &sorted_accounts,
+                    contracts: &contracts_json,
+                    metadata: GenesisMetadata::new(chain_id, config.genesis_timestamp),
+                },
+            )
+        })
+        .transpose()?;
+    let transactions = genesis_txns
+        .map(|txns| artifact_json("genesis_txns.json", &txns))
+        .transpose()?;
+    let events = artifact_json(EVENTS_FILE, &genesis_events(&logs))?;
+    let reverts = reverts
+        .map(|reverts| artifact_json(REVERTS_FILE, &reverts))
+        .transpose()?;
+
+    let artifacts = GenesisArtifacts {
+        bundle_state: bundle_state_json,
+        accounts,
+        contracts,
+        transactions,
+        contract_metrics: contract_metrics_csv(&contract_metrics),
+        geth_alloc,
+        combined,
+        tx_reports,
+        events,
+        reverts,
+        logs,
+    };
+    let transactions = artifacts.transactions.as_deref();
+    let combined = artifacts.combined.as_deref();
+    check_chain_id(
+        config.chain_id(),
+        &[
+            (
+                "genesis_txns.json",
+                transactions.and_then(|json| json_chain_id(json, "/chainId")),
+            ),
+            (
+                "The config in genesis.json",
+                combined.and_then(|json| json_chain_id(json, "/config/chainId")),
+            ),
+            (
+                "The metadata in genesis.json",
+                combined.and_then(|json| json_chain_id(json, "/metadata/chainId")),
+            ),
+        ],
+    )?;
+    Ok((db, ret_bundle_state, artifacts))
+}
+
+/// What a dry run executed, reported instead of writing any output file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunSummary {
+    /// System and extra contracts deployed
+    pub contracts: usize,
+    /// Accounts in the final genesis state
+    pub accounts: usize,
+    /// Every executed transaction, in the order of `genesis_txns.json`. Inject mode
+    /// runs no system contract creations, so only extra contracts have one
+    pub transactions: Vec<TxReport>,
+}
+
+/// Deploy and initialize exactly as `genesis_generate` does, but only summarize the
+/// outcome instead of serializing it
+pub fn genesis_dry_run(
+    byte_code_dir: &str,
+    config: &GenesisConfig,
+    jwks_file: Option<String>,
+    oidc_providers_file: Option<String>,
+    options: &GenerateOptions,
+) -> Result<DryRunSummary, GenesisError> {
+    let GenesisExecution {
+        db,
+        mut bundle_state,
+        contract_metrics,
+        tx_reports,
+        ..
+    } = execute_genesis(
+        byte_code_dir,
+        config,
+        jwks_file,
+        oidc_providers_file,
+        options,
+    )?;
+
+    bundle_state.state.remove(&config.system_caller());
+    let accounts = build_genesis_state(&db, bundle_state, &config.contract_addresses()).len();
+    Ok(DryRunSummary {
+        contracts: contract_metrics.len(),
+        accounts,
+        transactions: tx_reports,
+    })
+}
+
+/// Deploy the system contracts, run `Genesis.initialize` followed by the optional
+/// JWK/OIDC upserts and any `options.extra_transactions`, then write the resulting state
+///
+/// When generation fails a `failure.json` report is written to `output_dir` and the
+/// same report returned as `GenesisError::Failed`; output files that cannot be
+/// written are returned as `GenesisError::FileWrite`. The per transaction gas
+/// written to `gas_report.json` is returned as well.
+pub fn genesis_generate(
+    byte_code_dir: &str,
+    output_dir: &str,
+    config: &GenesisConfig,
+    jwks_file: Option<String>,
+    oidc_providers_file: Option<String>,
+    options: &GenerateOptions,
+) -> Result<(InMemoryDB, BundleState, Vec<TxReport>), GenesisError> {
+    let fail = |report: FailureReport| {
+        write_failure_report(output_dir, &report);
+        GenesisError::Failed(report)
+    };
+
+    if options.low_memory {
+        let GenesisExecution {
+            db,
+            bundle_state,
+            genesis_txns,
+            contract_metrics,
+            tx_reports,
+            logs,
+            reverts,
+            ..
+        } = execute_genesis(
+            byte_code_dir,
+            config,
+            jwks_file,
+            oidc_providers_file,
+            options,
+        )
+        .map_err(fail)?;
+        check_chain_id(
+            config.chain_id(),
+            &[(
+                "genesis_txns.json",
+                genesis_txns.as_ref().map(|txns| txns.chain_id),
+            )],
+        )
+        .map_err(fail)?;
+        let mut files = StagedFiles::default();
+        if let Some(genesis_txns) = &genesis_txns {
+            write_genesis_transactions(&mut files, output_dir, genesis_txns)?;
+        }
+        let path = format!("{output_dir}/{CONTRACT_METRICS_FILE}");
+        if let Err(source) = files.write(path.clone(), contract_metrics_csv(&contract_metrics)) {
+            return Err(GenesisError::FileWrite { path, source });
+        }
+        write_gas_report(&mut files, output_dir, &tx_reports).map_err(|source| {
+            GenesisError::FileWrite {
+                path: format!("{output_dir}/{GAS_REPORT_FILE}"),
+                source,
+            }
+        })?;
+        write_json(
+            &mut files,
+            format!("{output_dir}/{EVENTS_FILE}"),
+            &genesis_events(&logs),
+        )?;
+        if let Some(reverts) = &reverts {
+            write_json(&mut files, format!("{output_dir}/{REVERTS_FILE}"), reverts)?;
+        }
+        let (db, bundle_state) = write_genesis_low_memory(
+            &mut files,
+            output_dir,
+            db,
+            bundle_state,
+            config,
+            options.format,
+            options.combined,
+        )?;
+        files.commit().map_err(|source| GenesisError::FileWrite {
+            path: output_dir.to_string(),
+            source,
+        })?;
+        return Ok((db, bundle_state, tx_reports));
+    }
+
+    let (db, bundle_state, artifacts) = genesis_generate_in_memory(
+        byte_code_dir,
+        config,
+        jwks_file,
+        oidc_providers_file,
+        options,
+    )
+    .map_err(|error| match error {
+        GenesisError::Failed(report) => fail(report),
+        error => error,
+    })?;
+    artifacts
+        .write_to(output_dir)
+        .map_err(|source| GenesisError::FileWrite {
+            path: output_dir.to_string(),
+            source,
+        })?;
+    if artifacts.transactions.is_some() {
+        info!(
+            "Exported genesis transactions to {}/genesis_txns.json",
+            output_dir
+        );
+    }
+    log_peak_rss();
+    Ok((db, bundle_state, artifacts.tx_reports))
+}
+
+/// Borrowed view of a genesis account, serialized with the same shape as `PlainAccount`
+#[derive(Serialize)]
+struct AccountView<'a> {
+    info: &'a AccountInfo,
+    #[serde(rename = "storage", serialize_with = "serialize_present_storage")]
+    bundle_account: Option<&'a BundleAccount>,
+}
+
+fn serialize_present_storage<S: Serializer>(
+    bundle_account: &Option<&BundleAccount>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let storage: BTreeMap<U256, U256> = bundle_account
+        .iter()
+        .flat_map(|account| account.storage.iter())
+        .map(|(slot, value)| (*slot, value.present_value()))
+        .collect();
+    storage.serialize(serializer)
+}
+
+/// A genesis account with the same shape as `PlainAccount`, its storage in slot order
+#[derive(Serialize)]
+pub(crate) struct SortedAccount<'a> {
+    info: &'a AccountInfo,
+    storage: BTreeMap<U256, U256>,
+}
+
+/// Report a generated artifact that could not be serialized
+fn output_failure(file: &str) -> impl Fn(serde_json::Error) -> FailureReport + '_ {
+    move |e| {
+        FailureReport::new(
+            FailurePhase::Output,
+            format!("Failed to serialize {}: {}", file, e),
+        )
+    }
+}
+
+/// Pretty printed JSON of the artifact written to `file`
+fn artifact_json<T: Serialize + ?Sized>(file: &str, value: &T) -> Result<String, FailureReport> {
+    serde_json::to_string_pretty(value).map_err(output_failure(file))
+}
+
+/// `bundle_state.json`, the same bytes for the same inputs. Going through `Value`
+/// sorts the keys of the bundle's hash maps but keeps the order of each
+/// transaction's revert list, so those are sorted by address first
+fn bundle_state_json(bundle_state: &mut BundleState) -> serde_json::Result<String> {
+    bundle_state.reverts.sort();
+    serde_json::to_string_pretty(&serde_json::to_value(&*bundle_state)?)
+}
+
+/// The genesis state as written to `genesis_accounts.json`, so the same inputs
+/// always produce the same bytes
+pub(crate) fn sorted_accounts(
+    state: &BTreeMap<Address, PlainAccount>,
+) -> BTreeMap<ChecksumAddress, SortedAccount<'_>> {
+    state
+        .iter()
+        .map(|(address, account)| {
+            (
+                ChecksumAddress(*address),
+                SortedAccount {
+                    info: &account.info,
+                    storage: account
+                        .storage
+                        .iter()
+                        .map(|(slot, value)| (*slot, *value))
+                        .collect(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// The final genesis state as a lazy merge of the deployed contract code and the
+/// post-initialization bundle, so it can be serialized without being materialized
+struct GenesisStateView<'a> {
+    contracts: Vec<(Address, AccountInfo)>,
+    bundle_state: &'a BundleState,
+}
+
+impl<'a> GenesisStateView<'a> {
+    fn new(
+        db: &InMemoryDB,
+        bundle_state: &'a BundleState,
+        system_contracts: &[(&str, Address)],
+    ) -> Self {
+        // Contracts untouched by initialization only carry their deployed code
+        let contracts = system_contracts
+            .iter()
+            .filter(|(_, address)| {
+                bundle_state
+                    .state
+                    .get(address)
+                    .is_none_or(|account| account.info.is_none())
+            })
+            .map(|(_, address)| {
+                let code = db
+                    .accounts
+                    .get(address)
+                    .and_then(|account| account.info.code.clone());
+                (
+                    *address,
+                    AccountInfo {
+                        code,
+                        ..AccountInfo::default()
+                    },
+                )
+            })
+            .collect();
+        Self {
+            contracts,
+            bundle_state,
+        }
+    }
+
+    /// Every account in address order; sorting the references keeps the output
+    /// reproducible without materializing the state
+    fn accounts(&self) -> impl Iterator<Item = (&Address, AccountView<'_>)> {
+        let contracts = self.contracts.iter().map(|(address, info)| {
+            (
+                address,
+                AccountView {
+                    info,
+                    bundle_account: None,
+                },
+            )
+        });
+        let initialized = self
+            .bundle_state
+            .state
+            .iter()
+            .filter_map(|(address, account)| {
+                account.info.as_ref().map(|info| {
+                    (
+                        address,
+                        AccountView {
+                            info,
+                            bundle_account: Some(account),
+                        },
+                    )
+                })
+            });
+        let mut accounts: Vec<_> = contracts.chain(initialized).collect();
+        accounts.sort_by_key(|(address, _)| **address);
+        accounts.into_iter()
+    }
+}
+
+struct AccountsJson<'a>(&'a GenesisStateView<'a>);
+
+impl Serialize for AccountsJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .accounts()
+                .map(|(address, account)| (ChecksumAddress(*address), account)),
+        )
+    }
+}
+
+struct ContractsJson<'a>(&'a GenesisStateView<'a>);
+
+impl Serialize for ContractsJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.accounts().filter_map(|(address, account)| {
+            account
+                .info
+                .code
+                .as_ref()
+                .map(|code| (ChecksumAddress(*address), code.bytecode()))
+        }))
+    }
+}
+
+struct GethAllocJson<'a>(&'a GenesisStateView<'a>);
+
+impl Serialize for GethAllocJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.accounts().map(|(address, account)| {
+            let storage = account
+                .bundle_account
+                .iter()
+                .flat_map(|account| account.storage.iter())
+                .map(|(slot, value)| (*slot, value.present_value()));
+            (
+                address.to_checksum(None),
+                GethAccount::new(account.info, storage),
+            )
+        }))
+    }
+}
+
+/// `bundle_state.json` streamed with the same bytes as `bundle_state_json`: fields
+/// and hash map keys are written in sorted order, and only one account, contract
+/// or revert at a time goes through `Value` to sort its own keys
+struct BundleStateJson<'a>(&'a BundleState);
+
+impl Serialize for BundleStateJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bundle_state = self.0;
+        let contracts: BTreeMap<_, _> = bundle_state
+            .contracts
+            .iter()
+            .map(|(hash, code)| (hash, SortedJson(code)))
+            .collect();
+        let reverts: Vec<Vec<_>> = bundle_state
+            .reverts
+            .iter()
+            .map(|reverts| reverts.iter().map(SortedJson).collect())
+            .collect();
+        let state: BTreeMap<_, _> = bundle_state
+            .state
+            .iter()
+            .map(|(address, account)| (address, SortedJson(account)))
+            .collect();
+        let mut map = serializer.serialize_map(Some(5))?;
+        map.serialize_entry("contracts", &contracts)?;
+        map.serialize_entry("reverts", &reverts)?;
+        map.serialize_entry("reverts_size", &bundle_state.reverts_size)?;
+        map.serialize_entry("state", &state)?;
+        map.serialize_entry("state_size", &bundle_state.state_size)?;
+        map.end()
+    }
+}
+
+/// Serializes a value through `Value`, which sorts the keys of its maps and structs
+struct SortedJson<'a, T>(&'a T);
+
+impl<T: Serialize> Serialize for SortedJson<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde_json::to_value(self.0)
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// An account in the `alloc` section of a geth style genesis.json
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GethAccount {
+    pub balance: U256,
+    /// Hex encoded, as geth expects
+    pub nonce: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Non-zero slots as 32-byte words
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, B256>,
+}
+
+impl GethAccount {
+    fn new(info: &AccountInfo, storage: impl Iterator<Item = (U256, U256)>) -> Self {
+        Self {
+            balance: info.balance,
+            nonce: format!("{:#x}", info.nonce),
+            code: info
+                .code
+                .as_ref()
+                .map(|code| code.original_bytes())
+                .filter(|code| !code.is_empty()),
+            storage: storage
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(slot, value)| (B256::from(slot), B256::from(value)))
+                .collect(),
+        }
+    }
+}
+
+/// The genesis state as a geth `alloc` map, keyed by checksummed address
+pub fn geth_alloc(state: &BTreeMap<Address, PlainAccount>) -> BTreeMap<String, GethAccount> {
+    state
+        .iter()
+        .map(|(address, account)| {
+            let storage = account.storage.iter().map(|(slot, value)| (*slot, *value));
+            (
+                address.to_checksum(None),
+                GethAccount::new(&account.info, storage),
+            )
+        })
+        .collect()
+}
+
+/// Low memory tail of `genesis_generate`: streams `genesis_accounts.json` and
+/// `genesis_contracts.json` directly from the bundle instead of building an owned
+/// `genesis_state`, and hands the bundle back without cloning it. A combined
+/// `genesis.json` is written for `config` when `combined` is set. Every file is
+/// staged in `files`, for the caller to commit
+fn write_genesis_low_memory(
+    files: &mut StagedFiles,
+    output_dir: &str,
+    db: InMemoryDB,
+    mut bundle_state: BundleState,
+    config: &GenesisConfig,
+    format: OutputFormat,
+    combined: bool,
+) -> Result<(InMemoryDB, BundleState), GenesisError> {
+    info!("=== Writing genesis state in low memory mode ===");
+    bundle_state.state.remove(&config.system_caller());
+    bundle_state.reverts.sort();
+    let contracts = config.contract_addresses();
+    write_json(
+        files,
+        format!("{output_dir}/bundle_state.json"),
+        &BundleStateJson(&bundle_state),
+    )?;
+    {
+        let view = GenesisStateView::new(&db, &bundle_state, &contracts);
+        let accounts: Vec<(Address, &AccountInfo)> = view
+            .accounts()
+            .map(|(address, account)| (*address, account.info))
+            .collect();
+        check_referenced_code(&accounts, &contracts);
+        write_json(
+            files,
+            format!("{output_dir}/genesis_accounts.json"),
+            &AccountsJson(&view),
+        )?;
+        write_json(
+            files,
+            format!("{output_dir}/genesis_contracts.json"),
+            &ContractsJson(&view),
+        )?;
+        if format == OutputFormat::Geth {
+            write_json(
+                files,
+                format!("{output_dir}/{GETH_ALLOC_FILE}"),
+                &GethAllocJson(&view),
+            )?;
+        }
+        if combined {
+            let combined = CombinedGenesis {
+                config,
+                accounts: AccountsJson(&view),
+                contracts: ContractsJson(&view),
+                metadata: GenesisMetadata::new(config.chain_id(), config.genesis_timestamp),
+            };
+            write_json(
+                files,
+                format!("{output_dir}/{COMBINED_GENESIS_FILE}"),
+                &combined,
+            )?;
+        }
+    }
+    log_peak_rss();
+    Ok((db, bundle_state))
+}
+
+fn log_peak_rss() {
+    if let Some(peak_rss_kb) = peak_rss_kb() {
+        info!("Peak RSS during genesis generation: {} kB", peak_rss_kb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{BLOCK_ADDR, VALIDATOR_MANAGER_ADDR, VALIDATOR_MANAGER_UTILS_ADDR};
+    use revm::db::states::reverts::{AccountRevert, Reverts};
+    use revm_primitives::{TxKind, address};
+
+    #[test]
+    fn test_deploy_extra_contracts_moves_constructor_storage() {
+        // SSTORE(0, 0x2a), then return the one byte runtime code 0x00 (STOP)
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path();
+        std::fs::write(&path, "0x602a600055600060005360016000f3").unwrap();
+        let faucet = ExtraContractDeployment {
+            name: "Faucet".to_string(),
+            bytecode_path: path.to_str().unwrap().to_string(),
+            address: address!("000000000000000000000000000000000000fa00"),
+            balance: U256::from(1_000),
+            constructor_args: Bytes::new(),
+        };
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        let (create_txs, deploy_gas) = deploy_extra_contracts(
+            &mut db,
+            &prepare_env(DEFAULT_CHAIN_ID),
+            SpecId::LATEST,
+            std::slice::from_ref(&faucet),
+            0,
+            false,
+            DeployReporter {
+                callback: None,
+                total: 1,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(create_txs.len(), 1);
+        assert!(deploy_gas[0] > 0);
+        let account = &db.accounts[&faucet.address];
+        assert_eq!(account.info.balance, faucet.balance);
+        assert_eq!(
+            account.info.code.as_ref().unwrap().original_bytes(),
+            Bytes::from(vec![0x00])
+        );
+        assert_eq!(account.storage.get(&U256::ZERO), Some(&U256::from(0x2a)));
+    }
+
+    #[test]
+    fn test_seed_block_hashes() {
+        let config: GenesisConfig = serde_json::from_str(
+            r#"{
+                "validators": [],
+                "genesisBlockHashes": {
+                    "9": "0x1111111111111111111111111111111111111111111111111111111111111111"
+                }
+            }"#,
+        )
+        .unwrap();
+        let mut env = prepare_env(DEFAULT_CHAIN_ID);
+        prepare_block_env(&mut env, &config).unwrap();
+        assert_eq!(env.block.number, U256::from(10));
+
+        // Return BLOCKHASH(9)
+        let reader = address!("000000000000000000000000000000000000fa02");
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        db.insert_account_info(
+            reader,
+            AccountInfo::from_bytecode(Bytecode::new_raw(Bytes::from_static(&[
+                0x60, 0x09, 0x40, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+            ]))),
+        );
+        seed_block_hashes(&mut db, &config.parsed_block_hashes().unwrap(), &env);
+        let tx = TxEnv {
+            caller: SYSTEM_CALLER,
+            transact_to: TxKind::Call(reader),
+            gas_limit: env.tx.gas_limit,
+            ..Default::default()
+        };
+        let (results, _) = execute_revm_sequential(db, SpecId::LATEST, env, &[tx], None).unwrap();
+        assert_eq!(
+            results[0].output().map(|output| output.as_ref()),
+            Some(B256::repeat_byte(0x11).as_slice())
+        );
+    }
+
+    #[test]
+    fn test_genesis_gas_limit() {
+        // A constructor that loops until it runs out of gas
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path();
+        std::fs::write(&path, "0x5b600056").unwrap();
+        let runaway = ExtraContractDeployment {
+            name: "Runaway".to_string(),
+            bytecode_path: path.to_str().unwrap().to_string(),
+            address: address!("000000000000000000000000000000000000fa01"),
+            balance: U256::ZERO,
+            constructor_args: Bytes::new(),
+        };
+        let mut env = prepare_env(DEFAULT_CHAIN_ID);
+        env.tx.gas_limit = 1_000_000;
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let callback = ProgressCallback::new({
+            let events = events.clone();
+            move |event| events.lock().unwrap().push(event)
+        });
+        let report = deploy_extra_contracts(
+            &mut db,
+            &env,
+            SpecId::LATEST,
+            std::slice::from_ref(&runaway),
+            0,
+            false,
+            DeployReporter {
+                callback: Some(&callback),
+                total: CONTRACTS.len() + 1,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(report.contract.as_deref(), Some("Runaway"));
+        assert_eq!(report.gas_used, Some(1_000_000));
+        // The failure is reported as the step after the system contracts
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].step, CONTRACTS.len() + 1);
+        assert_eq!(events[0].total, events[0].step);
+        assert_eq!(events[0].contract_name, "Runaway");
+        assert_eq!(
+            events[0].status,
+            DeployStatus::Failed(report.reason.clone())
+        );
+
+        let tx_reports: Vec<TxReport> = [700_000, 900_000]
+            .into_iter()
+            .enumerate()
+            .map(|(index, gas_used)| TxReport {
+                index,
+                success: true,
+                gas_used,
+                revert_selector: None,
+                revert_reason: None,
+                elapsed: None,
+            })
+            .collect();
+        let near = transactions_near_gas_limit(&tx_reports, 1_000_000);
+        assert_eq!(near, vec![&tx_reports[1]]);
+        // A 27M gas initialization is close to the default limit, but not to no limit
+        let heavy = [TxReport {
+            gas_used: 27_000_000,
+            ..tx_reports[0].clone()
+        }];
+        assert_eq!(
+            transactions_near_gas_limit(&heavy, DEFAULT_GAS_LIMIT),
+            vec![&heavy[0]]
+        );
+        assert!(transactions_near_gas_limit(&heavy, UNBOUNDED_GAS_LIMIT).is_empty());
+    }
+
+    #[test]
+    fn test_geth_alloc() {
+        let address = address!("6e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f");
+        let mut account = PlainAccount {
+            info: AccountInfo {
+                balance: U256::from(1_000),
+                nonce: 1,
+                code: Some(Bytecode::new_raw(Bytes::from(vec![0x60, 0x00]))),
+                ..AccountInfo::default()
+            },
+            storage: Default::default(),
+        };
+        account.storage.insert(U256::from(1), U256::from(0x2a));
+        account.storage.insert(U256::from(2), U256::ZERO);
+        let state = BTreeMap::from([(address, account)]);
+
+        let alloc = serde_json::to_value(geth_alloc(&state)).unwrap();
+        assert_eq!(
+            alloc,
+            serde_json::json!({
+                "0x6e2021Ee24e2430DA0f5BB9C2Ae6c586BF3E0a0F": {
+                    "balance": "0x3e8",
+                    "nonce": "0x1",
+                    "code": "0x6000",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001":
+                            "0x000000000000000000000000000000000000000000000000000000000000002a"
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_combined_genesis() {
+        let config = GenesisConfig::default();
+        let contracts = BTreeMap::from([(GENESIS_ADDR, Bytes::from(vec![0x60, 0x00]))]);
+        let combined = serde_json::to_value(CombinedGenesis {
+            config: &config,
+            accounts: BTreeMap::<Address, PlainAccount>::new(),
+            contracts: &contracts,
+            metadata: GenesisMetadata::new(1337, None),
+        })
+        .unwrap();
+
+        assert_eq!(combined["config"], serde_json::to_value(&config).unwrap());
+        assert_eq!(combined["accounts"], serde_json::json!({}));
+        assert_eq!(
+            combined["contracts"][GENESIS_ADDR.to_string().to_lowercase()],
+            "0x6000"
+        );
+        let metadata: GenesisMetadata =
+            serde_json::from_value(combined["metadata"].clone()).unwrap();
+        assert_eq!(metadata.chain_id, 1337);
+        assert_eq!(metadata.tool_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata.timestamp, None);
+        assert!(combined["metadata"].get("timestamp").is_none());
+
+        let metadata = GenesisMetadata::new(1337, Some(1_700_000_000));
+        assert_eq!(
+            serde_json::to_value(&metadata).unwrap()["timestamp"],
+            1_700_000_000
+        );
+    }
+
+    #[test]
+    fn test_sorted_accounts() {
+        let first = address!("000000000000000000000000000000000000fa01");
+        let second = address!("000000000000000000000000000000000000fa02");
+        let mut state = BTreeMap::new();
+        for address in [second, first] {
+            let mut account = PlainAccount {
+                info: AccountInfo::default(),
+                storage: Default::default(),
+            };
+            for slot in [3u64, 1, 2] {
+                account.storage.insert(U256::from(slot), U256::from(slot));
+            }
+            state.insert(address, account);
+        }
+
+        let json = serde_json::to_string_pretty(&sorted_accounts(&state)).unwrap();
+        let position = |needle: &str| json.find(needle).unwrap();
+        // Keys carry the EIP-55 checksum but stay in address order
+        let key = |address| format!("\"{}\"", ChecksumAddress(address));
+        assert!(position(&key(first)) < position(&key(second)));
+        assert!(position("\"0x1\"") < position("\"0x2\""));
+        assert!(position("\"0x2\"") < position("\"0x3\""));
+    }
+
+    #[test]
+    fn test_bundle_state_json_sorts_reverts() {
+        let mut bundle_state = BundleState::default();
+        bundle_state.reverts = Reverts::new(vec![vec![
+            (
+                address!("000000000000000000000000000000000000fa02"),
+                AccountRevert::default(),
+            ),
+            (
+                address!("000000000000000000000000000000000000fa01"),
+                AccountRevert::default(),
+            ),
+        ]]);
+
+        let json = bundle_state_json(&mut bundle_state).unwrap();
+        let position = |needle: &str| json.find(needle).unwrap();
+        assert!(position("fa01") < position("fa02"));
+    }
+
+    #[test]
+    fn test_check_code_sizes() {
+        let address = address!("000000000000000000000000000000000000fa00");
+        let code = Bytes::from(vec![0x00; DEFAULT_MAX_CODE_SIZE + 1]);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            address,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(code)),
+                ..AccountInfo::default()
+            },
+        );
+        let contracts = || [("Large", address)].into_iter();
+
+        assert!(check_code_sizes(&db, contracts(), DEFAULT_MAX_CODE_SIZE, true).is_ok());
+        let report = check_code_sizes(&db, contracts(), DEFAULT_MAX_CODE_SIZE, false).unwrap_err();
+        assert_eq!(report.phase, FailurePhase::Deploy);
+        assert_eq!(report.contract.as_deref(), Some("Large"));
+        assert!(check_code_sizes(&db, contracts(), DEFAULT_MAX_CODE_SIZE + 1, false).is_ok());
+    }
+
+    #[test]
+    fn test_check_address_map() {
+        let leftover = address!("000000000000000000000000000000000000fa00");
+        let code = || AccountInfo {
+            code: Some(Bytecode::new_raw(Bytes::from(vec![0x60, 0x00]))),
+            ..AccountInfo::default()
+        };
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        db.insert_account_info(GENESIS_ADDR, code());
+        let contracts = || [("Genesis", GENESIS_ADDR)].into_iter();
+        assert!(check_address_map(&db, contracts(), None).is_ok());
+
+        let report = check_address_map(&db, [("Block", BLOCK_ADDR)].into_iter(), None).unwrap_err();
+        assert_eq!(report.contract.as_deref(), Some("Block"));
+
+        db.insert_account_info(leftover, code());
+        let report = check_address_map(&db, contracts(), None).unwrap_err();
+        assert_eq!(report.phase, FailurePhase::Deploy);
+        assert!(
+            report
+                .reason
+                .contains(&ChecksumAddress(leftover).to_string())
+        );
+        assert!(check_address_map(&db, contracts(), Some(leftover)).is_ok());
+    }
+
+    #[test]
+    fn test_check_runtime_code() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        std::fs::create_dir_all(dir.join("Genesis.sol")).unwrap();
+        // Constructor returning the single byte runtime code 0x00
+        std::fs::write(
+            dir.join("Genesis.sol/Genesis.json"),
+            r#"{"bytecode": {"object": "0x6001600c60003960016000f300"}}"#,
+        )
+        .unwrap();
+        let byte_code_dir = dir.to_str().unwrap();
+        let env = prepare_env(DEFAULT_CHAIN_ID);
+        let db_with_code = |code: u8| {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                GENESIS_ADDR,
+                AccountInfo {
+                    code: Some(Bytecode::new_raw(Bytes::from(vec![code]))),
+                    ..AccountInfo::default()
+                },
+            );
+            db
+        };
+
+        let check = |db: &InMemoryDB, strict: bool| {
+            check_runtime_code(db, byte_code_dir, &CONTRACTS, &env, SpecId::LATEST, strict)
+        };
+
+        assert!(check(&db_with_code(0x00), true).is_ok());
+        let mismatched = db_with_code(0x01);
+        assert!(check(&mismatched, false).is_ok());
+        let report = check(&mismatched, true).unwrap_err();
+        assert_eq!(report.contract.as_deref(), Some("Genesis"));
+    }
+
+    #[test]
+    fn test_deploy_contract_create2() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        std::fs::create_dir_all(dir.join("Genesis.sol")).unwrap();
+        // Constructor returning the single byte runtime code 0x00
+        std::fs::write(
+            dir.join("Genesis.sol/Genesis.json"),
+            r#"{"bytecode": {"object": "0x6001600c60003960016000f300"}}"#,
+        )
+        .unwrap();
+        let salt = B256::repeat_byte(0x01);
+        let (tx, created_address) =
+            deploy_contract_create2(dir.to_str().unwrap(), "Genesis", salt, &CONTRACTS).unwrap();
+        assert_eq!(
+            created_address,
+            CREATE2_DEPLOYER
+                .create2_from_code(salt, hex::decode("6001600c60003960016000f300").unwrap())
+        );
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        db.insert_account_info(
+            CREATE2_DEPLOYER,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from_static(CREATE2_DEPLOYER_CODE))),
+                ..AccountInfo::default()
+            },
+        );
+        let (results, bundle_state) = execute_revm_sequential(
+            &db,
+            SpecId::LATEST,
+            prepare_env(DEFAULT_CHAIN_ID),
+            &[tx],
+            None,
+        )
+        .unwrap();
+        let ExecutionResult::Success {
+            output: Output::Call(returned),
+            ..
+        } = &results[0]
+        else {
+            panic!("deployer call failed: {:?}", results[0]);
+        };
+        assert_eq!(returned.as_ref(), created_address.as_slice());
+        assert_eq!(
+            deployed_code(&bundle_state, &created_address),
+            Some(Bytes::from(vec![0x00]))
+        );
+    }
+
+    #[test]
+    fn test_deploy_from_foundry_artifacts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        for (name, _) in CONTRACTS {
+            std::fs::create_dir_all(dir.join(format!("{}.sol", name))).unwrap();
+            let artifact = serde_json::json!({
+                "abi": [],
+                "bytecode": { "object": "0x00" },
+                "deployedBytecode": { "object": "0x6001" },
+            });
+            std::fs::write(
+                artifact_path(dir.to_str().unwrap(), name),
+                artifact.to_string(),
+            )
+            .unwrap();
+        }
+        let byte_code_dir = dir.to_str().unwrap();
+        // No `.hex` file is needed once the artifacts are read
+        assert!(preflight_check(byte_code_dir, DeployMode::Inject, ArtifactFormat::Hex).is_err());
+        assert!(
+            preflight_check(byte_code_dir, DeployMode::Inject, ArtifactFormat::Foundry).is_ok()
+        );
+
+        let progress = DeployReporter {
+            callback: None,
+            total: CONTRACTS.len(),
+        };
+        let db = deploy_bsc_style(
+            byte_code_dir,
+            SYSTEM_CALLER,
+            &GenesisConfig::default(),
+            ArtifactFormat::Foundry,
+            progress,
+        )
+        .unwrap();
+        for (_, address) in CONTRACTS {
+            let code = db.accounts[&address].info.code.as_ref().unwrap();
+            assert_eq!(code.original_bytes(), Bytes::from(vec![0x60, 0x01]));
+        }
+
+        // A broken artifact fails the deployment, naming its contract
+        let (name, _) = CONTRACTS[1];
+        std::fs::write(
+            artifact_path(byte_code_dir, name),
+            serde_json::json!({
+                "abi": [],
+                "bytecode": { "object": "0x00" },
+                "deployedBytecode": { "object": "0xzz" },
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let report = deploy_bsc_style(
+            byte_code_dir,
+            SYSTEM_CALLER,
+            &GenesisConfig::default(),
+            ArtifactFormat::Foundry,
+            progress,
+        )
+        .unwrap_err();
+        assert_eq!(report.phase, FailurePhase::Deploy);
+        assert_eq!(report.contract.as_deref(), Some(name));
+    }
+
+    #[test]
+    fn test_preflight_check() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        for (name, _) in CONTRACTS {
+            std::fs::write(dir.join(format!("{}.hex", name)), "0x00").unwrap();
+        }
+        let byte_code_dir = dir.to_str().unwrap();
+        assert!(preflight_check(byte_code_dir, DeployMode::Inject, ArtifactFormat::Hex).is_ok());
+
+        // An empty file counts as missing, and every missing file is reported
+        std::fs::write(dir.join("Genesis.hex"), "").unwrap();
+        std::fs::remove_file(dir.join("ValidatorManagerUtils.hex")).unwrap();
+        let error =
+            preflight_check(byte_code_dir, DeployMode::Inject, ArtifactFormat::Hex).unwrap_err();
+        let GenesisError::MissingBytecode { files, .. } = &error else {
+            panic!("unexpected error {}", error);
+        };
+        assert_eq!(files.len(), 2);
+        assert!(error.to_string().contains("Genesis.hex"), "{}", error);
+        assert!(error.to_string().contains("ValidatorManagerUtils.hex"));
+
+        // Construct mode reads the artifacts instead
+        let error =
+            preflight_check(byte_code_dir, DeployMode::Construct, ArtifactFormat::Hex).unwrap_err();
+        assert!(
+            error.to_string().contains("Genesis.sol/Genesis.json"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_genesis_generate_returns_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let dir_str = dir.to_str().unwrap();
+
+        // No bytecode at all, so the deployment fails before anything runs
+        let result = genesis_generate(
+            dir_str,
+            dir_str,
+            &GenesisConfig::default(),
+            None,
+            None,
+            &GenerateOptions::default(),
+        );
+        let Err(GenesisError::Failed(report)) = result else {
+            panic!("expected a failure report");
+        };
+        assert_eq!(report.phase, FailurePhase::Deploy);
+        let written: FailureReport = serde_json::from_str(
+            &std::fs::read_to_string(dir.join(crate::failure::FAILURE_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written, report);
+    }
+
+    #[test]
+    fn test_build_genesis_transactions_reports_unreadable_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let missing = temp_dir
+            .path()
+            .join("missing.json")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let config = GenesisConfig::default();
+        assert!(matches!(
+            build_genesis_transactions(&config, Some(missing.clone()), None, &[]),
+            Err(GenesisError::FileRead { .. })
+        ));
+        assert!(matches!(
+            build_genesis_transactions(&config, None, Some(missing), &[]),
+            Err(GenesisError::FileRead { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_storage_overwrites() {
+        let (name, implementation_slot) = CODE_SLOTS[0];
+        let constructor_storage = ConstructorStorage::from([(
+            VALIDATOR_MANAGER_ADDR,
+            BTreeMap::from([(implementation_slot, U256::from(1))]),
+        )]);
+        let db = InMemoryDB::default();
+        let mut bundle_state = BundleState::default();
+        bundle_account_mut(&mut bundle_state, &db, VALIDATOR_MANAGER_ADDR)
+            .storage
+            .entry(implementation_slot)
+            .or_default()
+            .present_value = U256::from(2);
+
+        // Only logged unless strict
+        assert!(check_storage_overwrites(&db, &constructor_storage, &bundle_state, false).is_ok());
+        let report =
+            check_storage_overwrites(&db, &constructor_storage, &bundle_state, true).unwrap_err();
+        assert_eq!(report.phase, FailurePhase::Init);
+        assert!(report.reason.contains(name), "{}", report.reason);
+        // Slots no constructor wrote are not compared
+        assert!(
+            check_storage_overwrites(&db, &ConstructorStorage::new(), &bundle_state, true).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_check_chain_id() {
+        let combined = r#"{"config": {"chainId": 1337}, "metadata": {"chainId": 1}}"#;
+        assert_eq!(json_chain_id(combined, "/config/chainId"), Some(1337));
+        assert_eq!(json_chain_id(combined, "/chainId"), None);
+
+        assert!(check_chain_id(1337, &[("genesis_txns.json", None)]).is_ok());
+        let report = check_chain_id(
+            1337,
+            &[
+                (
+                    "The config in genesis.json",
+                    json_chain_id(combined, "/config/chainId"),
+                ),
+                (
+                    "The metadata in genesis.json",
+                    json_chain_id(combined, "/metadata/chainId"),
+                ),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(report.phase, FailurePhase::Verify);
+        assert_eq!(
+            report.reason,
+            "The metadata in genesis.json records chain id 1, but the config uses 1337"
+        );
+    }
+
+    #[test]
+    fn test_genesis_transactions_predeploys() {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            BLOCK_ADDR,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from(vec![0x60, 0x00]))),
+                ..AccountInfo::default()
+            },
+        );
+        let contracts = [("Genesis", GENESIS_ADDR), ("Block", BLOCK_ADDR)];
+        let create_tx = (TxEnv::default(), GENESIS_ADDR);
+
+        let txns = GenesisTransactions::new(1337, &db, &contracts, &[create_tx], &[]);
+        // Only the contract without a creation transaction is a predeploy, with its code
+        assert_eq!(
+            txns.predeploys,
+            vec![Predeploy {
+                name: "Block".to_string(),
+                address: BLOCK_ADDR,
+                code: Bytes::from(vec![0x60, 0x00]),
+            }]
+        );
+        assert_eq!(txns.transactions[0].deploy_to, Some(GENESIS_ADDR));
+        let json = serde_json::to_value(&txns).unwrap();
+        assert_eq!(json["predeploys"][0]["code"], "0x6000");
+    }
+
+    #[test]
+    fn test_low_memory_output_matches_in_memory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let dir_str = dir.to_str().unwrap();
+        let config = GenesisConfig::default();
+        let contracts = config.contract_addresses();
+
+        let code = |bytes: Vec<u8>| Some(Bytecode::new_raw(Bytes::from(bytes)));
+        let mut db = InMemoryDB::default();
+        // GENESIS_ADDR is left untouched by initialization, BLOCK_ADDR gets storage
+        db.insert_account_info(
+            GENESIS_ADDR,
+            AccountInfo {
+                code: code(vec![0x60, 0x00]),
+                ..AccountInfo::default()
+            },
+        );
+        db.insert_account_info(
+            BLOCK_ADDR,
+            AccountInfo {
+                code: code(vec![0x00]),
+                ..AccountInfo::default()
+            },
+        );
+        let mut bundle_state = BundleState::default();
+        for slot in [3u64, 1, 2] {
+            bundle_account_mut(&mut bundle_state, &db, BLOCK_ADDR)
+                .storage
+                .entry(U256::from(slot))
+                .or_default()
+                .present_value = U256::from(slot);
+        }
+        let premined = address!("000000000000000000000000000000000000fa01");
+        bundle_account_mut(&mut bundle_state, &db, premined)
+            .info
+            .as_mut()
+            .unwrap()
+            .balance = U256::from(1_000);
+        bundle_account_mut(&mut bundle_state, &db, config.system_caller());
+        for bytes in [vec![0x60, 0x00], vec![0x00]] {
+            let bytecode = Bytecode::new_raw(Bytes::from(bytes));
+            bundle_state
+                .contracts
+                .insert(bytecode.hash_slow(), bytecode);
+        }
+        bundle_state.reverts = Reverts::new(vec![vec![
+            (BLOCK_ADDR, AccountRevert::default()),
+            (premined, AccountRevert::default()),
+        ]]);
+
+        let mut files = StagedFiles::default();
+        let (_, returned) = write_genesis_low_memory(
+            &mut files,
+            dir_str,
+            db.clone(),
+            bundle_state.clone(),
+            &config,
+            OutputFormat::Geth,
+            false,
+        )
+        .unwrap();
+        files.commit().unwrap();
+        // Only the system caller is dropped from the returned bundle
+        assert_eq!(returned.state.len(), 2);
+
+        bundle_state.state.remove(&config.system_caller());
+        let read = |file: &str| std::fs::read_to_string(dir.join(file)).unwrap();
+        assert_eq!(
+            read("bundle_state.json"),
+            bundle_state_json(&mut bundle_state).unwrap()
+        );
+        let genesis_state = build_genesis_state(&db, bundle_state, &contracts);
+        assert_eq!(
+            read("genesis_accounts.json"),
+            serde_json::to_string_pretty(&sorted_accounts(&genesis_state)).unwrap()
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&read(GETH_ALLOC_FILE)).unwrap(),
+            serde_json::to_value(geth_alloc(&genesis_state)).unwrap()
+        );
+        let written: BTreeMap<Address, Bytes> =
+            serde_json::from_str(&read("genesis_contracts.json")).unwrap();
+        assert_eq!(written[&GENESIS_ADDR], Bytes::from(vec![0x60, 0x00]));
+        assert_eq!(written[&BLOCK_ADDR], Bytes::from(vec![0x00]));
+    }
+
+    #[test]
+    fn test_check_referenced_code() {
+        // PUSH20 VALIDATOR_MANAGER_UTILS_ADDR, then DELEGATECALL
+        let code = Bytecode::new_raw(Bytes::from(
+            hex::decode("730000000000000000000000000000000000002014f4").unwrap(),
+        ));
+        let caller = AccountInfo {
+            code: Some(code),
+            ..AccountInfo::default()
+        };
+        let library = AccountInfo {
+            code: Some(Bytecode::new_raw(Bytes::from(vec![0x00]))),
+            ..AccountInfo::default()
+        };
+        let accounts = [
+            (VALIDATOR_MANAGER_ADDR, &caller),
+            (VALIDATOR_MANAGER_UTILS_ADDR, &library),
+        ];
+        assert!(check_referenced_code(&accounts, &CONTRACTS).is_empty());
+        // Without the library its address is reported
+        assert_eq!(
+            check_referenced_code(&accounts[..1], &CONTRACTS),
+            vec![(VALIDATOR_MANAGER_ADDR, VALIDATOR_MANAGER_UTILS_ADDR)]
+        );
+    }
 }
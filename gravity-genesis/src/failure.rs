@@ -0,0 +1,179 @@
+use revm_primitives::ExecutionResult;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::utils::analyze_txn_result;
+
+pub const FAILURE_FILE: &str = "failure.json";
+
+/// Stage of generation a failure happened in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailurePhase {
+    /// Placing or constructing the system contracts
+    Deploy,
+    /// Running `Genesis.initialize` and the follow-up system calls
+    Init,
+    /// Checking the generated state against the config
+    Verify,
+    /// Serializing the generated artifacts
+    Output,
+}
+
+/// Machine readable description of a failed generation, written to `failure.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureReport {
+    pub phase: FailurePhase,
+    /// The system contract being deployed, for deploy failures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+    /// Index of the failing transaction, as numbered in `genesis_txns.json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<usize>,
+    /// Decoded revert or halt reason, or the error message
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    /// With `continue_on_failure`, the index of every failed transaction of the
+    /// batch this one was the first failure of
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_transactions: Vec<usize>,
+}
+
+impl FailureReport {
+    pub fn new(phase: FailurePhase, reason: impl Into<String>) -> Self {
+        Self {
+            phase,
+            contract: None,
+            transaction_index: None,
+            reason: reason.into(),
+            gas_used: None,
+            failed_transactions: Vec::new(),
+        }
+    }
+
+    /// Report for a transaction that reverted or halted
+    pub fn from_result(
+        phase: FailurePhase,
+        transaction_index: usize,
+        result: &ExecutionResult,
+    ) -> Self {
+        Self {
+            transaction_index: Some(transaction_index),
+            gas_used: Some(result.gas_used()),
+            ..Self::new(phase, analyze_txn_result(result))
+        }
+    }
+
+    pub fn with_contract(mut self, contract: &str) -> Self {
+        self.contract = Some(contract.to_string());
+        self
+    }
+
+    /// The first of `failures` carrying the indices of all of them, or `Ok` if the
+    /// batch had none
+    pub fn collect(failures: Vec<FailureReport>) -> Result<(), FailureReport> {
+        let failed_transactions: Vec<usize> = failures
+            .iter()
+            .filter_map(|failure| failure.transaction_index)
+            .collect();
+        match failures.into_iter().next() {
+            None => Ok(()),
+            Some(first) => Err(Self {
+                failed_transactions,
+                ..first
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for FailureReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} failed", self.phase)?;
+        if let Some(contract) = &self.contract {
+            write!(f, " for {}", contract)?;
+        }
+        if let Some(index) = self.transaction_index {
+            write!(f, " at transaction {}", index)?;
+        }
+        write!(f, ": {}", self.reason)?;
+        if self.failed_transactions.len() > 1 {
+            write!(
+                f,
+                " ({} transactions failed: {:?})",
+                self.failed_transactions.len(),
+                self.failed_transactions
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FailureReport {}
+
+/// Write `failure.json` into `output_dir`, logging instead of failing if that is
+/// not possible so the original error is not masked
+pub fn write_failure_report(output_dir: &str, report: &FailureReport) {
+    let path = format!("{output_dir}/{FAILURE_FILE}");
+    let written = serde_json::to_string_pretty(report)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    match written {
+        Ok(()) => info!("Wrote failure report to {}", path),
+        Err(e) => warn!("Failed to write failure report to {}: {}", path, e),
+    }
+}
+
+/// Remove a `failure.json` left over from an earlier failed run
+pub fn remove_stale_failure_report(output_dir: &str) {
+    let path = format!("{output_dir}/{FAILURE_FILE}");
+    if std::fs::remove_file(&path).is_ok() {
+        info!("Removed stale failure report {}", path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm_primitives::Bytes;
+
+    #[test]
+    fn test_failure_report_from_revert() {
+        let result = ExecutionResult::Revert {
+            gas_used: 21_000,
+            output: Bytes::from(vec![0x11, 0x6c, 0x64, 0xa8]),
+        };
+        let report = FailureReport::from_result(FailurePhase::Init, 3, &result);
+        assert_eq!(report.transaction_index, Some(3));
+        assert_eq!(report.gas_used, Some(21_000));
+        assert!(report.reason.contains("OnlyCoinbase"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["phase"], "init");
+        assert_eq!(json["transactionIndex"], 3);
+        assert!(json.get("contract").is_none());
+        assert!(json.get("failedTransactions").is_none());
+    }
+
+    #[test]
+    fn test_collect_failures() {
+        assert_eq!(FailureReport::collect(Vec::new()), Ok(()));
+
+        let revert = |index| {
+            let result = ExecutionResult::Revert {
+                gas_used: 21_000,
+                output: Bytes::new(),
+            };
+            FailureReport::from_result(FailurePhase::Deploy, index, &result)
+        };
+        let report = FailureReport::collect(vec![revert(2), revert(5)]).unwrap_err();
+        assert_eq!(report.transaction_index, Some(2));
+        assert_eq!(report.failed_transactions, vec![2, 5]);
+        assert!(
+            report
+                .to_string()
+                .ends_with("(2 transactions failed: [2, 5])")
+        );
+    }
+}
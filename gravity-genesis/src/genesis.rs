@@ -1,30 +1,2135 @@
 use alloy_sol_macro::sol;
 use alloy_sol_types::SolCall;
-use revm_primitives::{Address, Bytes, ExecutionResult, FixedBytes, TxEnv, U256, hex};
+use revm_primitives::{
+    AccountInfo, Address, B256, Bytes, ExecutionResult, FixedBytes, SpecId, TxEnv, U256, hex,
+    keccak256,
+};
 use serde::{Deserialize, Serialize};
-use tracing::{error, info};
+use std::{collections::BTreeMap, path::Path};
+use tracing::{error, info, warn};
 
 use crate::{
-    post_genesis::handle_execution_result,
-    utils::{EPOCH_MANAGER_ADDR, VALIDATOR_MANAGER_ADDR, new_system_call_txn},
+    execute::{DEFAULT_CHAIN_ID, DEFAULT_GAS_LIMIT},
+    post_genesis::{Mismatch, handle_execution_result},
+    utils::{
+        CONTRACTS, ChecksumAddress, DEAD_ADDRESS, EPOCH_MANAGER_ADDR, RESERVED_ADDRESSES,
+        STAKE_CONFIG_ADDR, SYSTEM_ACCOUNT_INFO, SYSTEM_CALLER, VALIDATOR_MANAGER_ADDR,
+        VALIDATOR_PERFORMANCE_TRACKER_ADDR, analyze_txn_result, derive_address,
+        new_system_call_txn, parse_hex_field, with_contract_addresses,
+    },
 };
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct GenesisConfig {
-    #[serde(rename = "validatorAddresses")]
+/// Basis points of a 100% commission rate
+pub const COMMISSION_RATE_BASE: u64 = 10_000;
+
+/// Byte lengths of a BLS12-381 G1 public key, compressed and uncompressed
+pub const BLS_PUBLIC_KEY_LENGTHS: [usize; 2] = [48, 96];
+
+#[cfg(feature = "schema")]
+const ADDRESS_PATTERN: &str = "^(0x)?[0-9a-fA-F]{40}$";
+#[cfg(feature = "schema")]
+const BLS_PUBLIC_KEY_PATTERN: &str = "^(0x)?([0-9a-fA-F]{96}|[0-9a-fA-F]{192})$";
+#[cfg(feature = "schema")]
+const HEX_PATTERN: &str = "^(0x)?([0-9a-fA-F]{2})*$";
+
+/// An account funded at genesis, with its balance in wei
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PremineEntry {
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = ADDRESS_PATTERN)))]
+    pub address: String,
+    pub balance: String,
+}
+
+/// A storage slot set directly in the genesis state, after initialization
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StorageOverride {
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = ADDRESS_PATTERN)))]
+    pub address: String,
+    pub slot: String,
+    pub value: String,
+}
+
+/// A user contract deployed at a fixed address alongside the system contracts
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraContract {
+    pub name: String,
+    /// Creation bytecode, either a `.hex` file or a Foundry artifact (`.json`)
+    pub bytecode_path: String,
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = ADDRESS_PATTERN)))]
+    pub address: String,
+    /// Starting balance in wei, zero if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<String>,
+    /// ABI encoded constructor arguments as hex, appended to the creation bytecode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = HEX_PATTERN)))]
+    pub constructor_args: Option<String>,
+}
+
+/// An `extraContracts` entry with its fields parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtraContractDeployment {
+    pub name: String,
+    pub bytecode_path: String,
+    pub address: Address,
+    pub balance: U256,
+    pub constructor_args: Bytes,
+}
+
+/// Expected staking parameters, compared with what initialization stored in
+/// `StakeConfig` and `EpochManager`. Unset fields are not checked.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct StakeConfigParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch_interval_microsecs: Option<String>,
+    /// In wei
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_validator_stake: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_validator_count: Option<String>,
+    /// Unbond period, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurring_lockup_duration: Option<String>,
+}
+
+/// A staking parameter readable through a view call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StakeConfigField {
+    EpochIntervalMicrosecs,
+    MinValidatorStake,
+    MaxValidatorCount,
+    RecurringLockupDuration,
+}
+
+impl StakeConfigField {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StakeConfigField::EpochIntervalMicrosecs => "epochIntervalMicrosecs",
+            StakeConfigField::MinValidatorStake => "minValidatorStake",
+            StakeConfigField::MaxValidatorCount => "maxValidatorCount",
+            StakeConfigField::RecurringLockupDuration => "recurringLockupDuration",
+        }
+    }
+
+    /// View call reading the stored value
+    pub fn call(&self) -> TxEnv {
+        let (contract, call_data) = match self {
+            StakeConfigField::EpochIntervalMicrosecs => (
+                EPOCH_MANAGER_ADDR,
+                IEpochManager::epochIntervalMicrosecsCall {}.abi_encode(),
+            ),
+            StakeConfigField::MinValidatorStake => (
+                STAKE_CONFIG_ADDR,
+                IStakeConfig::minValidatorStakeCall {}.abi_encode(),
+            ),
+            StakeConfigField::MaxValidatorCount => (
+                STAKE_CONFIG_ADDR,
+                IStakeConfig::maxValidatorCountCall {}.abi_encode(),
+            ),
+            StakeConfigField::RecurringLockupDuration => (
+                STAKE_CONFIG_ADDR,
+                IStakeConfig::recurringLockupDurationCall {}.abi_encode(),
+            ),
+        };
+        new_system_call_txn(contract, call_data.into())
+    }
+
+    /// Decode the stored value from a successful `call` result
+    pub fn decode(&self, result: &ExecutionResult) -> Result<U256, String> {
+        if !result.is_success() {
+            return Err(format!(
+                "{} failed: {}",
+                self.name(),
+                analyze_txn_result(result)
+            ));
+        }
+        let output = result.output().cloned().unwrap_or_default();
+        let value = match self {
+            StakeConfigField::EpochIntervalMicrosecs => {
+                IEpochManager::epochIntervalMicrosecsCall::abi_decode_returns(&output, false)
+                    .map(|r| r._0)
+            }
+            StakeConfigField::MinValidatorStake => {
+                IStakeConfig::minValidatorStakeCall::abi_decode_returns(&output, false)
+                    .map(|r| r._0)
+            }
+            StakeConfigField::MaxValidatorCount => {
+                IStakeConfig::maxValidatorCountCall::abi_decode_returns(&output, false)
+                    .map(|r| r._0)
+            }
+            StakeConfigField::RecurringLockupDuration => {
+                IStakeConfig::recurringLockupDurationCall::abi_decode_returns(&output, false)
+                    .map(|r| r._0)
+            }
+        };
+        value.map_err(|e| format!("Failed to decode {} result: {}", self.name(), e))
+    }
+}
+
+/// Syntax the validator and fullnode network addresses are checked against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum NetworkAddressFormat {
+    /// `/ip4|ip6|dns|dns4|dns6/<host>/tcp/<port>`, optionally followed by
+    /// `/noise-ik/<32 byte hex key>/handshake/<version>`
+    #[default]
+    Multiaddr,
+    /// `<host>:<port>`, with IPv6 hosts in brackets
+    HostPort,
+    /// Any string, left for the node to interpret
+    Any,
+}
+
+impl std::fmt::Display for NetworkAddressFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkAddressFormat::Multiaddr => write!(f, "multiaddr"),
+            NetworkAddressFormat::HostPort => write!(f, "hostPort"),
+            NetworkAddressFormat::Any => write!(f, "any"),
+        }
+    }
+}
+
+impl NetworkAddressFormat {
+    /// Why `address` does not have this format, if it does not
+    pub fn check(self, address: &str) -> Result<(), String> {
+        match self {
+            NetworkAddressFormat::Multiaddr => check_multiaddr(address),
+            NetworkAddressFormat::HostPort => check_host_port(address),
+            NetworkAddressFormat::Any => Ok(()),
+        }
+    }
+}
+
+fn check_multiaddr(address: &str) -> Result<(), String> {
+    let Some(protocols) = address.strip_prefix('/') else {
+        return Err("does not start with /".to_string());
+    };
+    let protocols: Vec<&str> = protocols.split('/').collect();
+    let rest = match protocols.as_slice() {
+        ["ip4", host, rest @ ..] => {
+            host.parse::<std::net::Ipv4Addr>()
+                .map_err(|_| format!("{} is not an IPv4 address", host))?;
+            rest
+        }
+        ["ip6", host, rest @ ..] => {
+            host.parse::<std::net::Ipv6Addr>()
+                .map_err(|_| format!("{} is not an IPv6 address", host))?;
+            rest
+        }
+        ["dns" | "dns4" | "dns6", host, rest @ ..] if !host.is_empty() => rest,
+        _ => return Err("does not start with an ip4, ip6 or dns host".to_string()),
+    };
+    let rest = match rest {
+        ["tcp", port, rest @ ..] => {
+            port.parse::<u16>()
+                .map_err(|_| format!("{} is not a port", port))?;
+            rest
+        }
+        _ => return Err("the host is not followed by /tcp/<port>".to_string()),
+    };
+    match rest {
+        [] => Ok(()),
+        ["noise-ik", key, "handshake", version] => {
+            if key.len() != 64 || hex::decode(key).is_err() {
+                return Err(format!("{} is not a 32 byte hex key", key));
+            }
+            version
+                .parse::<u8>()
+                .map(|_| ())
+                .map_err(|_| format!("{} is not a handshake version", version))
+        }
+        _ => Err("the port is not followed by /noise-ik/<key>/handshake/<version>".to_string()),
+    }
+}
+
+fn check_host_port(address: &str) -> Result<(), String> {
+    if address.parse::<std::net::SocketAddr>().is_ok() {
+        return Ok(());
+    }
+    let Some((host, port)) = address.rsplit_once(':') else {
+        return Err("has no :port".to_string());
+    };
+    port.parse::<u16>()
+        .map_err(|_| format!("{} is not a port", port))?;
+    let valid_host = !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid_host {
+        return Err(format!("{} is not a host name or IP address", host));
+    }
+    Ok(())
+}
+
+/// One genesis validator, the row-oriented entry of `validators`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorSpec {
+    /// May be left out when derived from `validatorPrivateKeys`
+    #[serde(default)]
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = ADDRESS_PATTERN)))]
+    pub address: String,
+    /// BLS12-381 public key as hex, 48 bytes compressed or 96 uncompressed
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = BLS_PUBLIC_KEY_PATTERN)))]
+    pub consensus_public_key: String,
+    /// In ether, converted to wei when initializing
+    pub voting_power: String,
+    pub validator_network_address: String,
+    pub fullnode_network_address: String,
+    pub aptos_address: String,
+    /// Initial commission rate in basis points, `defaultCommissionRate` if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commission_rate: Option<String>,
+}
+
+/// The legacy validator set format, one array per field indexed by validator
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorArrays {
+    #[serde(default)]
     pub validator_addresses: Vec<String>,
-    #[serde(rename = "consensusPublicKeys")]
+    #[serde(default)]
     pub consensus_public_keys: Vec<String>,
-    #[serde(rename = "votingPowers")]
+    #[serde(default)]
     pub voting_powers: Vec<String>,
-    #[serde(rename = "validatorNetworkAddresses")]
+    #[serde(default)]
     pub validator_network_addresses: Vec<String>,
-    #[serde(rename = "fullnodeNetworkAddresses")]
+    #[serde(default)]
     pub fullnode_network_addresses: Vec<String>,
-    #[serde(rename = "aptosAddresses")]
+    #[serde(default)]
     pub aptos_addresses: Vec<String>,
 }
 
+impl ValidatorArrays {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Zip the arrays into one `ValidatorSpec` per validator, rejecting arrays of
+    /// different lengths rather than silently pairing the wrong entries. Without
+    /// `validatorAddresses` the addresses are left empty, to be derived from
+    /// `validatorPrivateKeys`
+    pub fn into_specs(mut self) -> Result<Vec<ValidatorSpec>, GenesisConfigError> {
+        let mut expected_field = "validatorAddresses";
+        if self.validator_addresses.is_empty() {
+            self.validator_addresses = vec![String::new(); self.consensus_public_keys.len()];
+            expected_field = "consensusPublicKeys";
+        }
+        let count = self.validator_addresses.len();
+        let lengths = [
+            ("consensusPublicKeys", self.consensus_public_keys.len()),
+            ("votingPowers", self.voting_powers.len()),
+            (
+                "validatorNetworkAddresses",
+                self.validator_network_addresses.len(),
+            ),
+            (
+                "fullnodeNetworkAddresses",
+                self.fullnode_network_addresses.len(),
+            ),
+            ("aptosAddresses", self.aptos_addresses.len()),
+        ];
+        for (field, len) in lengths {
+            if len != count {
+                return Err(GenesisConfigError::ValidatorArrayLength {
+                    field,
+                    len,
+                    expected_field,
+                    expected: count,
+                });
+            }
+        }
+        Ok(self
+            .validator_addresses
+            .into_iter()
+            .zip(self.consensus_public_keys)
+            .zip(self.voting_powers)
+            .zip(self.validator_network_addresses)
+            .zip(self.fullnode_network_addresses)
+            .zip(self.aptos_addresses)
+            .map(
+                |(
+                    (
+                        (
+                            ((address, consensus_public_key), voting_power),
+                            validator_network_address,
+                        ),
+                        fullnode_network_address,
+                    ),
+                    aptos_address,
+                )| ValidatorSpec {
+                    address,
+                    consensus_public_key,
+                    voting_power,
+                    validator_network_address,
+                    fullnode_network_address,
+                    aptos_address,
+                    commission_rate: None,
+                },
+            )
+            .collect())
+    }
+
+    pub fn from_specs(validators: &[ValidatorSpec]) -> Self {
+        let column = |field: fn(&ValidatorSpec) -> &String| {
+            validators.iter().map(|v| field(v).clone()).collect()
+        };
+        Self {
+            validator_addresses: column(|v| &v.address),
+            consensus_public_keys: column(|v| &v.consensus_public_key),
+            voting_powers: column(|v| &v.voting_power),
+            validator_network_addresses: column(|v| &v.validator_network_address),
+            fullnode_network_addresses: column(|v| &v.fullnode_network_address),
+            aptos_addresses: column(|v| &v.aptos_address),
+        }
+    }
+}
+
+/// One validator of a `validatorsFile`, a CSV row or a JSONL object
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidatorRow {
+    #[serde(default)]
+    address: String,
+    consensus_key: String,
+    voting_power: String,
+    validator_network_addr: String,
+    fullnode_network_addr: String,
+    aptos_address: String,
+    #[serde(default)]
+    commission_rate: Option<String>,
+}
+
+impl From<ValidatorRow> for ValidatorSpec {
+    fn from(row: ValidatorRow) -> Self {
+        Self {
+            address: row.address,
+            consensus_public_key: row.consensus_key,
+            voting_power: row.voting_power,
+            validator_network_address: row.validator_network_addr,
+            fullnode_network_address: row.fullnode_network_addr,
+            aptos_address: row.aptos_address,
+            commission_rate: row.commission_rate.filter(|rate| !rate.is_empty()),
+        }
+    }
+}
+
+/// Parse a validator file, CSV with a header row naming the columns if `path` ends
+/// in `.csv` and otherwise JSONL. Blank lines are skipped, and an error names the
+/// line it is on
+pub fn parse_validators_file(path: &str, content: &str) -> Result<Vec<ValidatorSpec>, String> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+    let is_csv = path.ends_with(".csv");
+    let header: Vec<&str> = if is_csv {
+        match lines.next() {
+            Some((_, line)) => line.split(',').map(str::trim).collect(),
+            None => return Ok(Vec::new()),
+        }
+    } else {
+        Vec::new()
+    };
+    lines
+        .map(|(number, line)| {
+            let row = if is_csv {
+                let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+                if fields.len() != header.len() {
+                    return Err(format!(
+                        "line {}: {} columns but the header has {}",
+                        number,
+                        fields.len(),
+                        header.len()
+                    ));
+                }
+                let row: serde_json::Map<String, serde_json::Value> = header
+                    .iter()
+                    .zip(fields)
+                    .map(|(column, field)| (column.to_string(), field.into()))
+                    .collect();
+                serde_json::from_value::<ValidatorRow>(row.into())
+            } else {
+                serde_json::from_str::<ValidatorRow>(line)
+            };
+            row.map(ValidatorSpec::from)
+                .map_err(|e| format!("line {}: {}", number, e))
+        })
+        .collect()
+}
+
+/// Either validator set format as it appears at the top level of the config
+#[derive(Deserialize)]
+struct ValidatorsField {
+    #[serde(default)]
+    validators: Option<Vec<ValidatorSpec>>,
+    #[serde(flatten)]
+    legacy: ValidatorArrays,
+}
+
+fn deserialize_validators<'de, D>(deserializer: D) -> Result<Vec<ValidatorSpec>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let field = ValidatorsField::deserialize(deserializer)?;
+    match field.validators {
+        Some(_) if !field.legacy.is_empty() => Err(serde::de::Error::custom(
+            "use either validators or the legacy validator arrays, not both",
+        )),
+        Some(validators) => Ok(validators),
+        None => field.legacy.into_specs().map_err(serde::de::Error::custom),
+    }
+}
+
+fn serialize_validators<S>(validators: &[ValidatorSpec], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    #[derive(Serialize)]
+    struct Rows<'a> {
+        validators: &'a [ValidatorSpec],
+    }
+    Rows { validators }.serialize(serializer)
+}
+
+/// Both validator set formats, as the properties flattened into the config
+#[cfg(feature = "schema")]
+fn validators_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    let validators = generator.subschema_for::<Vec<ValidatorSpec>>();
+    let column = generator.subschema_for::<Vec<String>>();
+    schemars::json_schema!({
+        "type": "object",
+        "description": "The validator set, either as `validators` or as the legacy parallel \
+            arrays, which must all hold one entry per validator in the same order. The \
+            two formats cannot be mixed",
+        "properties": {
+            "validators": validators,
+            "validatorAddresses": column,
+            "consensusPublicKeys": column,
+            "votingPowers": column,
+            "validatorNetworkAddresses": column,
+            "fullnodeNetworkAddresses": column,
+            "aptosAddresses": column,
+        },
+    })
+}
+
+/// `premine` as it appears in the config, either a list of entries or the
+/// `initialBalances` style map of address to wei balance
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PremineField {
+    Entries(Vec<PremineEntry>),
+    Balances(BTreeMap<String, String>),
+}
+
+fn deserialize_premine<'de, D>(deserializer: D) -> Result<Vec<PremineEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match PremineField::deserialize(deserializer)? {
+        PremineField::Entries(entries) => entries,
+        PremineField::Balances(balances) => balances
+            .into_iter()
+            .map(|(address, balance)| PremineEntry { address, balance })
+            .collect(),
+    })
+}
+
+/// Both `premine` formats
+#[cfg(feature = "schema")]
+fn premine_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    let entries = generator.subschema_for::<Vec<PremineEntry>>();
+    let balances = generator.subschema_for::<BTreeMap<String, String>>();
+    schemars::json_schema!({
+        "description": "Accounts funded at genesis, either as a list of entries or as a map \
+            of address to wei balance. Also accepted as `initialBalances`",
+        "anyOf": [entries, balances],
+    })
+}
+
+/// JSON Schema of `genesis_config.json`, for validating a config or editor
+/// autocomplete
+#[cfg(feature = "schema")]
+pub fn genesis_config_schema() -> schemars::Schema {
+    schemars::schema_for!(GenesisConfig)
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GenesisConfig {
+    /// Read from either `validators` or the legacy parallel arrays
+    /// (`validatorAddresses`, `consensusPublicKeys`, ...), written as `validators`
+    #[serde(
+        flatten,
+        deserialize_with = "deserialize_validators",
+        serialize_with = "serialize_validators"
+    )]
+    #[cfg_attr(feature = "schema", schemars(schema_with = "validators_schema"))]
+    pub validators: Vec<ValidatorSpec>,
+    /// secp256k1 private keys to derive the validator addresses from, one per
+    /// validator. Never serialized, and dropped by `resolve_addresses`
+    #[serde(rename = "validatorPrivateKeys", default, skip_serializing)]
+    pub validator_private_keys: Vec<String>,
+    /// CSV or JSONL file holding the validator set instead of the config itself,
+    /// relative to the config file. Replaced by its validators in
+    /// `load_validators_file`
+    #[serde(
+        rename = "validatorsFile",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub validators_file: Option<String>,
+    /// Syntax every non-empty validator and fullnode network address must have,
+    /// `multiaddr` if unset
+    #[serde(
+        rename = "networkAddressFormat",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub network_address_format: Option<NetworkAddressFormat>,
+    /// Optional lower bound on the sum of `votingPowers`, in the same units
+    #[serde(
+        rename = "minTotalVotingPower",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_total_voting_power: Option<String>,
+    /// Optional upper bound on the sum of `votingPowers`, in the same units
+    #[serde(
+        rename = "maxTotalVotingPower",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_total_voting_power: Option<String>,
+    /// Commission rate in basis points of every validator that sets no
+    /// `commissionRate`, 0 if unset
+    #[serde(
+        rename = "defaultCommissionRate",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_commission_rate: Option<String>,
+    /// Accounts funded at genesis, independently of any validator stake. Read from
+    /// either a list of entries or a map of address to wei balance, also under the
+    /// name `initialBalances`, and written as the list
+    #[serde(
+        alias = "initialBalances",
+        default,
+        deserialize_with = "deserialize_premine",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    #[cfg_attr(feature = "schema", schemars(schema_with = "premine_schema"))]
+    pub premine: Vec<PremineEntry>,
+    /// Accept premine entries for validator addresses instead of rejecting them
+    #[serde(
+        rename = "allowValidatorPremine",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub allow_validator_premine: bool,
+    /// Wei credited to `DEAD_ADDRESS` at genesis, minted and burned in the same step
+    #[serde(
+        rename = "burnAmount",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub burn_amount: Option<String>,
+    /// Slots overwritten in the final state, e.g. to build test fixtures
+    #[serde(
+        rename = "storageOverrides",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub storage_overrides: Vec<StorageOverride>,
+    /// Staking parameters the generated state is expected to hold. They are only
+    /// compared with what initialization stored, never applied
+    #[serde(
+        rename = "expectedStakeConfig",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expected_stake_config: Option<StakeConfigParams>,
+    /// User contracts deployed and funded alongside the system contracts
+    #[serde(
+        rename = "extraContracts",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub extra_contracts: Vec<ExtraContract>,
+    /// Chain id of the EVM env genesis is generated under, mainnet if unset
+    #[serde(rename = "chainId", default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    /// Gas limit of each contract creation and initialization transaction,
+    /// `DEFAULT_GAS_LIMIT` if unset, or `UNBOUNDED_GAS_LIMIT` for no limit
+    #[serde(
+        rename = "genesisGasLimit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub genesis_gas_limit: Option<u64>,
+    /// Unix time in seconds the genesis transactions see as `block.timestamp`, the
+    /// time of generation if unset
+    #[serde(
+        rename = "genesisTimestamp",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub genesis_timestamp: Option<u64>,
+    /// Caller of every genesis transaction, for contracts compiled against a system
+    /// caller other than `SYSTEM_CALLER`, which is used if unset
+    #[serde(
+        rename = "systemCaller",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = ADDRESS_PATTERN)))]
+    pub system_caller: Option<String>,
+    /// `block.coinbase` of the genesis transactions, the zero address if unset
+    #[serde(rename = "coinbase", default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = ADDRESS_PATTERN)))]
+    pub coinbase: Option<String>,
+    /// `block.basefee` of the genesis transactions in wei, zero if unset
+    #[serde(rename = "baseFee", default, skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<u64>,
+    /// Hardfork the genesis transactions execute under, such as "cancun", the latest
+    /// supported one if unset
+    #[serde(rename = "hardfork", default, skip_serializing_if = "Option::is_none")]
+    pub hardfork: Option<String>,
+    /// Hashes `BLOCKHASH` returns for the given block numbers during initialization,
+    /// as decimal block number to hex hash
+    #[serde(
+        rename = "genesisBlockHashes",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub genesis_block_hashes: BTreeMap<String, String>,
+    /// `block.number` of the genesis transactions, one past the highest
+    /// `genesisBlockHashes` entry if unset, or 0 without any
+    #[serde(
+        rename = "genesisBlockNumber",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub genesis_block_number: Option<u64>,
+    /// Construct the system contracts through `CREATE2_DEPLOYER` instead of plain
+    /// CREATE transactions, so their creation addresses do not depend on the order
+    /// they are deployed in
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub create2: bool,
+    /// CREATE2 salt of a system contract as contract name to 32 byte hex, for
+    /// contracts not keeping the default keccak256 of their name
+    #[serde(
+        rename = "create2Salts",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub create2_salts: BTreeMap<String, String>,
+    /// Nonce of the system caller before its first contract creation, 1 if unset.
+    /// Each system contract is created at the next nonce in construct mode
+    #[serde(
+        rename = "deployerNonce",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub deployer_nonce: Option<u64>,
+    /// CREATE address a system contract must be created at in construct mode, as
+    /// contract name to address, for addresses precomputed off-chain
+    #[serde(
+        rename = "expectedCreateAddresses",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub expected_create_addresses: BTreeMap<String, String>,
+    /// Address of a system contract as contract name to address, in place of its
+    /// `CONTRACTS` constant, for chains with another address layout
+    #[serde(
+        rename = "contractAddressOverrides",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub contract_address_overrides: BTreeMap<String, String>,
+}
+
+/// Reasons a `GenesisConfig` is rejected before any contract is deployed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenesisConfigError {
+    /// Two validators announce the same network address
+    DuplicateNetworkAddress {
+        field: &'static str,
+        address: String,
+        first_index: usize,
+        second_index: usize,
+    },
+    /// A network address does not have the syntax of `networkAddressFormat`
+    InvalidNetworkAddress {
+        field: &'static str,
+        index: usize,
+        address: String,
+        format: NetworkAddressFormat,
+        reason: String,
+    },
+    /// Two validators have the same address, without `--allow-duplicate-validators`
+    DuplicateValidatorAddress {
+        address: Address,
+        first_index: usize,
+        second_index: usize,
+    },
+    /// A validator repeating an earlier address differs from that entry in more than
+    /// its voting power, so merging the two would drop `field`
+    ConflictingDuplicateValidator {
+        address: Address,
+        first_index: usize,
+        second_index: usize,
+        field: &'static str,
+    },
+    /// A voting power entry is not a valid unsigned integer
+    InvalidVotingPower { index: usize, value: String },
+    /// A legacy validator array does not have one entry per validator, as counted by
+    /// `expected_field`: `validatorAddresses`, or `consensusPublicKeys` when the
+    /// addresses are left to be derived
+    ValidatorArrayLength {
+        field: &'static str,
+        len: usize,
+        expected_field: &'static str,
+        expected: usize,
+    },
+    /// A commission rate is not an integer number of basis points up to 10000
+    InvalidCommissionRate { field: String, value: String },
+    /// A `genesisBlockHashes` entry has an unparsable block number or hash
+    InvalidBlockHash { number: String, hash: String },
+    /// `hardfork` names no hardfork this tool can execute under
+    InvalidHardfork { value: String },
+    /// A configured voting power bound is not a valid unsigned integer
+    InvalidVotingPowerBound { field: &'static str, value: String },
+    /// The voting powers no longer fit in a U256, in wei or summed, from the
+    /// validator at `index` on
+    VotingPowerOverflow { index: usize },
+    /// The summed voting power is below `minTotalVotingPower`
+    TotalVotingPowerBelowMin { total: U256, min: U256 },
+    /// The summed voting power exceeds `maxTotalVotingPower`, first doing so at the
+    /// validator at `index`
+    TotalVotingPowerAboveMax {
+        total: U256,
+        max: U256,
+        index: usize,
+    },
+    /// A premine entry has an unparsable address or balance
+    InvalidPremine {
+        index: usize,
+        field: &'static str,
+        value: String,
+    },
+    /// `burnAmount` is not a valid unsigned integer
+    InvalidBurnAmount { value: String },
+    /// A premine entry funds an address an earlier entry already funds
+    DuplicatePremine { index: usize, address: Address },
+    /// `DEAD_ADDRESS` is premined while `burnAmount` also funds it
+    BurnAddressPremined,
+    /// A storage override has an unparsable address, slot or value
+    InvalidStorageOverride {
+        index: usize,
+        field: &'static str,
+        value: String,
+    },
+    /// A consensus public key is not hex of a BLS12-381 public key's length or, with
+    /// the `bls` feature, not a valid G1 point in the prime order subgroup
+    InvalidConsensusPublicKey { index: usize, reason: String },
+    /// A validator address is not a 20 byte hex address
+    InvalidValidatorAddress { index: usize, value: String },
+    /// An aptos address is not 32 bytes of hex
+    InvalidAptosAddress { index: usize, value: String },
+    /// A validator address is not the last 20 bytes of its aptos address
+    AptosAddressMismatch {
+        index: usize,
+        address: Address,
+        aptos_address: String,
+    },
+    /// `validatorsFile` cannot be read or parsed, or was never loaded
+    InvalidValidatorsFile { path: String, reason: String },
+    /// `validatorsFile` is given next to validators in the config itself
+    ValidatorsFileWithInline,
+    /// `validatorPrivateKeys` does not have one entry per validator
+    ValidatorPrivateKeyCount { len: usize, expected: usize },
+    /// A validator private key is not a secp256k1 key, the key itself left out
+    InvalidValidatorPrivateKey { index: usize, reason: String },
+    /// A validator address is given and differs from the one its private key derives
+    ValidatorAddressMismatch {
+        index: usize,
+        address: String,
+        derived: Address,
+    },
+    /// Validator addresses that are also premined, without `allowValidatorPremine`
+    PremineOverlapsValidators { addresses: Vec<Address> },
+    /// An `expectedStakeConfig` value is not a valid unsigned integer
+    InvalidStakeConfig { field: &'static str, value: String },
+    /// An extra contract has an unparsable address, balance or constructor arguments
+    InvalidExtraContract {
+        index: usize,
+        field: &'static str,
+        value: String,
+    },
+    /// An extra contract would be placed at an address already in use
+    ExtraContractAddressTaken {
+        name: String,
+        address: Address,
+        taken_by: String,
+    },
+    /// A `create2Salts` entry names no system contract or has an unparsable salt
+    InvalidCreate2Salt { name: String, salt: String },
+    /// `systemCaller` is not an address, or is the address of a system contract
+    InvalidSystemCaller { value: String, reason: &'static str },
+    /// `coinbase` is not a 20 byte hex address
+    InvalidCoinbase { value: String },
+    /// An `expectedCreateAddresses` entry names no system contract or has an
+    /// unparsable address
+    InvalidExpectedCreateAddress { name: String, address: String },
+    /// `expectedCreateAddresses` is given with `create2`, which creates no contract
+    /// at a CREATE address
+    ExpectedCreateAddressesWithCreate2,
+    /// A system contract would be created at another address than the expected one,
+    /// given the system caller and `deployerNonce`
+    CreateAddressMismatch {
+        name: String,
+        expected: Address,
+        computed: Address,
+    },
+    /// A `contractAddressOverrides` entry names no system contract or has an
+    /// unparsable or zero address
+    InvalidContractAddressOverride { name: String, address: String },
+    /// Two system contracts, or a system contract and a reserved address, share an
+    /// address once `contractAddressOverrides` is applied
+    ContractAddressCollision {
+        first: String,
+        second: String,
+        address: Address,
+    },
+}
+
+impl std::fmt::Display for GenesisConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenesisConfigError::DuplicateNetworkAddress {
+                field,
+                address,
+                first_index,
+                second_index,
+            } => write!(
+                f,
+                "{} entries {} and {} share the same address: {}",
+                field, first_index, second_index, address
+            ),
+            GenesisConfigError::InvalidNetworkAddress {
+                field,
+                index,
+                address,
+                format,
+                reason,
+            } => write!(
+                f,
+                "{}[{}] is not a {} address, {}: {:?}",
+                field, index, format, reason, address
+            ),
+            GenesisConfigError::DuplicateValidatorAddress {
+                address,
+                first_index,
+                second_index,
+            } => write!(
+                f,
+                "validators {} and {} share the address {} (pass --allow-duplicate-validators to merge their voting powers)",
+                first_index, second_index, address
+            ),
+            GenesisConfigError::ConflictingDuplicateValidator {
+                address,
+                first_index,
+                second_index,
+                field,
+            } => write!(
+                f,
+                "validators {} and {} share the address {} but differ in {}, so they cannot be merged",
+                first_index, second_index, address, field
+            ),
+            GenesisConfigError::ValidatorArrayLength {
+                field,
+                len,
+                expected_field,
+                expected,
+            } => write!(
+                f,
+                "{} has {} entries but {} has {}",
+                field, len, expected_field, expected
+            ),
+            GenesisConfigError::InvalidVotingPower { index, value } => {
+                write!(
+                    f,
+                    "votingPowers[{}] is not a valid integer: {:?}",
+                    index, value
+                )
+            }
+            GenesisConfigError::InvalidCommissionRate { field, value } => write!(
+                f,
+                "{} is not a commission rate in basis points (0 to {}): {:?}",
+                field, COMMISSION_RATE_BASE, value
+            ),
+            GenesisConfigError::InvalidBlockHash { number, hash } => write!(
+                f,
+                "genesisBlockHashes entry {:?}: {:?} is not a block number and 32 byte hash",
+                number, hash
+            ),
+            GenesisConfigError::InvalidHardfork { value } => write!(
+                f,
+                "unknown hardfork {:?}, expected one of {}",
+                value,
+                HARDFORKS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            GenesisConfigError::InvalidVotingPowerBound { field, value } => {
+                write!(f, "{} is not a valid integer: {:?}", field, value)
+            }
+            GenesisConfigError::VotingPowerOverflow { index } => write!(
+                f,
+                "votingPowers[{}] overflows U256 in wei or in the total",
+                index
+            ),
+            GenesisConfigError::TotalVotingPowerBelowMin { total, min } => write!(
+                f,
+                "total voting power {} is below minTotalVotingPower {}",
+                total, min
+            ),
+            GenesisConfigError::TotalVotingPowerAboveMax { total, max, index } => write!(
+                f,
+                "total voting power {} exceeds maxTotalVotingPower {}, from votingPowers[{}] on",
+                total, max, index
+            ),
+            GenesisConfigError::InvalidPremine {
+                index,
+                field,
+                value,
+            } => write!(f, "premine[{}].{} is invalid: {:?}", index, field, value),
+            GenesisConfigError::InvalidBurnAmount { value } => {
+                write!(f, "burnAmount is not a valid integer: {:?}", value)
+            }
+            GenesisConfigError::DuplicatePremine { index, address } => write!(
+                f,
+                "premine[{}] funds {}, which an earlier premine entry already funds",
+                index,
+                ChecksumAddress(*address)
+            ),
+            GenesisConfigError::BurnAddressPremined => write!(
+                f,
+                "premine funds {}, which burnAmount already sets",
+                ChecksumAddress(DEAD_ADDRESS)
+            ),
+            GenesisConfigError::InvalidStorageOverride {
+                index,
+                field,
+                value,
+            } => write!(
+                f,
+                "storageOverrides[{}].{} is invalid: {:?}",
+                index, field, value
+            ),
+            GenesisConfigError::InvalidConsensusPublicKey { index, reason } => {
+                write!(f, "consensusPublicKeys[{}] is invalid: {}", index, reason)
+            }
+            GenesisConfigError::InvalidValidatorAddress { index, value } => write!(
+                f,
+                "validatorAddresses[{}] is not a 20 byte hex address: {:?}",
+                index, value
+            ),
+            GenesisConfigError::ValidatorPrivateKeyCount { len, expected } => write!(
+                f,
+                "validatorPrivateKeys has {} entries but there are {} validators",
+                len, expected
+            ),
+            GenesisConfigError::InvalidValidatorPrivateKey { index, reason } => {
+                write!(f, "validatorPrivateKeys[{}] is invalid: {}", index, reason)
+            }
+            GenesisConfigError::ValidatorAddressMismatch {
+                index,
+                address,
+                derived,
+            } => write!(
+                f,
+                "validatorAddresses[{}] is {}, but its private key derives {}",
+                index, address, derived
+            ),
+            GenesisConfigError::PremineOverlapsValidators { addresses } => write!(
+                f,
+                "premine addresses are also validators (set allowValidatorPremine to accept): {}",
+                addresses
+                    .iter()
+                    .map(|address| ChecksumAddress(*address).to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            GenesisConfigError::InvalidStakeConfig { field, value } => {
+                write!(
+                    f,
+                    "expectedStakeConfig.{} is not a valid integer: {:?}",
+                    field, value
+                )
+            }
+            GenesisConfigError::InvalidExtraContract {
+                index,
+                field,
+                value,
+            } => write!(
+                f,
+                "extraContracts[{}].{} is invalid: {:?}",
+                index, field, value
+            ),
+            GenesisConfigError::ExtraContractAddressTaken {
+                name,
+                address,
+                taken_by,
+            } => write!(
+                f,
+                "extra contract {} cannot be placed at {}, already used by {}",
+                name,
+                ChecksumAddress(*address),
+                taken_by
+            ),
+            GenesisConfigError::InvalidCreate2Salt { name, salt } => write!(
+                f,
+                "create2Salts entry {:?}: {:?} is not a system contract and 32 byte salt",
+                name, salt
+            ),
+            GenesisConfigError::InvalidSystemCaller { value, reason } => {
+                write!(f, "systemCaller {:?} {}", value, reason)
+            }
+            GenesisConfigError::InvalidCoinbase { value } => {
+                write!(f, "coinbase {:?} is not a 20 byte hex address", value)
+            }
+            GenesisConfigError::InvalidValidatorsFile { path, reason } => {
+                write!(f, "validatorsFile {}: {}", path, reason)
+            }
+            GenesisConfigError::ValidatorsFileWithInline => write!(
+                f,
+                "use either validatorsFile or validators in the config, not both"
+            ),
+            GenesisConfigError::InvalidAptosAddress { index, value } => {
+                write!(
+                    f,
+                    "aptosAddresses[{}] is not 32 bytes of hex: {:?}",
+                    index, value
+                )
+            }
+            GenesisConfigError::AptosAddressMismatch {
+                index,
+                address,
+                aptos_address,
+            } => write!(
+                f,
+                "validators[{}] address {} is not the last 20 bytes of its aptosAddress {}",
+                index,
+                ChecksumAddress(*address),
+                aptos_address
+            ),
+            GenesisConfigError::InvalidExpectedCreateAddress { name, address } => write!(
+                f,
+                "expectedCreateAddresses entry {:?}: {:?} is not a system contract and address",
+                name, address
+            ),
+            GenesisConfigError::ExpectedCreateAddressesWithCreate2 => write!(
+                f,
+                "expectedCreateAddresses cannot be checked with create2, which does not create at CREATE addresses"
+            ),
+            GenesisConfigError::CreateAddressMismatch {
+                name,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "{} would be created at {} instead of the expected {} (check deployerNonce and systemCaller)",
+                name, computed, expected
+            ),
+            GenesisConfigError::InvalidContractAddressOverride { name, address } => write!(
+                f,
+                "contractAddressOverrides entry {:?}: {:?} is not a system contract and non-zero 20 byte address",
+                name, address
+            ),
+            GenesisConfigError::ContractAddressCollision {
+                first,
+                second,
+                address,
+            } => write!(
+                f,
+                "{} and {} share the address {} with contractAddressOverrides applied",
+                first,
+                second,
+                ChecksumAddress(*address)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenesisConfigError {}
+
+/// Find the first pair of indices holding the same non-empty network address
+fn find_duplicate_network_address<'a>(
+    field: &'static str,
+    addresses: impl Iterator<Item = &'a str>,
+) -> Result<(), GenesisConfigError> {
+    let mut seen = std::collections::HashMap::new();
+    for (index, address) in addresses.enumerate() {
+        if address.is_empty() {
+            continue;
+        }
+        if let Some(first_index) = seen.insert(address, index) {
+            return Err(GenesisConfigError::DuplicateNetworkAddress {
+                field,
+                address: address.to_string(),
+                first_index,
+                second_index: index,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Later indices of validators whose address, compared case-insensitively, already
+/// appeared, each paired with the index of its first appearance. Unparsable
+/// addresses are left to `validate_validator_keys`
+fn duplicate_validator_addresses(validators: &[ValidatorSpec]) -> Vec<(Address, usize, usize)> {
+    let mut seen = std::collections::HashMap::new();
+    validators
+        .iter()
+        .enumerate()
+        .filter_map(|(index, validator)| {
+            let address = validator.address.parse::<Address>().ok()?;
+            let first_index = *seen.entry(address).or_insert(index);
+            (first_index != index).then_some((address, first_index, index))
+        })
+        .collect()
+}
+
+fn parse_voting_power_bound(
+    field: &'static str,
+    value: &Option<String>,
+) -> Result<Option<U256>, GenesisConfigError> {
+    value
+        .as_ref()
+        .map(|value| {
+            value
+                .parse::<U256>()
+                .map_err(|_| GenesisConfigError::InvalidVotingPowerBound {
+                    field,
+                    value: value.clone(),
+                })
+        })
+        .transpose()
+}
+
+/// Hardfork names accepted in `hardfork`, matched case-insensitively
+pub const HARDFORKS: &[(&str, SpecId)] = &[
+    ("berlin", SpecId::BERLIN),
+    ("london", SpecId::LONDON),
+    ("merge", SpecId::MERGE),
+    ("paris", SpecId::MERGE),
+    ("shanghai", SpecId::SHANGHAI),
+    ("cancun", SpecId::CANCUN),
+    ("prague", SpecId::PRAGUE),
+    ("latest", SpecId::LATEST),
+];
+
+impl GenesisConfig {
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id.unwrap_or(DEFAULT_CHAIN_ID)
+    }
+
+    /// Gas limit of every system transaction genesis runs, `DEFAULT_GAS_LIMIT` if unset
+    pub fn gas_limit(&self) -> u64 {
+        self.genesis_gas_limit.unwrap_or(DEFAULT_GAS_LIMIT)
+    }
+
+    /// `block.timestamp` of the genesis transactions, the current time if
+    /// `genesisTimestamp` is unset
+    pub fn block_timestamp(&self) -> u64 {
+        self.genesis_timestamp.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs())
+        })
+    }
+
+    /// Spec the genesis transactions execute under, from `hardfork`
+    pub fn parsed_spec_id(&self) -> Result<SpecId, GenesisConfigError> {
+        let Some(hardfork) = &self.hardfork else {
+            return Ok(SpecId::LATEST);
+        };
+        // revm's own name lookup falls back to LATEST, which would hide typos
+        HARDFORKS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(hardfork.trim()))
+            .map(|(_, spec_id)| *spec_id)
+            .ok_or_else(|| GenesisConfigError::InvalidHardfork {
+                value: hardfork.clone(),
+            })
+    }
+
+    /// Like `parsed_spec_id`, for configs that already passed `validate`
+    pub fn spec_id(&self) -> SpecId {
+        self.parsed_spec_id()
+            .unwrap_or_else(|e| panic!("Invalid genesis config: {}", e))
+    }
+
+    /// Caller of the genesis transactions, from `systemCaller`. It sends transactions,
+    /// so it cannot be a system contract, which would carry code at genesis
+    pub fn parsed_system_caller(&self) -> Result<Address, GenesisConfigError> {
+        let Some(value) = &self.system_caller else {
+            return Ok(SYSTEM_CALLER);
+        };
+        let invalid = |reason| GenesisConfigError::InvalidSystemCaller {
+            value: value.clone(),
+            reason,
+        };
+        let address = value
+            .trim()
+            .parse::<Address>()
+            .map_err(|_| invalid("is not a 20 byte hex address"))?;
+        if CONTRACTS.iter().any(|(_, contract)| *contract == address) {
+            return Err(invalid("is the address of a system contract"));
+        }
+        Ok(address)
+    }
+
+    /// Like `parsed_system_caller`, for configs that already passed `validate`
+    pub fn system_caller(&self) -> Address {
+        self.parsed_system_caller()
+            .unwrap_or_else(|e| panic!("Invalid genesis config: {}", e))
+    }
+
+    /// `block.coinbase` of the genesis transactions, from `coinbase`
+    pub fn parsed_coinbase(&self) -> Result<Address, GenesisConfigError> {
+        let Some(value) = &self.coinbase else {
+            return Ok(Address::ZERO);
+        };
+        value
+            .trim()
+            .parse()
+            .map_err(|_| GenesisConfigError::InvalidCoinbase {
+                value: value.clone(),
+            })
+    }
+
+    /// Like `parsed_coinbase`, for configs that already passed `validate`
+    pub fn coinbase(&self) -> Address {
+        self.parsed_coinbase()
+            .unwrap_or_else(|e| panic!("Invalid genesis config: {}", e))
+    }
+
+    /// Voting power of every validator, in config units (ether)
+    pub fn voting_powers(&self) -> Result<Vec<U256>, GenesisConfigError> {
+        self.validators
+            .iter()
+            .enumerate()
+            .map(|(index, validator)| {
+                validator.voting_power.parse::<U256>().map_err(|_| {
+                    GenesisConfigError::InvalidVotingPower {
+                        index,
+                        value: validator.voting_power.clone(),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Voting power of every validator in wei, as `Genesis.initialize` takes it,
+    /// checking neither a single power nor their sum overflows
+    pub fn voting_powers_wei(&self) -> Result<Vec<U256>, GenesisConfigError> {
+        let wei_per_ether = U256::from(10).pow(U256::from(18));
+        let mut total = U256::ZERO;
+        self.voting_powers()?
+            .into_iter()
+            .enumerate()
+            .map(|(index, power)| {
+                let overflow = GenesisConfigError::VotingPowerOverflow { index };
+                let power = power.checked_mul(wei_per_ether).ok_or(overflow.clone())?;
+                total = total.checked_add(power).ok_or(overflow)?;
+                Ok(power)
+            })
+            .collect()
+    }
+
+    /// Sum of all validator voting powers, in config units
+    pub fn total_voting_power(&self) -> Result<U256, GenesisConfigError> {
+        let mut total = U256::ZERO;
+        for (index, power) in self.voting_powers()?.into_iter().enumerate() {
+            total = total
+                .checked_add(power)
+                .ok_or(GenesisConfigError::VotingPowerOverflow { index })?;
+        }
+        Ok(total)
+    }
+
+    /// Index of the validator whose voting power takes the running total over `max`
+    fn validator_over_total(&self, max: U256) -> Result<usize, GenesisConfigError> {
+        let mut total = U256::ZERO;
+        for (index, power) in self.voting_powers()?.into_iter().enumerate() {
+            total = total.saturating_add(power);
+            if total > max {
+                return Ok(index);
+            }
+        }
+        Ok(self.validators.len().saturating_sub(1))
+    }
+
+    /// Commission rate of every validator in basis points, its `commissionRate` or
+    /// else `defaultCommissionRate`
+    pub fn commission_rates(&self) -> Result<Vec<u64>, GenesisConfigError> {
+        let parse = |field: String, value: &String| {
+            value
+                .parse::<u64>()
+                .ok()
+                .filter(|rate| *rate <= COMMISSION_RATE_BASE)
+                .ok_or_else(|| GenesisConfigError::InvalidCommissionRate {
+                    field,
+                    value: value.clone(),
+                })
+        };
+        let default_rate = match &self.default_commission_rate {
+            Some(value) => parse("defaultCommissionRate".to_string(), value)?,
+            None => 0,
+        };
+        self.validators
+            .iter()
+            .enumerate()
+            .map(|(index, validator)| match &validator.commission_rate {
+                Some(value) => parse(format!("validators[{}].commissionRate", index), value),
+                None => Ok(default_rate),
+            })
+            .collect()
+    }
+
+    /// Log the address, voting power and commission rate of every validator, and
+    /// their total voting power, for configs that already passed `validate`
+    pub fn summarize(&self) {
+        let voting_powers = self
+            .voting_powers()
+            .unwrap_or_else(|e| panic!("Invalid genesis config: {}", e));
+        let commission_rates = self
+            .commission_rates()
+            .unwrap_or_else(|e| panic!("Invalid genesis config: {}", e));
+        info!("=== Genesis config summary ===");
+        info!(
+            "Chain id: {}, hardfork: {:?}",
+            self.chain_id(),
+            self.spec_id()
+        );
+        for (index, ((validator, voting_power), commission_rate)) in self
+            .validators
+            .iter()
+            .zip(voting_powers)
+            .zip(commission_rates)
+            .enumerate()
+        {
+            info!(
+                "Validator {}: {}, voting power {}, commission {} bps",
+                index, validator.address, voting_power, commission_rate
+            );
+        }
+        info!(
+            "{} validators, total voting power {}",
+            self.validators.len(),
+            self.total_voting_power()
+                .unwrap_or_else(|e| panic!("Invalid genesis config: {}", e))
+        );
+    }
+
+    /// Parsed `genesisBlockHashes` entries, in block number order
+    pub fn parsed_block_hashes(&self) -> Result<Vec<(u64, B256)>, GenesisConfigError> {
+        let mut block_hashes = self
+            .genesis_block_hashes
+            .iter()
+            .map(|(number, hash)| {
+                let parsed = number.parse::<u64>().ok().zip(hash.parse::<B256>().ok());
+                parsed.ok_or_else(|| GenesisConfigError::InvalidBlockHash {
+                    number: number.clone(),
+                    hash: hash.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        block_hashes.sort();
+        Ok(block_hashes)
+    }
+
+    /// `block.number` the genesis transactions run at, so that `BLOCKHASH` can read
+    /// the configured `genesisBlockHashes`
+    pub fn parsed_block_number(&self) -> Result<u64, GenesisConfigError> {
+        if let Some(number) = self.genesis_block_number {
+            return Ok(number);
+        }
+        Ok(self
+            .parsed_block_hashes()?
+            .last()
+            .map_or(0, |(number, _)| number.saturating_add(1)))
+    }
+
+    /// CREATE2 salt of every system contract, in `CONTRACTS` order: the
+    /// `create2Salts` entry if there is one, keccak256 of the contract name otherwise
+    pub fn create2_salts(&self) -> Result<Vec<B256>, GenesisConfigError> {
+        for (name, salt) in &self.create2_salts {
+            let known = CONTRACTS.iter().any(|(contract, _)| contract == name);
+            if !known || salt.parse::<B256>().is_err() {
+                return Err(GenesisConfigError::InvalidCreate2Salt {
+                    name: name.clone(),
+                    salt: salt.clone(),
+                });
+            }
+        }
+        Ok(CONTRACTS
+            .iter()
+            .map(|(name, _)| match self.create2_salts.get(*name) {
+                Some(salt) => salt.parse().expect("checked above"),
+                None => keccak256(name.as_bytes()),
+            })
+            .collect())
+    }
+
+    /// Account the system caller deploys from: `SYSTEM_ACCOUNT_INFO` at `deployerNonce`
+    pub fn system_account_info(&self) -> AccountInfo {
+        AccountInfo {
+            nonce: self.deployer_nonce.unwrap_or(SYSTEM_ACCOUNT_INFO.nonce),
+            ..SYSTEM_ACCOUNT_INFO
+        }
+    }
+
+    /// Every system contract with its address, in `CONTRACTS` order: the
+    /// `contractAddressOverrides` entry if there is one, the constant otherwise
+    pub fn parsed_contract_addresses(
+        &self,
+    ) -> Result<Vec<(&'static str, Address)>, GenesisConfigError> {
+        let mut overrides = std::collections::HashMap::new();
+        for (name, value) in &self.contract_address_overrides {
+            let contract = CONTRACTS.iter().find(|(contract, _)| contract == name);
+            let address = value.trim().parse::<Address>().ok();
+            match (contract, address) {
+                (Some((contract, _)), Some(address)) if !address.is_zero() => {
+                    overrides.insert(*contract, address);
+                }
+                _ => {
+                    return Err(GenesisConfigError::InvalidContractAddressOverride {
+                        name: name.clone(),
+                        address: value.clone(),
+                    });
+                }
+            }
+        }
+        let contracts: Vec<(&'static str, Address)> = CONTRACTS
+            .iter()
+            .map(|(name, address)| (*name, overrides.get(name).copied().unwrap_or(*address)))
+            .collect();
+
+        // The configured system caller is checked against the constants already
+        let system_caller = self.parsed_system_caller()?;
+        let callers = [("the system caller", system_caller)];
+        let callers = if system_caller == SYSTEM_CALLER {
+            &callers[..0]
+        } else {
+            &callers[..]
+        };
+        let mut seen = std::collections::HashMap::new();
+        for (name, address) in contracts.iter().chain(&RESERVED_ADDRESSES).chain(callers) {
+            if let Some(first) = seen.insert(*address, *name) {
+                return Err(GenesisConfigError::ContractAddressCollision {
+                    first: first.to_string(),
+                    second: name.to_string(),
+                    address: *address,
+                });
+            }
+        }
+        Ok(contracts)
+    }
+
+    /// Like `parsed_contract_addresses`, for configs that already passed `validate`
+    pub fn contract_addresses(&self) -> Vec<(&'static str, Address)> {
+        self.parsed_contract_addresses()
+            .unwrap_or_else(|e| panic!("Invalid genesis config: {}", e))
+    }
+
+    /// Address of the system contract `name`, for configs that already passed `validate`
+    pub fn contract_address(&self, name: &str) -> Address {
+        self.contract_addresses()
+            .into_iter()
+            .find(|(contract, _)| *contract == name)
+            .map(|(_, address)| address)
+            .unwrap_or_else(|| panic!("{} is not a system contract", name))
+    }
+
+    /// `tx`, built against the `CONTRACTS` constants, sent to the configured address
+    /// of the system contract it calls
+    pub fn retarget_system_call(&self, tx: TxEnv) -> TxEnv {
+        if self.contract_address_overrides.is_empty() {
+            return tx;
+        }
+        with_contract_addresses(tx, &self.contract_addresses())
+    }
+
+    /// CREATE address of every system contract in construct mode, in `CONTRACTS`
+    /// order, as each creation takes the next nonce of the system caller
+    pub fn create_addresses(&self) -> Vec<Address> {
+        let caller = self.system_caller();
+        let nonce = self.system_account_info().nonce;
+        (0..CONTRACTS.len() as u64)
+            .map(|index| caller.create(nonce.saturating_add(index)))
+            .collect()
+    }
+
+    /// Compare every `expectedCreateAddresses` entry with its computed CREATE address
+    pub fn check_create_addresses(&self) -> Result<(), GenesisConfigError> {
+        if self.expected_create_addresses.is_empty() {
+            return Ok(());
+        }
+        if self.create2 {
+            return Err(GenesisConfigError::ExpectedCreateAddressesWithCreate2);
+        }
+        let computed = self.create_addresses();
+        for (name, address) in &self.expected_create_addresses {
+            let index = CONTRACTS.iter().position(|(contract, _)| contract == name);
+            let (Some(index), Ok(expected)) = (index, address.parse::<Address>()) else {
+                return Err(GenesisConfigError::InvalidExpectedCreateAddress {
+                    name: name.clone(),
+                    address: address.clone(),
+                });
+            };
+            if computed[index] != expected {
+                return Err(GenesisConfigError::CreateAddressMismatch {
+                    name: name.clone(),
+                    expected,
+                    computed: computed[index],
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Parsed `premine` entries, in config order
+    pub fn premine_balances(&self) -> Result<Vec<(Address, U256)>, GenesisConfigError> {
+        let balances = self.premine_entries()?;
+        // Two balances for one account would silently keep only the last
+        for (index, (address, _)) in balances.iter().enumerate() {
+            if balances[..index]
+                .iter()
+                .any(|(premined, _)| premined == address)
+            {
+                return Err(GenesisConfigError::DuplicatePremine {
+                    index,
+                    address: *address,
+                });
+            }
+        }
+        Ok(balances)
+    }
+
+    fn premine_entries(&self) -> Result<Vec<(Address, U256)>, GenesisConfigError> {
+        self.premine
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let address = entry.address.parse::<Address>().map_err(|_| {
+                    GenesisConfigError::InvalidPremine {
+                        index,
+                        field: "address",
+                        value: entry.address.clone(),
+                    }
+                })?;
+                let balance = entry.balance.parse::<U256>().map_err(|_| {
+                    GenesisConfigError::InvalidPremine {
+                        index,
+                        field: "balance",
+                        value: entry.balance.clone(),
+                    }
+                })?;
+                Ok((address, balance))
+            })
+            .collect()
+    }
+
+    /// Parsed `burnAmount`
+    pub fn parsed_burn_amount(&self) -> Result<Option<U256>, GenesisConfigError> {
+        self.burn_amount
+            .as_ref()
+            .map(|value| {
+                value
+                    .parse::<U256>()
+                    .map_err(|_| GenesisConfigError::InvalidBurnAmount {
+                        value: value.clone(),
+                    })
+            })
+            .transpose()
+    }
+
+    /// Every balance set before the genesis transactions run: the premine, followed by
+    /// the burned amount at `DEAD_ADDRESS`
+    pub fn genesis_balances(&self) -> Result<Vec<(Address, U256)>, GenesisConfigError> {
+        let mut balances = self.premine_balances()?;
+        if let Some(burn_amount) = self.parsed_burn_amount()? {
+            if balances.iter().any(|(address, _)| *address == DEAD_ADDRESS) {
+                return Err(GenesisConfigError::BurnAddressPremined);
+            }
+            balances.push((DEAD_ADDRESS, burn_amount));
+        }
+        Ok(balances)
+    }
+
+    /// Parsed `storageOverrides` entries as `(address, slot, value)`
+    pub fn parsed_storage_overrides(
+        &self,
+    ) -> Result<Vec<(Address, U256, U256)>, GenesisConfigError> {
+        self.storage_overrides
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let invalid = |field: &'static str, value: &String| {
+                    GenesisConfigError::InvalidStorageOverride {
+                        index,
+                        field,
+                        value: value.clone(),
+                    }
+                };
+                let address = entry
+                    .address
+                    .parse::<Address>()
+                    .map_err(|_| invalid("address", &entry.address))?;
+                let slot = entry
+                    .slot
+                    .parse::<U256>()
+                    .map_err(|_| invalid("slot", &entry.slot))?;
+                let value = entry
+                    .value
+                    .parse::<U256>()
+                    .map_err(|_| invalid("value", &entry.value))?;
+                Ok((address, slot, value))
+            })
+            .collect()
+    }
+
+    /// Read the validators of `validatorsFile`, resolved against `base_dir`, into
+    /// `validators` and clear the field. The config must not list validators itself
+    pub fn load_validators_file(&mut self, base_dir: &Path) -> Result<(), GenesisConfigError> {
+        let Some(file) = self.validators_file.take() else {
+            return Ok(());
+        };
+        if !self.validators.is_empty() {
+            return Err(GenesisConfigError::ValidatorsFileWithInline);
+        }
+        let path = base_dir.join(&file);
+        let invalid = |reason: String| GenesisConfigError::InvalidValidatorsFile {
+            path: path.display().to_string(),
+            reason,
+        };
+        let content = std::fs::read_to_string(&path).map_err(|e| invalid(e.to_string()))?;
+        self.validators = parse_validators_file(&file, &content).map_err(invalid)?;
+        Ok(())
+    }
+
+    /// Fill in each validator address from `validatorPrivateKeys`, then drop the
+    /// keys so they are neither logged nor written with the config. Addresses
+    /// already given must match their key
+    pub fn resolve_addresses(&mut self) -> Result<(), GenesisConfigError> {
+        let private_keys = std::mem::take(&mut self.validator_private_keys);
+        if private_keys.is_empty() {
+            return Ok(());
+        }
+        if private_keys.len() != self.validators.len() {
+            return Err(GenesisConfigError::ValidatorPrivateKeyCount {
+                len: private_keys.len(),
+                expected: self.validators.len(),
+            });
+        }
+        for (index, (validator, private_key)) in
+            self.validators.iter_mut().zip(&private_keys).enumerate()
+        {
+            let derived = derive_address(private_key).map_err(|reason| {
+                GenesisConfigError::InvalidValidatorPrivateKey { index, reason }
+            })?;
+            if validator.address.is_empty() {
+                validator.address = derived.to_string();
+            } else if validator.address.parse::<Address>().ok() != Some(derived) {
+                return Err(GenesisConfigError::ValidatorAddressMismatch {
+                    index,
+                    address: validator.address.clone(),
+                    derived,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check every non-empty network address against `networkAddressFormat`
+    pub fn check_network_addresses(&self) -> Result<(), GenesisConfigError> {
+        let format = self.network_address_format.unwrap_or_default();
+        for (index, validator) in self.validators.iter().enumerate() {
+            for (field, address) in [
+                (
+                    "validatorNetworkAddresses",
+                    &validator.validator_network_address,
+                ),
+                (
+                    "fullnodeNetworkAddresses",
+                    &validator.fullnode_network_address,
+                ),
+            ] {
+                if address.is_empty() {
+                    continue;
+                }
+                format.check(address).map_err(|reason| {
+                    GenesisConfigError::InvalidNetworkAddress {
+                        field,
+                        index,
+                        address: address.clone(),
+                        format,
+                        reason,
+                    }
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check every validator address is a 20 byte hex address, every consensus
+    /// public key is hex of a BLS12-381 public key's length, which needs no curve
+    /// arithmetic and so runs without the `bls` feature too, and every aptos address
+    /// is 32 bytes of hex ending in the validator address
+    pub fn validate_validator_keys(&self) -> Result<(), GenesisConfigError> {
+        for (index, validator) in self.validators.iter().enumerate() {
+            let address = validator.address.parse::<Address>().map_err(|_| {
+                GenesisConfigError::InvalidValidatorAddress {
+                    index,
+                    value: validator.address.clone(),
+                }
+            })?;
+            let invalid =
+                |reason: String| GenesisConfigError::InvalidConsensusPublicKey { index, reason };
+            let bytes = parse_hex_field("consensusPublicKey", &validator.consensus_public_key)
+                .map_err(|e| invalid(e.to_string()))?;
+            if !BLS_PUBLIC_KEY_LENGTHS.contains(&bytes.len()) {
+                return Err(invalid(format!(
+                    "{} bytes, expected {} (compressed) or {} (uncompressed)",
+                    bytes.len(),
+                    BLS_PUBLIC_KEY_LENGTHS[0],
+                    BLS_PUBLIC_KEY_LENGTHS[1]
+                )));
+            }
+            validator_aptos_address(index, validator, address)?;
+        }
+        Ok(())
+    }
+
+    /// Deserialize every consensus public key as a compressed BLS12-381 G1 point and
+    /// check it is on the curve, in the prime order subgroup and not the identity
+    #[cfg(feature = "bls")]
+    pub fn validate_consensus_public_keys(&self) -> Result<(), GenesisConfigError> {
+        for (index, validator) in self.validators.iter().enumerate() {
+            let invalid =
+                |reason: String| GenesisConfigError::InvalidConsensusPublicKey { index, reason };
+            let bytes = parse_hex_field("consensusPublicKey", &validator.consensus_public_key)
+                .map_err(|e| invalid(e.to_string()))?;
+            blst::min_pk::PublicKey::key_validate(&bytes)
+                .map_err(|e| invalid(format!("not a valid BLS public key: {:?}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// The configured `expectedStakeConfig` fields with their expected values
+    pub fn parsed_expected_stake_config(
+        &self,
+    ) -> Result<Vec<(StakeConfigField, U256)>, GenesisConfigError> {
+        let Some(params) = &self.expected_stake_config else {
+            return Ok(Vec::new());
+        };
+        [
+            (
+                StakeConfigField::EpochIntervalMicrosecs,
+                &params.epoch_interval_microsecs,
+            ),
+            (
+                StakeConfigField::MinValidatorStake,
+                &params.min_validator_stake,
+            ),
+            (
+                StakeConfigField::MaxValidatorCount,
+                &params.max_validator_count,
+            ),
+            (
+                StakeConfigField::RecurringLockupDuration,
+                &params.recurring_lockup_duration,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| value.as_ref().map(|value| (field, value)))
+        .map(|(field, value)| {
+            value
+                .parse::<U256>()
+                .map(|value| (field, value))
+                .map_err(|_| GenesisConfigError::InvalidStakeConfig {
+                    field: field.name(),
+                    value: value.clone(),
+                })
+        })
+        .collect()
+    }
+
+    /// Parsed `extraContracts` entries, rejecting addresses taken by a system contract,
+    /// the system caller or an earlier extra contract
+    pub fn parsed_extra_contracts(
+        &self,
+    ) -> Result<Vec<ExtraContractDeployment>, GenesisConfigError> {
+        let mut taken: std::collections::HashMap<Address, String> = self
+            .parsed_contract_addresses()?
+            .into_iter()
+            .map(|(name, address)| (address, name.to_string()))
+            .collect();
+        taken.insert(
+            self.parsed_system_caller()?,
+            "the system caller".to_string(),
+        );
+
+        let mut deployments = Vec::with_capacity(self.extra_contracts.len());
+        for (index, entry) in self.extra_contracts.iter().enumerate() {
+            let invalid =
+                |field: &'static str, value: &String| GenesisConfigError::InvalidExtraContract {
+                    index,
+                    field,
+                    value: value.clone(),
+                };
+            let address = entry
+                .address
+                .parse::<Address>()
+                .map_err(|_| invalid("address", &entry.address))?;
+            let balance = entry
+                .balance
+                .as_ref()
+                .map(|balance| {
+                    balance
+                        .parse::<U256>()
+                        .map_err(|_| invalid("balance", balance))
+                })
+                .transpose()?
+                .unwrap_or_default();
+            let constructor_args = entry
+                .constructor_args
+                .as_ref()
+                .map(|args| {
+                    parse_hex_field("constructorArgs", args)
+                        .map_err(|_| invalid("constructorArgs", args))
+                })
+                .transpose()?
+                .unwrap_or_default();
+            if let Some(taken_by) = taken.insert(address, entry.name.clone()) {
+                return Err(GenesisConfigError::ExtraContractAddressTaken {
+                    name: entry.name.clone(),
+                    address,
+                    taken_by,
+                });
+            }
+            deployments.push(ExtraContractDeployment {
+                name: entry.name.clone(),
+                bytecode_path: entry.bytecode_path.clone(),
+                address,
+                balance,
+                constructor_args: constructor_args.into(),
+            });
+        }
+        Ok(deployments)
+    }
+
+    /// Premined addresses that are also validators, in premine order
+    pub fn premine_validator_overlap(&self) -> Result<Vec<Address>, GenesisConfigError> {
+        let validators: std::collections::HashSet<Address> = self
+            .validators
+            .iter()
+            .filter_map(|validator| validator.address.parse::<Address>().ok())
+            .collect();
+        Ok(self
+            .premine_balances()?
+            .into_iter()
+            .map(|(address, _)| address)
+            .filter(|address| validators.contains(address))
+            .collect())
+    }
+
+    /// Fold every validator repeating an earlier address into that first entry,
+    /// adding up their voting powers and dropping the later entries. Entries that
+    /// also differ in anything but the voting power are rejected rather than have
+    /// that dropped. Returns the addresses that were merged
+    pub fn merge_duplicate_validators(&mut self) -> Result<Vec<Address>, GenesisConfigError> {
+        let duplicates = duplicate_validator_addresses(&self.validators);
+        if duplicates.is_empty() {
+            return Ok(Vec::new());
+        }
+        for &(address, first_index, index) in &duplicates {
+            let (first, later) = (&self.validators[first_index], &self.validators[index]);
+            let hex = |value: &str| {
+                value
+                    .strip_prefix("0x")
+                    .unwrap_or(value)
+                    .to_ascii_lowercase()
+            };
+            let conflict = [
+                (
+                    "consensusPublicKey",
+                    hex(&first.consensus_public_key) != hex(&later.consensus_public_key),
+                ),
+                (
+                    "validatorNetworkAddress",
+                    first.validator_network_address != later.validator_network_address,
+                ),
+                (
+                    "fullnodeNetworkAddress",
+                    first.fullnode_network_address != later.fullnode_network_address,
+                ),
+                (
+                    "aptosAddress",
+                    hex(&first.aptos_address) != hex(&later.aptos_address),
+                ),
+                (
+                    "commissionRate",
+                    first.commission_rate != later.commission_rate,
+                ),
+            ]
+            .into_iter()
+            .find(|(_, differs)| *differs);
+            if let Some((field, _)) = conflict {
+                return Err(GenesisConfigError::ConflictingDuplicateValidator {
+                    address,
+                    first_index,
+                    second_index: index,
+                    field,
+                });
+            }
+        }
+        let mut powers = self.voting_powers()?;
+        for &(address, first_index, index) in &duplicates {
+            powers[first_index] = powers[first_index]
+                .checked_add(powers[index])
+                .ok_or(GenesisConfigError::VotingPowerOverflow { index })?;
+            warn!(
+                "Validator {} is listed at {} and {}, merging their voting power into entry {}",
+                address, first_index, index, first_index
+            );
+        }
+
+        let dropped: std::collections::HashSet<usize> =
+            duplicates.iter().map(|&(_, _, index)| index).collect();
+        let validators = std::mem::take(&mut self.validators);
+        self.validators = validators
+            .into_iter()
+            .zip(powers)
+            .enumerate()
+            .filter(|(index, _)| !dropped.contains(index))
+            .map(|(_, (validator, power))| ValidatorSpec {
+                voting_power: power.to_string(),
+                ..validator
+            })
+            .collect();
+        Ok(duplicates
+            .into_iter()
+            .map(|(address, _, _)| address)
+            .collect())
+    }
+
+    /// Check the configuration for mistakes that would otherwise only surface
+    /// after deployment, or on a running network
+    pub fn validate(&self) -> Result<(), GenesisConfigError> {
+        if let Some(file) = &self.validators_file {
+            return Err(GenesisConfigError::InvalidValidatorsFile {
+                path: file.clone(),
+                reason: "not loaded, call load_validators_file first".to_string(),
+            });
+        }
+
+        // A repeated validator would double count its stake or revert initialization
+        if let Some(&(address, first_index, second_index)) =
+            duplicate_validator_addresses(&self.validators).first()
+        {
+            return Err(GenesisConfigError::DuplicateValidatorAddress {
+                address,
+                first_index,
+                second_index,
+            });
+        }
+
+        // Every power must be an integer that still fits once converted to wei
+        self.voting_powers_wei()?;
+
+        // Catch "extra zeros" mistakes that look plausible per validator but not in sum
+        let min = parse_voting_power_bound("minTotalVotingPower", &self.min_total_voting_power)?;
+        let max = parse_voting_power_bound("maxTotalVotingPower", &self.max_total_voting_power)?;
+        if min.is_some() || max.is_some() {
+            let total = self.total_voting_power()?;
+            if let Some(max) = max.filter(|max| total > *max) {
+                let index = self.validator_over_total(max)?;
+                return Err(GenesisConfigError::TotalVotingPowerAboveMax { total, max, index });
+            }
+            if let Some(min) = min.filter(|min| total < *min) {
+                return Err(GenesisConfigError::TotalVotingPowerBelowMin { total, min });
+            }
+        }
+
+        // Validators announcing the same network address break peer discovery
+        find_duplicate_network_address(
+            "validatorNetworkAddresses",
+            self.validators
+                .iter()
+                .map(|validator| validator.validator_network_address.as_str()),
+        )?;
+        find_duplicate_network_address(
+            "fullnodeNetworkAddresses",
+            self.validators
+                .iter()
+                .map(|validator| validator.fullnode_network_address.as_str()),
+        )?;
+        // Malformed addresses only show up once nodes fail to connect
+        self.check_network_addresses()?;
+
+        self.validate_validator_keys()?;
+        self.parsed_spec_id()?;
+        self.parsed_system_caller()?;
+        self.parsed_contract_addresses()?;
+        self.parsed_coinbase()?;
+        self.commission_rates()?;
+        self.parsed_block_hashes()?;
+        self.parsed_storage_overrides()?;
+        self.genesis_balances()?;
+        self.parsed_extra_contracts()?;
+        self.parsed_expected_stake_config()?;
+        self.create2_salts()?;
+        self.check_create_addresses()?;
+
+        // Corrupted key material would only be rejected by the consensus layer
+        #[cfg(feature = "bls")]
+        self.validate_consensus_public_keys()?;
+
+        // A premined validator starts with the premine balance before `Genesis.initialize`
+        // runs, which is easy to mistake for (or double-count with) its stake
+        let overlap = self.premine_validator_overlap()?;
+        if !overlap.is_empty() {
+            if !self.allow_validator_premine {
+                return Err(GenesisConfigError::PremineOverlapsValidators { addresses: overlap });
+            }
+            for address in &overlap {
+                warn!("Validator {} is also premined", address);
+            }
+        }
+        Ok(())
+    }
+}
+
 pub struct GenesisInitParam {
     pub validator_addresses: Vec<Address>,
     pub consensus_public_keys: Vec<Bytes>,
@@ -32,33 +2137,61 @@ pub struct GenesisInitParam {
     pub validator_network_addresses: Vec<Bytes>,
     pub fullnode_network_addresses: Vec<Bytes>,
     pub aptos_addresses: Vec<Bytes>,
+    pub commission_rates: Vec<u64>,
 }
 
-fn bytes_to_fixed32(bytes: &Bytes) -> Result<FixedBytes<32>, &'static str> {
-    if bytes.len() != 32 {
-        return Err("bytes length is not 32");
+/// The aptos address of the validator at `index`, which must be 32 bytes of hex
+/// whose last 20 bytes are its `address`
+fn validator_aptos_address(
+    index: usize,
+    validator: &ValidatorSpec,
+    address: Address,
+) -> Result<[u8; 32], GenesisConfigError> {
+    let aptos_address: [u8; 32] = parse_hex_field("aptosAddress", &validator.aptos_address)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| GenesisConfigError::InvalidAptosAddress {
+            index,
+            value: validator.aptos_address.clone(),
+        })?;
+    if Address::from_word(FixedBytes(aptos_address)) != address {
+        return Err(GenesisConfigError::AptosAddressMismatch {
+            index,
+            address,
+            aptos_address: validator.aptos_address.clone(),
+        });
     }
-
-    let fixed = FixedBytes::<32>::try_from(bytes.as_ref())
-        .map_err(|_| "failed to convert to FixedBytes<32>")?;
-
-    Ok(fixed)
+    Ok(aptos_address)
 }
 
-pub fn parse_genesis_config(config: &GenesisConfig) -> GenesisInitParam {
+pub fn parse_genesis_config(
+    config: &GenesisConfig,
+) -> Result<GenesisInitParam, GenesisConfigError> {
     // Convert string addresses to Address type
     let validator_addresses: Vec<Address> = config
-        .validator_addresses
+        .validators
         .iter()
-        .map(|addr| addr.parse::<Address>().expect("Invalid validator address"))
-        .collect();
+        .enumerate()
+        .map(|(index, validator)| {
+            validator.address.parse::<Address>().map_err(|_| {
+                GenesisConfigError::InvalidValidatorAddress {
+                    index,
+                    value: validator.address.clone(),
+                }
+            })
+        })
+        .collect::<Result<_, _>>()?;
     info!("validator addresses: {:?}", validator_addresses);
 
     // Convert consensus public keys from hex strings to bytes
     let consensus_public_keys: Vec<Bytes> = config
-        .consensus_public_keys
+        .validators
         .iter()
+        .map(|validator| &validator.consensus_public_key)
         .map(|key| {
+            // The key is passed as its hex text, which must not depend on a `0x` prefix
+            // or on the case of its digits
+            let key = key.strip_prefix("0x").unwrap_or(key).to_ascii_lowercase();
             // GApots would use the following code
             // let public_key = bls12381::PublicKey::try_from(
             //     hex::decode(node_config.consensus_public_key.as_bytes()).unwrap().as_slice(),
@@ -68,20 +2201,13 @@ pub fn parse_genesis_config(config: &GenesisConfig) -> GenesisInitParam {
         })
         .collect();
 
-    let voting_powers: Vec<U256> = config
-        .voting_powers
-        .iter()
-        .map(|power| {
-            let power_ether = power.parse::<U256>().expect("Invalid voting power");
-            // Convert from ether to wei (1 ether = 10^18 wei)
-            power_ether * U256::from(10).pow(U256::from(18))
-        })
-        .collect();
+    let voting_powers = config.voting_powers_wei()?;
 
     // Convert validator network addresses from hex strings to bytes
     let validator_network_addresses: Vec<Bytes> = config
-        .validator_network_addresses
+        .validators
         .iter()
+        .map(|validator| &validator.validator_network_address)
         .map(|addr| {
             if addr.is_empty() {
                 Bytes::new()
@@ -98,8 +2224,9 @@ pub fn parse_genesis_config(config: &GenesisConfig) -> GenesisInitParam {
 
     // Convert fullnode network addresses from hex strings to bytes
     let fullnode_network_addresses: Vec<Bytes> = config
-        .fullnode_network_addresses
+        .validators
         .iter()
+        .map(|validator| &validator.fullnode_network_address)
         .map(|addr| {
             if addr.is_empty() {
                 Bytes::new()
@@ -109,46 +2236,34 @@ pub fn parse_genesis_config(config: &GenesisConfig) -> GenesisInitParam {
         })
         .collect();
 
+    // The validator address is the tail of the aptos address
     let aptos_addresses: Vec<Bytes> = config
-        .aptos_addresses
-        .iter()
-        .map(|addr| {
-            let bytes: [u8; 32] = hex::decode(addr).unwrap().try_into().unwrap();
-            bytes.into()
-        })
-        .collect();
-
-    let address_from_aptos_address: Vec<Address> = aptos_addresses
+        .validators
         .iter()
-        .map(|addr| {
-            let address = Address::from_word(bytes_to_fixed32(addr).unwrap());
-            address
+        .zip(&validator_addresses)
+        .enumerate()
+        .map(|(index, (validator, address))| {
+            validator_aptos_address(index, validator, *address).map(Bytes::from)
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    for i in 0..validator_addresses.len() {
-        if validator_addresses[i] != address_from_aptos_address[i] {
-            panic!(
-                "❌ Validator address mismatch! Expected: {:?}, Actual: {:?}",
-                validator_addresses[i], address_from_aptos_address[i]
-            );
-        }
-    }
+    let commission_rates = config.commission_rates()?;
 
-    GenesisInitParam {
+    Ok(GenesisInitParam {
         validator_addresses,
         consensus_public_keys,
         voting_powers,
         validator_network_addresses,
         fullnode_network_addresses,
         aptos_addresses,
-    }
+        commission_rates,
+    })
 }
 
 pub fn validate_genesis_data_consistency(
     config: &GenesisConfig,
     active_validators: &[IValidatorManager::ValidatorInfo],
-) {
+) -> Vec<Mismatch> {
     info!("=== Validating Genesis Initial Data Consistency with ValidatorSet Return Data ===");
 
     let GenesisInitParam {
@@ -158,7 +2273,14 @@ pub fn validate_genesis_data_consistency(
         validator_network_addresses,
         fullnode_network_addresses,
         aptos_addresses,
-    } = parse_genesis_config(config);
+        commission_rates,
+    } = match parse_genesis_config(config) {
+        Ok(param) => param,
+        Err(e) => {
+            error!("❌ Invalid genesis config: {}", e);
+            return vec![Mismatch::new("genesis config", "valid", e)];
+        }
+    };
     let expected_count = validator_addresses.len();
     let actual_count = active_validators.len();
 
@@ -170,10 +2292,15 @@ pub fn validate_genesis_data_consistency(
             "❌ Validator count mismatch! Expected: {}, Actual: {}",
             expected_count, actual_count
         );
-        return;
+        return vec![Mismatch::new(
+            "validator count",
+            expected_count,
+            actual_count,
+        )];
     }
 
-    let mut all_match = true;
+    let mut mismatches = Vec::new();
+    let field = |i: usize, name: &str| format!("validators[{}].{}", i, name);
 
     for (i, validator) in active_validators.iter().enumerate() {
         info!("--- Validating Validator {} ---", i + 1);
@@ -183,13 +2310,21 @@ pub fn validate_genesis_data_consistency(
         let actual_operator = validator.operator;
 
         if expected_operator == actual_operator {
-            info!("✅ Operator address matches: {:?}", actual_operator);
+            info!(
+                "✅ Operator address matches: {}",
+                ChecksumAddress(actual_operator)
+            );
         } else {
             error!(
-                "❌ Operator address mismatch! Expected: {:?}, Actual: {:?}",
-                expected_operator, actual_operator
+                "❌ Operator address mismatch! Expected: {}, Actual: {}",
+                ChecksumAddress(expected_operator),
+                ChecksumAddress(actual_operator)
             );
-            all_match = false;
+            mismatches.push(Mismatch::new(
+                field(i, "address"),
+                expected_operator,
+                actual_operator,
+            ));
         }
 
         let expected_aptos_address = aptos_addresses[i].clone();
@@ -203,7 +2338,11 @@ pub fn validate_genesis_data_consistency(
             error!("❌ Aptos address mismatch!");
             error!("Expected: 0x{}", hex::encode(&expected_aptos_address));
             error!("Actual: 0x{}", hex::encode(&actual_aptos_address));
-            all_match = false;
+            mismatches.push(Mismatch::new(
+                field(i, "aptosAddress"),
+                format!("0x{}", hex::encode(&expected_aptos_address)),
+                format!("0x{}", hex::encode(&actual_aptos_address)),
+            ));
         }
 
         // Validate consensus public key
@@ -219,7 +2358,11 @@ pub fn validate_genesis_data_consistency(
             error!("❌ Consensus public key mismatch!");
             error!("Expected: 0x{}", hex::encode(&expected_consensus_key));
             error!("Actual: 0x{}", hex::encode(&actual_consensus_key));
-            all_match = false;
+            mismatches.push(Mismatch::new(
+                field(i, "consensusPublicKey"),
+                format!("0x{}", hex::encode(&expected_consensus_key)),
+                format!("0x{}", hex::encode(&actual_consensus_key)),
+            ));
         }
 
         // Validate voting power
@@ -233,7 +2376,11 @@ pub fn validate_genesis_data_consistency(
                 "❌ Voting power mismatch! Expected: {}, Actual: {}",
                 expected_voting_power, actual_voting_power
             );
-            all_match = false;
+            mismatches.push(Mismatch::new(
+                field(i, "votingPower"),
+                expected_voting_power,
+                actual_voting_power,
+            ));
         }
 
         // Validate validator network addresses
@@ -255,7 +2402,11 @@ pub fn validate_genesis_data_consistency(
                 "Actual: {:?}",
                 String::from_utf8_lossy(&actual_validator_network_addr)
             );
-            all_match = false;
+            mismatches.push(Mismatch::new(
+                field(i, "validatorNetworkAddress"),
+                format!("0x{}", hex::encode(&expected_validator_network_addr)),
+                format!("0x{}", hex::encode(&actual_validator_network_addr)),
+            ));
         }
 
         // Validate fullnode network addresses
@@ -277,26 +2428,66 @@ pub fn validate_genesis_data_consistency(
                 "Actual: {:?}",
                 String::from_utf8_lossy(&actual_fullnode_network_addr)
             );
-            all_match = false;
+            mismatches.push(Mismatch::new(
+                field(i, "fullnodeNetworkAddress"),
+                format!("0x{}", hex::encode(&expected_fullnode_network_addr)),
+                format!("0x{}", hex::encode(&actual_fullnode_network_addr)),
+            ));
+        }
+
+        // Validate commission rate
+        let expected_commission_rate = commission_rates[i];
+        let actual_commission_rate = validator.commission.rate;
+
+        if expected_commission_rate == actual_commission_rate {
+            info!("✅ Commission rate matches: {}", actual_commission_rate);
+        } else {
+            error!(
+                "❌ Commission rate mismatch! Expected: {}, Actual: {}",
+                expected_commission_rate, actual_commission_rate
+            );
+            mismatches.push(Mismatch::new(
+                field(i, "commissionRate"),
+                expected_commission_rate,
+                actual_commission_rate,
+            ));
         }
 
         info!(""); // Empty line separator
     }
 
-    if all_match {
+    if mismatches.is_empty() {
         info!(
             "🎉 All validator data validation passed! Genesis initialization data is completely consistent with ValidatorSet return data."
         );
     } else {
         error!("⚠️  Data inconsistency found, please check the error messages above.");
     }
+    mismatches
 }
 
-pub fn call_genesis_initialize(genesis_address: Address, config: &GenesisConfig) -> TxEnv {
-    let param = parse_genesis_config(config);
+sol! {
+    contract Genesis {
+        function initialize(
+            address[] calldata validatorAddresses,
+            bytes[] calldata consensusPublicKeys,
+            uint256[] calldata votingPowers,
+            bytes[] calldata validatorNetworkAddresses,
+            bytes[] calldata fullnodeNetworkAddresses,
+            bytes[] calldata aptosAddresses,
+            uint64[] calldata commissionRates
+        ) external;
+    }
+}
+
+pub fn call_genesis_initialize(
+    genesis_address: Address,
+    config: &GenesisConfig,
+) -> Result<TxEnv, GenesisConfigError> {
+    let param = parse_genesis_config(config)?;
 
     info!("=== Genesis Initialize Parameters ===");
-    info!("Genesis address: {:?}", genesis_address);
+    info!("Genesis address: {}", ChecksumAddress(genesis_address));
     info!("Validator addresses: {:?}", param.validator_addresses);
     info!(
         "Consensus public keys count: {}",
@@ -312,19 +2503,7 @@ pub fn call_genesis_initialize(genesis_address: Address, config: &GenesisConfig)
         param.fullnode_network_addresses.len()
     );
     info!("Aptos addresses count: {}", param.aptos_addresses.len());
-
-    sol! {
-        contract Genesis {
-            function initialize(
-                address[] calldata validatorAddresses,
-                bytes[] calldata consensusPublicKeys,
-                uint256[] calldata votingPowers,
-                bytes[] calldata validatorNetworkAddresses,
-                bytes[] calldata fullnodeNetworkAddresses,
-                bytes[] calldata aptosAddresses
-            ) external;
-        }
-    }
+    info!("Commission rates: {:?}", param.commission_rates);
 
     let call_data = Genesis::initializeCall {
         validatorAddresses: param.validator_addresses,
@@ -333,14 +2512,14 @@ pub fn call_genesis_initialize(genesis_address: Address, config: &GenesisConfig)
         validatorNetworkAddresses: param.validator_network_addresses,
         fullnodeNetworkAddresses: param.fullnode_network_addresses,
         aptosAddresses: param.aptos_addresses,
+        commissionRates: param.commission_rates,
     }
     .abi_encode();
 
     info!("Call data length: {}", call_data.len());
     info!("Call data: 0x{}", hex::encode(&call_data));
 
-    let txn = new_system_call_txn(genesis_address, call_data.into());
-    txn
+    Ok(new_system_call_txn(genesis_address, call_data.into()))
 }
 
 sol! {
@@ -393,8 +2572,23 @@ sol! {
 sol! {
     contract IEpochManager {
         function getCurrentEpochInfo() external view returns (uint256 epoch, uint256 lastTransitionTime, uint256 duration);
+        function epochIntervalMicrosecs() external view returns (uint256);
     }
 }
+
+sol! {
+    contract IStakeConfig {
+        function minValidatorStake() external view returns (uint256);
+        function maxValidatorCount() external view returns (uint256);
+        function recurringLockupDuration() external view returns (uint256);
+    }
+}
+sol! {
+    contract IValidatorPerformanceTracker {
+        function getCurrentValidatorCount() external view returns (uint256);
+    }
+}
+
 pub fn call_get_validator_set() -> TxEnv {
     let call_data = IValidatorManager::getValidatorSetCall {}.abi_encode();
     new_system_call_txn(VALIDATOR_MANAGER_ADDR, call_data.into())
@@ -405,7 +2599,55 @@ pub fn call_get_current_epoch_info() -> TxEnv {
     new_system_call_txn(EPOCH_MANAGER_ADDR, call_data.into())
 }
 
-pub fn print_validator_set_result(result: &ExecutionResult, config: &GenesisConfig) {
+/// Decode the active validators from a `getValidatorSet` execution result
+pub fn active_validators(
+    result: &ExecutionResult,
+) -> Result<Vec<IValidatorManager::ValidatorInfo>, String> {
+    if !result.is_success() {
+        return Err(format!(
+            "getValidatorSet failed: {}",
+            analyze_txn_result(result)
+        ));
+    }
+    let output = result.output().cloned().unwrap_or_default();
+    let validator_set = IValidatorManager::getValidatorSetCall::abi_decode_returns(&output, false)
+        .map_err(|e| format!("Failed to decode getValidatorSet result: {}", e))?;
+    Ok(validator_set._0.activeValidators)
+}
+
+/// Decode the number of active validators from a `getValidatorSet` execution result
+pub fn active_validator_count(result: &ExecutionResult) -> Result<usize, String> {
+    active_validators(result).map(|validators| validators.len())
+}
+
+pub fn call_get_current_validator_count() -> TxEnv {
+    let call_data = IValidatorPerformanceTracker::getCurrentValidatorCountCall {}.abi_encode();
+    new_system_call_txn(VALIDATOR_PERFORMANCE_TRACKER_ADDR, call_data.into())
+}
+
+/// Decode the `ValidatorPerformanceTracker` validator count from a
+/// `getCurrentValidatorCount` execution result
+pub fn tracked_validator_count(result: &ExecutionResult) -> Result<usize, String> {
+    if !result.is_success() {
+        return Err(format!(
+            "getCurrentValidatorCount failed: {}",
+            analyze_txn_result(result)
+        ));
+    }
+    let output = result.output().cloned().unwrap_or_default();
+    let count = IValidatorPerformanceTracker::getCurrentValidatorCountCall::abi_decode_returns(
+        &output, false,
+    )
+    .map_err(|e| format!("Failed to decode getCurrentValidatorCount result: {}", e))?;
+    usize::try_from(count._0).map_err(|_| format!("Validator count {} overflows usize", count._0))
+}
+
+/// Log the on-chain validator set and return where it differs from the config
+pub fn print_validator_set_result(
+    result: &ExecutionResult,
+    config: &GenesisConfig,
+) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
     handle_execution_result(result, "getValidatorSet", |output_bytes| {
         let solidity_validator_set =
             IValidatorManager::getValidatorSetCall::abi_decode_returns(output_bytes, false)
@@ -415,11 +2657,14 @@ pub fn print_validator_set_result(result: &ExecutionResult, config: &GenesisConf
         info!("Active validators count: {}", active_validators.len());
 
         // Validate consistency between initial data and returned data
-        validate_genesis_data_consistency(config, active_validators);
+        mismatches = validate_genesis_data_consistency(config, active_validators);
     });
+    mismatches
 }
 
-pub fn print_current_epoch_info_result(result: &ExecutionResult) {
+/// Check that the chain starts in epoch 0, as `EpochManager.initialize` sets it
+pub fn print_current_epoch_info_result(result: &ExecutionResult) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
     handle_execution_result(result, "getCurrentEpochInfo", |output_bytes| {
         let solidity_current_epoch_info =
             IEpochManager::getCurrentEpochInfoCall::abi_decode_returns(output_bytes, false)
@@ -429,5 +2674,1039 @@ pub fn print_current_epoch_info_result(result: &ExecutionResult) {
             "Current epoch info: {:?}",
             solidity_current_epoch_info.epoch
         );
+        if solidity_current_epoch_info.epoch != U256::ZERO {
+            error!("❌ Genesis does not start in epoch 0");
+            mismatches.push(Mismatch::new(
+                "EpochManager.currentEpoch",
+                0,
+                solidity_current_epoch_info.epoch,
+            ));
+        }
     });
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        execute::prepare_env,
+        failure::{FailurePhase, FailureReport},
+        utils::{GENESIS_ADDR, execute_revm_sequential},
+    };
+    use revm::db::InMemoryDB;
+    use revm_primitives::Bytecode;
+
+    fn test_config() -> GenesisConfig {
+        let validators: Vec<ValidatorSpec> = serde_json::from_str(
+            r#"[
+                {
+                    "address": "0x6e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f",
+                    "consensusPublicKey": "851d41932d866f5fabed6673898e15473e6a0adcf5033d2c93816c6b115c85ad3451e0bac61d570d5ed9f23e1e7f77c4",
+                    "votingPower": "20000",
+                    "validatorNetworkAddress": "/ip4/127.0.0.1/tcp/2024/noise-ik/2d86b40a1d692c0749a0a0426e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f/handshake/0",
+                    "fullnodeNetworkAddress": "/ip4/127.0.0.1/tcp/2024/noise-ik/2d86b40a1d692c0749a0a0426e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f/handshake/0",
+                    "aptosAddress": "2d86b40a1d692c0749a0a0426e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f"
+                },
+                {
+                    "address": "0xedde7f05ae91961d0804ec634d7535969b7d171f",
+                    "consensusPublicKey": "99ff89f453d9a9bf273e3ae8b61b99a2b336edc7b6eb9b8e308249fd59f3b76211771d7e0daaa97fad11518c4ad8eabd",
+                    "votingPower": "20000",
+                    "validatorNetworkAddress": "/ip4/127.0.0.1/tcp/2025/noise-ik/caafc5b658f0590d7e31de91edde7f05ae91961d0804ec634d7535969b7d171f/handshake/0",
+                    "fullnodeNetworkAddress": "/ip4/127.0.0.1/tcp/2025/noise-ik/caafc5b658f0590d7e31de91edde7f05ae91961d0804ec634d7535969b7d171f/handshake/0",
+                    "aptosAddress": "caafc5b658f0590d7e31de91edde7f05ae91961d0804ec634d7535969b7d171f"
+                }
+            ]"#,
+        )
+        .unwrap();
+        GenesisConfig {
+            validators,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_legacy_validator_arrays_round_trip() {
+        let config = test_config();
+        let mut legacy =
+            serde_json::to_value(ValidatorArrays::from_specs(&config.validators)).unwrap();
+        legacy["chainId"] = 7.into();
+        let parsed: GenesisConfig = serde_json::from_value(legacy.clone()).unwrap();
+        assert_eq!(parsed.validators, config.validators);
+        assert_eq!(parsed.chain_id, Some(7));
+
+        // Written back in the row-oriented shape
+        let written = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(written["validators"][1]["votingPower"], "20000");
+        assert!(written.get("validatorAddresses").is_none());
+
+        // A missing entry in one array would shift every later validator
+        legacy["votingPowers"].as_array_mut().unwrap().pop();
+        let err = serde_json::from_value::<GenesisConfig>(legacy.clone()).unwrap_err();
+        assert!(err.to_string().contains("votingPowers has 1 entries"));
+        let arrays: ValidatorArrays = serde_json::from_value(legacy.clone()).unwrap();
+        assert_eq!(
+            arrays.into_specs(),
+            Err(GenesisConfigError::ValidatorArrayLength {
+                field: "votingPowers",
+                len: 1,
+                expected_field: "validatorAddresses",
+                expected: 2,
+            })
+        );
+
+        // Addresses left to be derived are counted by the consensus keys instead
+        legacy["validatorAddresses"] = serde_json::json!([]);
+        let err = serde_json::from_value::<GenesisConfig>(legacy.clone()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("votingPowers has 1 entries but consensusPublicKeys has 2")
+        );
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_config() {
+        assert_eq!(test_config().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_duplicate_validator_addresses() {
+        let mut config = test_config();
+        let address: Address = config.validators[0].address.parse().unwrap();
+        // The same address, differing only in case
+        config.validators.push(config.validators[0].clone());
+        config.validators[2].address = format!("0x{}", hex::encode_upper(address));
+        config.validators[2].voting_power = "5000".to_string();
+        for (validator, rate) in config.validators.iter_mut().zip(["100", "200", "300"]) {
+            validator.commission_rate = Some(rate.to_string());
+        }
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::DuplicateValidatorAddress {
+                address,
+                first_index: 0,
+                second_index: 2,
+            })
+        );
+
+        // Merging would drop the later entry's commission rate
+        assert_eq!(
+            config.merge_duplicate_validators(),
+            Err(GenesisConfigError::ConflictingDuplicateValidator {
+                address,
+                first_index: 0,
+                second_index: 2,
+                field: "commissionRate",
+            })
+        );
+        assert_eq!(config.validators.len(), 3);
+        config.validators[2].commission_rate = Some("100".to_string());
+        // The same key, prefixed and in upper case
+        config.validators[2].consensus_public_key = format!(
+            "0x{}",
+            config.validators[0].consensus_public_key.to_uppercase()
+        );
+        assert_eq!(config.merge_duplicate_validators(), Ok(vec![address]));
+        assert_eq!(config.validators.len(), 2);
+        assert_eq!(config.validators[0].voting_power, "25000");
+        assert_eq!(config.commission_rates(), Ok(vec![100, 200]));
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(config.merge_duplicate_validators(), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_check_network_addresses() {
+        let mut config = test_config();
+        assert_eq!(config.check_network_addresses(), Ok(()));
+        for address in [
+            "/dns/validator-1.example.com/tcp/6180",
+            "/ip6/::1/tcp/2024/noise-ik/2d86b40a1d692c0749a0a0426e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f/handshake/0",
+        ] {
+            assert_eq!(
+                NetworkAddressFormat::Multiaddr.check(address),
+                Ok(()),
+                "{}",
+                address
+            );
+        }
+        for address in [
+            "127.0.0.1:2024",
+            "/ip4/127.0.0.300/tcp/2024",
+            "/ip4/127.0.0.1/udp/2024",
+            "/ip4/127.0.0.1/tcp/2024/noise-ik/2d86/handshake/0",
+        ] {
+            assert!(
+                NetworkAddressFormat::Multiaddr.check(address).is_err(),
+                "{}",
+                address
+            );
+        }
+
+        config.validators[1].fullnode_network_address = "127.0.0.1:2024".to_string();
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidNetworkAddress {
+                field: "fullnodeNetworkAddresses",
+                index: 1,
+                format: NetworkAddressFormat::Multiaddr,
+                ..
+            })
+        ));
+
+        // host:port chains reject the multiaddrs and accept the plain address
+        config.network_address_format = Some(NetworkAddressFormat::HostPort);
+        assert!(matches!(
+            config.check_network_addresses(),
+            Err(GenesisConfigError::InvalidNetworkAddress { index: 0, .. })
+        ));
+        for address in [
+            "127.0.0.1:2024",
+            "[::1]:2024",
+            "validator-1.example.com:6180",
+        ] {
+            assert_eq!(
+                NetworkAddressFormat::HostPort.check(address),
+                Ok(()),
+                "{}",
+                address
+            );
+        }
+        assert!(NetworkAddressFormat::HostPort.check("example.com").is_err());
+        assert!(NetworkAddressFormat::HostPort.check("bad host:80").is_err());
+
+        config.network_address_format = Some(NetworkAddressFormat::Any);
+        assert_eq!(config.check_network_addresses(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicate_network_addresses() {
+        let mut config = test_config();
+        config.validators[1].validator_network_address =
+            config.validators[0].validator_network_address.clone();
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::DuplicateNetworkAddress {
+                field: "validatorNetworkAddresses",
+                first_index: 0,
+                second_index: 1,
+                ..
+            })
+        ));
+
+        let mut config = test_config();
+        config.validators[0].fullnode_network_address =
+            config.validators[1].fullnode_network_address.clone();
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::DuplicateNetworkAddress {
+                field: "fullnodeNetworkAddresses",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_validate_total_voting_power_range() {
+        let mut config = test_config();
+        config.min_total_voting_power = Some("30000".to_string());
+        config.max_total_voting_power = Some("50000".to_string());
+        assert_eq!(config.validate(), Ok(()));
+
+        // A stray extra zero pushes the total over the ceiling
+        config.validators[1].voting_power = "200000".to_string();
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::TotalVotingPowerAboveMax {
+                total: U256::from(220000),
+                max: U256::from(50000),
+                index: 1,
+            })
+        );
+
+        // A missing zero drops it under the floor
+        config.validators[1].voting_power = "2000".to_string();
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::TotalVotingPowerBelowMin {
+                total: U256::from(22000),
+                min: U256::from(30000),
+            })
+        );
+
+        config.validators[1].voting_power = "twenty".to_string();
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidVotingPower { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_voting_power_overflow() {
+        let mut config = test_config();
+        // Malformed powers are rejected without any bound configured
+        config.validators[0].voting_power = "-1".to_string();
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidVotingPower { index: 0, .. })
+        ));
+
+        // Fits a U256 in ether but not once converted to wei
+        config.validators[0].voting_power = U256::MAX.to_string();
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::VotingPowerOverflow { index: 0 })
+        );
+
+        // Each fits in wei, their sum does not
+        let half = (U256::MAX / U256::from(10).pow(U256::from(18))) / U256::from(2) + U256::from(1);
+        config.validators[0].voting_power = half.to_string();
+        config.validators[1].voting_power = half.to_string();
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::VotingPowerOverflow { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_premined_validator() {
+        let mut config = test_config();
+        config.premine = vec![
+            PremineEntry {
+                address: "0x0000000000000000000000000000000000001234".to_string(),
+                balance: "1000".to_string(),
+            },
+            PremineEntry {
+                // Same validator address, different case
+                address: "0xEDDE7F05AE91961D0804EC634D7535969B7D171F".to_string(),
+                balance: "1000".to_string(),
+            },
+        ];
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::PremineOverlapsValidators {
+                addresses: vec![config.validators[1].address.parse().unwrap()],
+            })
+        );
+
+        config.allow_validator_premine = true;
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_initial_balances() {
+        let config: GenesisConfig = serde_json::from_str(
+            r#"{
+                "validators": [],
+                "initialBalances": {
+                    "0x000000000000000000000000000000000000fa00": "2000",
+                    "0x0000000000000000000000000000000000001234": "1000"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.genesis_balances(),
+            Ok(vec![
+                (
+                    "0x0000000000000000000000000000000000001234"
+                        .parse()
+                        .unwrap(),
+                    U256::from(1000)
+                ),
+                (
+                    "0x000000000000000000000000000000000000fa00"
+                        .parse()
+                        .unwrap(),
+                    U256::from(2000)
+                ),
+            ])
+        );
+        // The map form is written back as the premine list
+        let written = serde_json::to_value(&config).unwrap();
+        assert_eq!(written["premine"][1]["balance"], "2000");
+        assert!(written.get("initialBalances").is_none());
+
+        assert!(
+            serde_json::from_str::<GenesisConfig>(
+                r#"{"validators": [], "premine": [], "initialBalances": {}}"#
+            )
+            .is_err()
+        );
+
+        let mut config = test_config();
+        config.premine = vec![
+            PremineEntry {
+                address: "0x0000000000000000000000000000000000001234".to_string(),
+                balance: "1000".to_string(),
+            },
+            PremineEntry {
+                address: "0x0000000000000000000000000000000000001234".to_string(),
+                balance: "1".to_string(),
+            },
+        ];
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::DuplicatePremine {
+                index: 1,
+                address: "0x0000000000000000000000000000000000001234"
+                    .parse()
+                    .unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_burn_amount_funds_dead_address() {
+        let mut config = test_config();
+        config.premine = vec![PremineEntry {
+            address: "0x0000000000000000000000000000000000001234".to_string(),
+            balance: "1000".to_string(),
+        }];
+        config.burn_amount = Some("500".to_string());
+        assert_eq!(
+            config.genesis_balances(),
+            Ok(vec![
+                (config.premine[0].address.parse().unwrap(), U256::from(1000)),
+                (DEAD_ADDRESS, U256::from(500)),
+            ])
+        );
+
+        config.premine[0].address = DEAD_ADDRESS.to_string();
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::BurnAddressPremined)
+        );
+
+        config.burn_amount = Some("-1".to_string());
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidBurnAmount {
+                value: "-1".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_commission_rates() {
+        let mut config = test_config();
+        let count = config.validators.len();
+        assert_eq!(config.commission_rates(), Ok(vec![0; count]));
+
+        config.default_commission_rate = Some("300".to_string());
+        assert_eq!(config.commission_rates(), Ok(vec![300; count]));
+
+        config.validators[0].commission_rate = Some("10001".to_string());
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidCommissionRate {
+                field: "validators[0].commissionRate".to_string(),
+                value: "10001".to_string(),
+            })
+        );
+
+        // Validators without their own rate keep the default
+        config.validators[0].commission_rate = Some("10000".to_string());
+        let mut expected = vec![300; count];
+        expected[0] = 10_000;
+        assert_eq!(config.commission_rates(), Ok(expected));
+    }
+
+    #[test]
+    fn test_invalid_commission_revert() {
+        // Genesis stub reverting every call with ValidatorManager's
+        // InvalidCommissionRate(10001, 10000), copied from behind the 12 code bytes
+        let mut revert_data = keccak256("InvalidCommissionRate(uint64,uint64)")[..4].to_vec();
+        revert_data.extend_from_slice(&U256::from(10_001).to_be_bytes::<32>());
+        revert_data.extend_from_slice(&U256::from(10_000).to_be_bytes::<32>());
+        let mut code = vec![
+            0x60, 0x44, 0x80, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x00, 0xfd, 0x00,
+        ];
+        code.extend_from_slice(&revert_data);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            GENESIS_ADDR,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(code.into())),
+                ..AccountInfo::default()
+            },
+        );
+        let mut config = test_config();
+        config.validators[0].commission_rate = Some("10000".to_string());
+
+        let (results, _) = execute_revm_sequential(
+            &db,
+            config.spec_id(),
+            prepare_env(config.chain_id()),
+            &[call_genesis_initialize(GENESIS_ADDR, &config).unwrap()],
+            None,
+        )
+        .unwrap();
+        let report = FailureReport::from_result(FailurePhase::Init, 0, &results[0]);
+        assert_eq!(report.transaction_index, Some(0));
+        assert!(
+            report
+                .reason
+                .contains("(revert: InvalidCommissionRate { rate: 10001, maxRate: 10000 })"),
+            "{}",
+            report.reason
+        );
+    }
+
+    #[test]
+    fn test_parsed_block_hashes() {
+        let json = r#"{
+            "validators": [],
+            "genesisBlockHashes": {
+                "10": "0x1111111111111111111111111111111111111111111111111111111111111111",
+                "9": "0x2222222222222222222222222222222222222222222222222222222222222222"
+            }
+        }"#;
+        let mut config: GenesisConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.parsed_block_hashes(),
+            Ok(vec![
+                (9, B256::repeat_byte(0x22)),
+                (10, B256::repeat_byte(0x11)),
+            ])
+        );
+        assert_eq!(config.parsed_block_number(), Ok(11));
+        config.genesis_block_number = Some(100);
+        assert_eq!(config.parsed_block_number(), Ok(100));
+
+        config
+            .genesis_block_hashes
+            .insert("latest".to_string(), "0x00".to_string());
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidBlockHash {
+                number: "latest".to_string(),
+                hash: "0x00".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_hex_prefix_is_optional() {
+        let config = test_config();
+        let mut prefixed = test_config();
+        let mut upper = test_config();
+        for validator in &mut prefixed.validators {
+            validator.consensus_public_key = format!("0x{}", validator.consensus_public_key);
+            validator.aptos_address = format!("0x{}", validator.aptos_address);
+        }
+        for validator in &mut upper.validators {
+            validator.consensus_public_key = validator.consensus_public_key.to_uppercase();
+        }
+        prefixed.validate().unwrap();
+        upper.validate().unwrap();
+        let call_data = |config| call_genesis_initialize(GENESIS_ADDR, config).unwrap().data;
+        assert_eq!(call_data(&prefixed), call_data(&config));
+        assert_eq!(call_data(&upper), call_data(&config));
+        let (bare, prefixed) = (
+            parse_genesis_config(&config).unwrap(),
+            parse_genesis_config(&prefixed).unwrap(),
+        );
+        assert_eq!(prefixed.consensus_public_keys, bare.consensus_public_keys);
+        assert_eq!(prefixed.aptos_addresses, bare.aptos_addresses);
+
+        let mut config = test_config();
+        config.validators[1].aptos_address.truncate(62);
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidAptosAddress { index: 1, .. })
+        ));
+
+        // The aptos address must end in the validator address
+        let mut config = test_config();
+        let last = config.validators[0].aptos_address.pop().unwrap();
+        config.validators[0]
+            .aptos_address
+            .push(if last == '0' { '1' } else { '0' });
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::AptosAddressMismatch { index: 0, .. })
+        ));
+        assert!(matches!(
+            parse_genesis_config(&config),
+            Err(GenesisConfigError::AptosAddressMismatch { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_genesis_data_consistency() {
+        let config = test_config();
+        let param = parse_genesis_config(&config).unwrap();
+        let mut validators: Vec<IValidatorManager::ValidatorInfo> = (0..config.validators.len())
+            .map(|i| IValidatorManager::ValidatorInfo {
+                consensusPublicKey: param.consensus_public_keys[i].clone(),
+                commission: IValidatorManager::Commission {
+                    rate: param.commission_rates[i],
+                    maxRate: 5_000,
+                    maxChangeRate: 0,
+                },
+                moniker: String::new(),
+                registered: true,
+                stakeCreditAddress: Address::ZERO,
+                status: IValidatorManager::ValidatorStatus::ACTIVE,
+                votingPower: param.voting_powers[i],
+                validatorIndex: U256::from(i),
+                updateTime: U256::ZERO,
+                operator: param.validator_addresses[i],
+                validatorNetworkAddresses: param.validator_network_addresses[i].clone(),
+                fullnodeNetworkAddresses: param.fullnode_network_addresses[i].clone(),
+                aptosAddress: param.aptos_addresses[i].clone(),
+            })
+            .collect();
+        assert!(validate_genesis_data_consistency(&config, &validators).is_empty());
+
+        validators[1].votingPower = U256::from(1);
+        assert_eq!(
+            validate_genesis_data_consistency(&config, &validators),
+            vec![Mismatch::new(
+                "validators[1].votingPower",
+                param.voting_powers[1],
+                1
+            )]
+        );
+
+        validators.pop();
+        assert_eq!(
+            validate_genesis_data_consistency(&config, &validators),
+            vec![Mismatch::new("validator count", 2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_create2_salts() {
+        let mut config = test_config();
+        let salts = config.create2_salts().unwrap();
+        assert_eq!(salts.len(), CONTRACTS.len());
+        assert_eq!(salts[0], keccak256(CONTRACTS[0].0.as_bytes()));
+
+        let salt = B256::repeat_byte(0x01);
+        config
+            .create2_salts
+            .insert("Genesis".to_string(), salt.to_string());
+        let genesis_index = CONTRACTS
+            .iter()
+            .position(|(name, _)| *name == "Genesis")
+            .unwrap();
+        assert_eq!(config.create2_salts().unwrap()[genesis_index], salt);
+
+        config
+            .create2_salts
+            .insert("Faucet".to_string(), salt.to_string());
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidCreate2Salt {
+                name: "Faucet".to_string(),
+                salt: salt.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_create_addresses() {
+        let mut config = test_config();
+        config.deployer_nonce = Some(5);
+        let (name, _) = CONTRACTS[2];
+        let expected = SYSTEM_CALLER.create(7);
+        assert_eq!(config.create_addresses()[2], expected);
+        config
+            .expected_create_addresses
+            .insert(name.to_string(), expected.to_string());
+        config.validate().unwrap();
+
+        // A creation from the system caller lands where the first one is expected
+        let mut db = revm::InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, config.system_account_info());
+        let create = crate::utils::new_system_create_txn("00", Bytes::new());
+        let (results, _) = crate::utils::execute_revm_sequential(
+            &db,
+            SpecId::LATEST,
+            crate::execute::prepare_env(1),
+            &[create],
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            &results[0],
+            ExecutionResult::Success {
+                output: revm_primitives::Output::Create(_, Some(address)),
+                ..
+            } if *address == config.create_addresses()[0]
+        ));
+
+        config.deployer_nonce = None;
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::CreateAddressMismatch {
+                name: name.to_string(),
+                expected,
+                computed: SYSTEM_CALLER.create(3),
+            })
+        );
+        config.create2 = true;
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::ExpectedCreateAddressesWithCreate2)
+        );
+    }
+
+    #[test]
+    fn test_parsed_spec_id() {
+        let mut config = test_config();
+        assert_eq!(config.parsed_spec_id(), Ok(SpecId::LATEST));
+
+        config.hardfork = Some("Cancun".to_string());
+        assert_eq!(config.parsed_spec_id(), Ok(SpecId::CANCUN));
+        config.hardfork = Some("paris".to_string());
+        assert_eq!(config.spec_id(), SpecId::MERGE);
+
+        config.hardfork = Some("cancum".to_string());
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidHardfork {
+                value: "cancum".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parsed_system_caller() {
+        let mut config = test_config();
+        assert_eq!(config.system_caller(), SYSTEM_CALLER);
+
+        config.system_caller = Some("0x0000000000000000000000000000000000000000".to_string());
+        assert_eq!(config.parsed_system_caller(), Ok(Address::ZERO));
+        assert!(config.validate().is_ok());
+
+        config.system_caller = Some(format!("{:?}", EPOCH_MANAGER_ADDR));
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidSystemCaller { .. })
+        ));
+        config.system_caller = Some("0x2000".to_string());
+        assert!(config.parsed_system_caller().is_err());
+    }
+
+    #[test]
+    fn test_parsed_contract_addresses() {
+        let mut config = test_config();
+        assert_eq!(config.contract_addresses(), CONTRACTS.to_vec());
+        let tx = call_get_current_epoch_info();
+        assert_eq!(
+            config.retarget_system_call(tx.clone()).transact_to,
+            tx.transact_to
+        );
+
+        let moved = "0x000000000000000000000000000000000000f625";
+        config
+            .contract_address_overrides
+            .insert("EpochManager".to_string(), moved.to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.contract_address("EpochManager"),
+            moved.parse::<Address>().unwrap()
+        );
+        assert_eq!(
+            config.retarget_system_call(tx).transact_to,
+            revm_primitives::TxKind::Call(moved.parse().unwrap())
+        );
+
+        for (name, address) in [
+            ("Faucet", moved),
+            ("EpochManager", "0x0000000000000000000000000000000000000000"),
+            ("EpochManager", "0x2000"),
+        ] {
+            let mut config = test_config();
+            config
+                .contract_address_overrides
+                .insert(name.to_string(), address.to_string());
+            assert!(matches!(
+                config.validate(),
+                Err(GenesisConfigError::InvalidContractAddressOverride { .. })
+            ));
+        }
+
+        config
+            .contract_address_overrides
+            .insert("Block".to_string(), moved.to_string());
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::ContractAddressCollision { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parsed_coinbase() {
+        let mut config = test_config();
+        assert_eq!(config.coinbase(), Address::ZERO);
+        config.coinbase = Some(format!("{:?}", DEAD_ADDRESS));
+        assert_eq!(config.parsed_coinbase(), Ok(DEAD_ADDRESS));
+        config.coinbase = Some("coinbase".to_string());
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidCoinbase {
+                value: "coinbase".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_storage_override() {
+        let mut config = test_config();
+        config.storage_overrides = vec![StorageOverride {
+            address: "0x0000000000000000000000000000000000002013".to_string(),
+            slot: "0x1".to_string(),
+            value: "not-a-number".to_string(),
+        }];
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidStorageOverride {
+                index: 0,
+                field: "value",
+                value: "not-a-number".to_string(),
+            })
+        );
+
+        config.storage_overrides[0].value = "0x2a".to_string();
+        assert_eq!(
+            config.parsed_storage_overrides(),
+            Ok(vec![(
+                config.storage_overrides[0].address.parse().unwrap(),
+                U256::from(1),
+                U256::from(42),
+            )])
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_extra_contract_at_system_address() {
+        let mut config = test_config();
+        config.extra_contracts = vec![ExtraContract {
+            name: "Faucet".to_string(),
+            bytecode_path: "Faucet.hex".to_string(),
+            address: "0x0000000000000000000000000000000000FaCe7".to_string(),
+            balance: Some("1000000000000000000".to_string()),
+            constructor_args: None,
+        }];
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidExtraContract {
+                field: "address",
+                ..
+            })
+        ));
+
+        config.extra_contracts[0].address =
+            "0x0000000000000000000000000000000000FaCe70".to_string();
+        assert_eq!(config.validate(), Ok(()));
+
+        config.extra_contracts[0].address =
+            "0x0000000000000000000000000000000000002013".to_string();
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::ExtraContractAddressTaken {
+                name: "Faucet".to_string(),
+                address: VALIDATOR_MANAGER_ADDR,
+                taken_by: "ValidatorManager".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parsed_expected_stake_config() {
+        let mut config = test_config();
+        assert_eq!(config.parsed_expected_stake_config(), Ok(vec![]));
+
+        config.expected_stake_config = Some(StakeConfigParams {
+            max_validator_count: Some("100".to_string()),
+            recurring_lockup_duration: Some("1209600".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            config.parsed_expected_stake_config(),
+            Ok(vec![
+                (StakeConfigField::MaxValidatorCount, U256::from(100)),
+                (
+                    StakeConfigField::RecurringLockupDuration,
+                    U256::from(1_209_600)
+                ),
+            ])
+        );
+
+        config
+            .expected_stake_config
+            .as_mut()
+            .unwrap()
+            .min_validator_stake = Some("1e18".to_string());
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidStakeConfig {
+                field: "minValidatorStake",
+                value: "1e18".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_validator_keys() {
+        let mut config = test_config();
+        assert_eq!(config.validate_validator_keys(), Ok(()));
+
+        let key = config.validators[1].consensus_public_key.clone();
+        config.validators[1].consensus_public_key = key[..94].to_string();
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidConsensusPublicKey { index: 1, .. })
+        ));
+        config.validators[1].consensus_public_key = key.replacen('8', "g", 1);
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidConsensusPublicKey { index: 1, .. })
+        ));
+        config.validators[1].consensus_public_key = key;
+
+        config.validators[0].address = "0x6e2021ee24e2430da0f5bb9c2ae6c586bf3e0a".to_string();
+        assert_eq!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidValidatorAddress {
+                index: 0,
+                value: "0x6e2021ee24e2430da0f5bb9c2ae6c586bf3e0a".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_resolve_addresses() {
+        // The first Anvil/Hardhat development account
+        let private_key = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+        let mut legacy = ValidatorArrays::from_specs(&test_config().validators[..1]);
+        legacy.validator_addresses.clear();
+        let mut json = serde_json::to_value(&legacy).unwrap();
+        json["validatorPrivateKeys"] = serde_json::json!([private_key]);
+
+        let mut config: GenesisConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.validators[0].address, "");
+        config.resolve_addresses().unwrap();
+        assert_eq!(
+            config.validators[0].address,
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert!(config.validator_private_keys.is_empty());
+        let written = serde_json::to_string(&config).unwrap();
+        assert!(!written.contains(private_key), "{}", written);
+
+        let mut config = test_config();
+        config.validator_private_keys = vec![private_key.to_string()];
+        assert_eq!(
+            config.resolve_addresses(),
+            Err(GenesisConfigError::ValidatorPrivateKeyCount {
+                len: 1,
+                expected: 2
+            })
+        );
+        config.validators.truncate(1);
+        config.validator_private_keys = vec![private_key.to_string()];
+        assert!(matches!(
+            config.resolve_addresses(),
+            Err(GenesisConfigError::ValidatorAddressMismatch { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_validators_file() {
+        let validators = test_config().validators;
+        let row = |v: &ValidatorSpec| {
+            [
+                v.address.as_str(),
+                &v.consensus_public_key,
+                &v.voting_power,
+                &v.validator_network_address,
+                &v.fullnode_network_address,
+                &v.aptos_address,
+            ]
+            .join(",")
+        };
+        let csv = format!(
+            "address,consensus_key,voting_power,validator_network_addr,fullnode_network_addr,aptos_address\n{}\n\n{}\n",
+            row(&validators[0]),
+            row(&validators[1])
+        );
+        assert_eq!(parse_validators_file("v.csv", &csv).unwrap(), validators);
+        let jsonl: String = validators
+            .iter()
+            .map(|v| {
+                let object = serde_json::json!({
+                    "address": v.address,
+                    "consensus_key": v.consensus_public_key,
+                    "voting_power": v.voting_power,
+                    "validator_network_addr": v.validator_network_address,
+                    "fullnode_network_addr": v.fullnode_network_address,
+                    "aptos_address": v.aptos_address,
+                });
+                format!("{}\n", object)
+            })
+            .collect();
+        assert_eq!(
+            parse_validators_file("v.jsonl", &jsonl).unwrap(),
+            validators
+        );
+        let short = csv.replacen(&format!(",{}", validators[0].aptos_address), "", 1);
+        let error = parse_validators_file("v.csv", &short).unwrap_err();
+        assert!(error.starts_with("line 2: 5 columns"), "{}", error);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        std::fs::write(dir.join("validators.csv"), &csv).unwrap();
+        let mut config: GenesisConfig =
+            serde_json::from_str(r#"{"validatorsFile": "validators.csv"}"#).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(GenesisConfigError::InvalidValidatorsFile { .. })
+        ));
+        config.load_validators_file(&dir).unwrap();
+        assert_eq!(config.validators, validators);
+        assert!(config.validators_file.is_none());
+        config.validate().unwrap();
+
+        config.validators_file = Some("validators.csv".to_string());
+        assert_eq!(
+            config.load_validators_file(&dir),
+            Err(GenesisConfigError::ValidatorsFileWithInline)
+        );
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_genesis_config_schema() {
+        let schema = serde_json::to_value(genesis_config_schema()).unwrap();
+        let properties = &schema["properties"];
+        for field in ["validators", "validatorAddresses", "chainId", "premine"] {
+            assert!(properties.get(field).is_some(), "{} missing", field);
+        }
+        let validator = &schema["$defs"]["ValidatorSpec"]["properties"];
+        assert_eq!(validator["address"]["pattern"], ADDRESS_PATTERN);
+        assert_eq!(
+            validator["consensusPublicKey"]["pattern"],
+            BLS_PUBLIC_KEY_PATTERN
+        );
+    }
+
+    #[cfg(feature = "bls")]
+    #[test]
+    fn test_validate_rejects_invalid_bls_key() {
+        let mut config = test_config();
+        assert_eq!(config.validate_consensus_public_keys(), Ok(()));
+
+        // Flip a bit in the x coordinate so the point leaves the curve
+        let mut key = hex::decode(&config.validators[1].consensus_public_key).unwrap();
+        key[47] ^= 1;
+        config.validators[1].consensus_public_key = hex::encode(key);
+        assert!(matches!(
+            config.validate_consensus_public_keys(),
+            Err(GenesisConfigError::InvalidConsensusPublicKey { index: 1, .. })
+        ));
+    }
 }
@@ -1,16 +1,22 @@
+use std::collections::BTreeMap;
+
 use alloy_sol_macro::sol;
 use alloy_sol_types::{SolCall, SolValue};
 use revm::{
     db::BundleState,
     primitives::{Env, SpecId, TxEnv},
 };
-use revm_primitives::{ExecutionResult, hex};
+use revm_primitives::{Address, ExecutionResult, U256, hex};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    post_genesis::handle_execution_result,
-    utils::{JWK_MANAGER_ADDR, execute_revm_sequential, new_system_call_txn},
+    error::GenesisError,
+    post_genesis::{Mismatch, handle_execution_result},
+    utils::{
+        ChecksumAddress, GROTH16_VERIFIER_ADDR, JWK_MANAGER_ADDR, KEYLESS_ACCOUNT_ADDR,
+        analyze_txn_result, execute_revm_sequential, new_system_call_txn, parse_hex_field,
+    },
 };
 
 // JSON structures for deserialization
@@ -32,19 +38,44 @@ pub struct JsonAllProvidersJWKs {
     pub entries: Vec<JsonProviderJWKs>,
 }
 
-// JSON structures for OIDC Provider deserialization
+// JSON structures for OIDC Provider deserialization. `issuer` and `config_url` are
+// accepted for the name and configuration URL, and a provider is active unless set
 #[derive(Debug, Deserialize, Serialize)]
 pub struct JsonOIDCProvider {
+    #[serde(alias = "issuer")]
     pub name: String,
+    #[serde(alias = "config_url")]
     pub configUrl: String,
+    #[serde(default = "default_provider_active")]
     pub active: bool,
 }
 
+fn default_provider_active() -> bool {
+    true
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct JsonOIDCProviders {
     pub providers: Vec<JsonOIDCProvider>,
 }
 
+/// An OIDC providers file, either `{ "providers": [...] }` or the bare list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonOIDCProvidersFile {
+    Providers(JsonOIDCProviders),
+    List(Vec<JsonOIDCProvider>),
+}
+
+impl From<JsonOIDCProvidersFile> for JsonOIDCProviders {
+    fn from(file: JsonOIDCProvidersFile) -> Self {
+        match file {
+            JsonOIDCProvidersFile::Providers(providers) => providers,
+            JsonOIDCProvidersFile::List(providers) => JsonOIDCProviders { providers },
+        }
+    }
+}
+
 sol! {
     struct OIDCProvider {
         string name; // Provider name, e.g., "https://accounts.google.com"
@@ -135,12 +166,82 @@ pub fn call_get_active_providers() -> TxEnv {
     new_system_call_txn(JWK_MANAGER_ADDR, call_data.into())
 }
 
-pub fn read_jwks_from_file(jwks_file_path: &str) -> Result<Vec<ProviderJWKs>, String> {
-    let jwks_content = std::fs::read_to_string(jwks_file_path)
-        .map_err(|e| format!("Failed to read JWKS file: {}", e))?;
+sol! {
+    contract IKeylessAccount {
+        function verifier() external view returns (address);
+    }
+}
+
+/// Call KeylessAccount's `verifier` getter
+pub fn call_get_keyless_verifier() -> TxEnv {
+    let call_data = IKeylessAccount::verifierCall {}.abi_encode();
+    new_system_call_txn(KEYLESS_ACCOUNT_ADDR, call_data.into())
+}
+
+/// Compare the verifier KeylessAccount is wired to with `expected`
+pub fn print_keyless_verifier_result(result: &ExecutionResult, expected: Address) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    handle_execution_result(result, "verifier", |output_bytes| {
+        let verifier: Address =
+            IKeylessAccount::verifierCall::abi_decode_returns(output_bytes, false)
+                .unwrap()
+                ._0;
+        info!("KeylessAccount verifier: {}", ChecksumAddress(verifier));
+        if verifier != expected {
+            error!("KeylessAccount is wired to the wrong Groth16 verifier");
+            mismatches.push(Mismatch::new("KeylessAccount.verifier", expected, verifier));
+        }
+    });
+    mismatches
+}
+
+sol! {
+    contract IGroth16Verifier {
+        function compressProof(uint256[8] calldata proof) external view returns (uint256[4] memory compressed);
+    }
+}
+
+/// An uncompressed proof whose points A and C are the BN254 G1 generator (1, 2) and
+/// whose B is the point at infinity. Compressing the generator takes a square root
+/// through the modexp precompile; 2 is the root found, so `compressProof` returns
+/// `COMPRESSED_GENERATOR_PROOF`
+pub const GENERATOR_PROOF: [u64; 8] = [1, 2, 0, 0, 0, 0, 1, 2];
+pub const COMPRESSED_GENERATOR_PROOF: [u64; 4] = [2, 0, 0, 2];
+
+/// Call the Groth16 verifier's `compressProof` view on `GENERATOR_PROOF`, which
+/// only succeeds if the verifier code actually runs
+pub fn call_compress_generator_proof() -> TxEnv {
+    let call_data = IGroth16Verifier::compressProofCall {
+        proof: GENERATOR_PROOF.map(U256::from),
+    }
+    .abi_encode();
+    new_system_call_txn(GROTH16_VERIFIER_ADDR, call_data.into())
+}
+
+/// The compressed proof `compressProof` returned, `None` if the call failed or
+/// returned something else
+pub fn decode_compressed_proof(result: &ExecutionResult) -> Option<[U256; 4]> {
+    let mut compressed = None;
+    handle_execution_result(result, "compressProof", |output_bytes| {
+        compressed = IGroth16Verifier::compressProofCall::abi_decode_returns(output_bytes, false)
+            .ok()
+            .map(|returns| returns.compressed);
+    });
+    compressed
+}
 
-    let jwks: JsonAllProvidersJWKs = serde_json::from_str(&jwks_content)
-        .map_err(|e| format!("Failed to parse JWKS file: {}", e))?;
+pub fn read_jwks_from_file(jwks_file_path: &str) -> Result<Vec<ProviderJWKs>, GenesisError> {
+    let jwks_content =
+        std::fs::read_to_string(jwks_file_path).map_err(|source| GenesisError::FileRead {
+            path: jwks_file_path.to_string(),
+            source,
+        })?;
+
+    let jwks: JsonAllProvidersJWKs =
+        serde_json::from_str(&jwks_content).map_err(|source| GenesisError::JsonParse {
+            path: jwks_file_path.to_string(),
+            source,
+        })?;
 
     info!("Successfully loaded JWKs from file");
     info!("Total providers: {}", jwks.entries.len());
@@ -161,22 +262,17 @@ pub fn read_jwks_from_file(jwks_file_path: &str) -> Result<Vec<ProviderJWKs>, St
     }
 
     // Convert JSON structure to Solidity structure
-    let provider_jwks_array: Result<Vec<ProviderJWKs>, String> = jwks
+    let provider_jwks_array: Result<Vec<ProviderJWKs>, GenesisError> = jwks
         .entries
         .into_iter()
         .map(|entry| {
-            let jwks: Result<Vec<JWK>, String> = entry
+            let issuer = entry.issuer;
+            let jwks: Result<Vec<JWK>, GenesisError> = entry
                 .jwks
                 .into_iter()
                 .map(|jwk| {
-                    // Convert hex string to bytes
-                    let data_bytes = if jwk.data.starts_with("0x") {
-                        hex::decode(&jwk.data[2..])
-                            .map_err(|e| format!("Failed to decode hex data: {}", e))
-                    } else {
-                        hex::decode(&jwk.data)
-                            .map_err(|e| format!("Failed to decode hex data: {}", e))
-                    }?;
+                    let data_bytes =
+                        parse_hex_field(&format!("JWK data of {}", issuer), &jwk.data)?;
 
                     Ok(JWK {
                         variant: jwk.variant,
@@ -185,26 +281,35 @@ pub fn read_jwks_from_file(jwks_file_path: &str) -> Result<Vec<ProviderJWKs>, St
                 })
                 .collect();
 
+            let jwks = jwks?;
             Ok(ProviderJWKs {
-                issuer: entry.issuer,
+                issuer,
                 version: entry.version,
-                jwks: jwks?,
+                jwks,
             })
         })
         .collect();
 
-    Ok(provider_jwks_array?)
+    provider_jwks_array
 }
 
 /// Read OIDC providers from JSON file
 pub fn read_oidc_providers_from_file(
     provider_file_path: &str,
-) -> Result<Vec<OIDCProvider>, String> {
-    let provider_content = std::fs::read_to_string(provider_file_path)
-        .map_err(|e| format!("Failed to read OIDC provider file: {}", e))?;
-
-    let providers: JsonOIDCProviders = serde_json::from_str(&provider_content)
-        .map_err(|e| format!("Failed to parse OIDC provider file: {}", e))?;
+) -> Result<Vec<OIDCProvider>, GenesisError> {
+    let provider_content =
+        std::fs::read_to_string(provider_file_path).map_err(|source| GenesisError::FileRead {
+            path: provider_file_path.to_string(),
+            source,
+        })?;
+
+    let providers: JsonOIDCProviders =
+        serde_json::from_str::<JsonOIDCProvidersFile>(&provider_content)
+            .map_err(|source| GenesisError::JsonParse {
+                path: provider_file_path.to_string(),
+                source,
+            })?
+            .into();
 
     info!("Successfully loaded OIDC providers from file");
     info!("Total providers: {}", providers.providers.len());
@@ -231,7 +336,7 @@ pub fn read_oidc_providers_from_file(
 }
 
 /// Upsert OIDC providers from file
-pub fn upsert_oidc_providers(provider_file_path: &str) -> Result<Vec<TxEnv>, String> {
+pub fn upsert_oidc_providers(provider_file_path: &str) -> Result<Vec<TxEnv>, GenesisError> {
     info!(
         "=== Loading OIDC providers from file: {} ===",
         provider_file_path
@@ -266,7 +371,7 @@ pub fn upsert_oidc_providers(provider_file_path: &str) -> Result<Vec<TxEnv>, Str
     Ok(transactions)
 }
 
-pub fn upsert_observed_jwks(jwks_file_path: &str) -> Result<TxEnv, String> {
+pub fn upsert_observed_jwks(jwks_file_path: &str) -> Result<TxEnv, GenesisError> {
     info!("=== Loading JWKs from file: {} ===", jwks_file_path);
 
     let provider_jwks_array = read_jwks_from_file(jwks_file_path)?;
@@ -287,34 +392,135 @@ pub fn upsert_observed_jwks(jwks_file_path: &str) -> Result<TxEnv, String> {
     Ok(upsert_tx)
 }
 
-pub fn print_jwks_result(result: &ExecutionResult, jwks_file: &str) {
-    let provider_jwks_array = read_jwks_from_file(jwks_file).unwrap();
+/// Compare the observed JWKs with the keys in `jwks_file`
+pub fn print_jwks_result(result: &ExecutionResult, jwks_file: &str) -> Vec<Mismatch> {
+    let provider_jwks_array = match read_jwks_from_file(jwks_file) {
+        Ok(provider_jwks_array) => provider_jwks_array,
+        Err(e) => return vec![Mismatch::new("JWKs", jwks_file, e)],
+    };
 
+    let mut mismatches = Vec::new();
     handle_execution_result(result, "getObservedJWKs", |output_bytes| {
         let solidity_current_epoch_info =
             getObservedJWKsCall::abi_decode_returns(output_bytes, false).unwrap();
         let result_jwks = solidity_current_epoch_info._0.entries;
 
-        // 和provider_jwks_array进行比较
-        for (_i, provider) in result_jwks.iter().enumerate() {
-            let provider_jwks = provider_jwks_array
-                .iter()
-                .find(|p| p.issuer == provider.issuer);
-            if let Some(provider_jwks) = provider_jwks {
-                assert_eq!(provider_jwks.version, provider.version);
-                assert_eq!(provider_jwks.jwks.len(), provider.jwks.len());
-                for (j, jwk) in provider_jwks.jwks.iter().enumerate() {
-                    assert_eq!(jwk.variant, provider.jwks[j].variant);
-                    assert_eq!(jwk.data, provider.jwks[j].data);
+        // The contract keeps providers sorted by issuer and keys sorted by kid, so
+        // compare by those rather than by position in the input file
+        let differences = jwks_differences(&provider_jwks_array, &result_jwks);
+        for difference in &differences {
+            error!("JWK mismatch: {}", difference);
+        }
+        if differences.is_empty() {
+            info!(
+                "✅ Observed JWKs of {} providers match {}",
+                result_jwks.len(),
+                jwks_file
+            );
+        }
+        mismatches = differences
+            .into_iter()
+            .map(|difference| Mismatch::new("JWKs", jwks_file, difference))
+            .collect();
+    });
+    mismatches
+}
+
+/// Identity of a JWK within its provider: the kid of an RSA key, the raw data of
+/// any other variant
+fn jwk_key(jwk: &JWK) -> String {
+    let rsa_jwk = (jwk.variant == 0)
+        .then(|| RSATestJWK::abi_decode(&jwk.data, true).ok())
+        .flatten();
+    match rsa_jwk {
+        Some(rsa_jwk) => rsa_jwk.kid,
+        None => format!("0x{}", hex::encode(&jwk.data)),
+    }
+}
+
+/// Content differences between the expected and the on-chain JWKs, matching
+/// providers by issuer and keys by kid so ordering alone is never reported
+pub fn jwks_differences(expected: &[ProviderJWKs], actual: &[ProviderJWKs]) -> Vec<String> {
+    let by_issuer = |providers: &[ProviderJWKs]| -> BTreeMap<String, usize> {
+        providers
+            .iter()
+            .enumerate()
+            .map(|(index, provider)| (provider.issuer.clone(), index))
+            .collect()
+    };
+    let expected_issuers = by_issuer(expected);
+    let actual_issuers = by_issuer(actual);
+
+    let mut differences = Vec::new();
+    for (issuer, index) in &expected_issuers {
+        let Some(actual_index) = actual_issuers.get(issuer) else {
+            differences.push(format!("provider {} is missing on chain", issuer));
+            continue;
+        };
+        let (expected, actual) = (&expected[*index], &actual[*actual_index]);
+        if expected.version != actual.version {
+            differences.push(format!(
+                "provider {} has version {}, expected {}",
+                issuer, actual.version, expected.version
+            ));
+        }
+
+        let by_kid = |jwks: &[JWK]| -> BTreeMap<String, usize> {
+            jwks.iter()
+                .enumerate()
+                .map(|(index, jwk)| (jwk_key(jwk), index))
+                .collect()
+        };
+        let expected_kids = by_kid(&expected.jwks);
+        let actual_kids = by_kid(&actual.jwks);
+        for (kid, index) in &expected_kids {
+            match actual_kids.get(kid) {
+                None => differences.push(format!("key {} of {} is missing on chain", kid, issuer)),
+                Some(actual_index) => {
+                    let (expected_jwk, actual_jwk) =
+                        (&expected.jwks[*index], &actual.jwks[*actual_index]);
+                    if expected_jwk.variant != actual_jwk.variant
+                        || expected_jwk.data != actual_jwk.data
+                    {
+                        differences
+                            .push(format!("key {} of {} has different content", kid, issuer));
+                    }
                 }
             }
         }
-    });
+        for kid in actual_kids
+            .keys()
+            .filter(|kid| !expected_kids.contains_key(*kid))
+        {
+            differences.push(format!(
+                "key {} of {} is on chain but not expected",
+                kid, issuer
+            ));
+        }
+        if expected_kids.len() != expected.jwks.len() {
+            differences.push(format!("expected keys of {} repeat a kid", issuer));
+        }
+    }
+    for issuer in actual_issuers
+        .keys()
+        .filter(|issuer| !expected_issuers.contains_key(*issuer))
+    {
+        differences.push(format!("provider {} is on chain but not expected", issuer));
+    }
+    differences
 }
 
-pub fn print_oidc_providers_result(result: &ExecutionResult, oidc_providers_file: &str) {
-    let expected_providers = read_oidc_providers_from_file(oidc_providers_file).unwrap();
+/// Compare the active OIDC providers with the providers in `oidc_providers_file`
+pub fn print_oidc_providers_result(
+    result: &ExecutionResult,
+    oidc_providers_file: &str,
+) -> Vec<Mismatch> {
+    let expected_providers = match read_oidc_providers_from_file(oidc_providers_file) {
+        Ok(expected_providers) => expected_providers,
+        Err(e) => return vec![Mismatch::new("OIDC providers", oidc_providers_file, e)],
+    };
 
+    let mut mismatches = Vec::new();
     handle_execution_result(result, "getActiveProviders", |output_bytes| {
         let solidity_active_providers =
             getActiveProvidersCall::abi_decode_returns(output_bytes, false).unwrap();
@@ -327,24 +533,63 @@ pub fn print_oidc_providers_result(result: &ExecutionResult, oidc_providers_file
             info!("  Active: {}", provider.active);
 
             let expected_provider = expected_providers.iter().find(|p| p.name == provider.name);
-            if let Some(expected) = expected_provider {
-                assert_eq!(expected.name, provider.name);
-                assert_eq!(expected.configUrl, provider.configUrl);
-                assert_eq!(expected.active, provider.active);
+            let Some(expected) = expected_provider else {
+                error!("  ❌ Provider not found in {}", oidc_providers_file);
+                mismatches.push(Mismatch::new(
+                    format!("OIDC provider {}", provider.name),
+                    format!("no such provider in {}", oidc_providers_file),
+                    "an active provider",
+                ));
+                continue;
+            };
+            let field = |name: &str| format!("OIDC provider {}.{}", provider.name, name);
+            let before = mismatches.len();
+            if expected.configUrl != provider.configUrl {
+                mismatches.push(Mismatch::new(
+                    field("configUrl"),
+                    &expected.configUrl,
+                    &provider.configUrl,
+                ));
+            }
+            if expected.active != provider.active {
+                mismatches.push(Mismatch::new(
+                    field("active"),
+                    expected.active,
+                    provider.active,
+                ));
+            }
+            if mismatches.len() == before {
                 info!("  ✓ Provider verified successfully");
             } else {
-                info!("  ⚠ Provider not found in expected data");
+                error!("  ❌ Provider differs from {}", oidc_providers_file);
             }
         }
+        for expected in expected_providers.iter().filter(|expected| {
+            expected.active
+                && !result_providers
+                    .iter()
+                    .any(|provider| provider.name == expected.name)
+        }) {
+            error!("  ❌ Provider {} is not active on chain", expected.name);
+            mismatches.push(Mismatch::new(
+                format!("OIDC provider {}", expected.name),
+                "an active provider",
+                "no such active provider",
+            ));
+        }
     });
+    mismatches
 }
 
-/// Execute JWK management operations
+/// Log the currently observed JWKs, then upsert `provider_jwks` on top of
+/// `bundle_state`, returning the emitted logs and the resulting bundle
 pub fn execute_jwk_operations<DB>(
     db: DB,
     env: Env,
+    spec_id: SpecId,
     bundle_state: Option<BundleState>,
-) -> Result<(Vec<alloy_primitives::Log>, BundleState), String>
+    provider_jwks: Vec<ProviderJWKs>,
+) -> Result<(Vec<alloy_primitives::Log>, BundleState), GenesisError>
 where
     DB: revm::DatabaseRef + Clone,
 {
@@ -355,8 +600,14 @@ where
 
     // Execute get transaction
     info!("Executing getObservedJWKs transaction...");
-    let get_result = execute_revm_sequential(db, SpecId::LATEST, env, &[get_tx], bundle_state)
-        .map_err(|_| "get transaction failed".to_string())?;
+    let get_result = execute_revm_sequential(
+        db.clone(),
+        spec_id,
+        env.clone(),
+        &[get_tx],
+        bundle_state.clone(),
+    )
+    .map_err(GenesisError::revm)?;
 
     let (get_results, _) = get_result;
 
@@ -394,11 +645,35 @@ where
                 }
             }
         } else {
-            return Err(format!("getObservedJWKs failed: {:?}", result));
+            return Err(GenesisError::TxReverted {
+                index: 0,
+                reason: format!("getObservedJWKs failed: {}", analyze_txn_result(result)),
+            });
         }
     }
 
-    todo!()
+    info!(
+        "Executing upsertObservedJWKs transaction for {} providers...",
+        provider_jwks.len()
+    );
+    let upsert_tx = call_upsert_observed_jwks(provider_jwks);
+    let (upsert_results, new_bundle_state) =
+        execute_revm_sequential(db, spec_id, env, &[upsert_tx], bundle_state)
+            .map_err(GenesisError::revm)?;
+    let result = upsert_results
+        .first()
+        .ok_or_else(|| GenesisError::Revm("upsertObservedJWKs produced no result".to_string()))?;
+    if !result.is_success() {
+        return Err(GenesisError::TxReverted {
+            index: 0,
+            reason: format!("upsertObservedJWKs failed: {}", analyze_txn_result(result)),
+        });
+    }
+    info!(
+        "upsertObservedJWKs transaction successful, {} logs emitted",
+        result.logs().len()
+    );
+    Ok((result.logs().to_vec(), new_bundle_state))
 }
 
 // Helper struct for RSA JWK encoding
@@ -546,16 +821,123 @@ mod tests {
         let jwk_file_path = config.jwk_template_abs().to_string_lossy().to_string();
         let oidc_file_path = config.oidc_provider_abs().to_string_lossy().to_string();
         
-        let (db, bundle_state) = execute::genesis_generate(
+        let (db, bundle_state, _) = execute::genesis_generate(
             &config.out_dir_abs().to_string_lossy(),
             &config.final_output_dir_abs().to_string_lossy(),
             &genesis_config,
             Some(jwk_file_path.clone()),
             Some(oidc_file_path.clone()),
+            &execute::GenerateOptions::default(),
+        )
+        .unwrap();
+
+        verify_jwks(
+            db.clone(),
+            bundle_state.clone(),
+            &genesis_config,
+            &jwk_file_path,
+        )
+        .unwrap();
+        verify_oidc_providers(
+            db.clone(),
+            bundle_state.clone(),
+            &genesis_config,
+            &oidc_file_path,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_execute_jwk_operations_round_trip() {
+        let config = TestConfig::default();
+        if let Err(e) = config.validate() {
+            panic!("Test configuration validation failed: {}", e);
+        }
+        let config_content = fs::read_to_string(config.genesis_config_abs()).unwrap();
+        let genesis_config: GenesisConfig = serde_json::from_str(&config_content).unwrap();
+        let (db, bundle_state, _) = execute::genesis_generate(
+            &config.out_dir_abs().to_string_lossy(),
+            &config.final_output_dir_abs().to_string_lossy(),
+            &genesis_config,
+            None,
+            None,
+            &execute::GenerateOptions::default(),
+        )
+        .unwrap();
+
+        let jwk = create_test_rsa_jwk("test-key", "RS256", "AQAB", "test-modulus");
+        let provider = create_provider_jwks("https://issuer.example.com", 1, vec![jwk]);
+        let env = execute::prepare_env(genesis_config.chain_id());
+        let (_, bundle_state) = execute_jwk_operations(
+            db.clone(),
+            env.clone(),
+            genesis_config.spec_id(),
+            Some(bundle_state),
+            vec![provider.clone()],
+        )
+        .unwrap();
+
+        let (results, _) = execute_revm_sequential(
+            db,
+            SpecId::LATEST,
+            env,
+            &[call_get_observed_jwks()],
+            Some(bundle_state),
+        )
+        .unwrap();
+        let observed = getObservedJWKsCall::abi_decode_returns(results[0].output().unwrap(), false)
+            .unwrap()
+            ._0
+            .entries;
+        let upserted = observed
+            .iter()
+            .find(|observed| observed.issuer == provider.issuer)
+            .expect("upserted provider is not observed");
+        assert!(
+            jwks_differences(
+                std::slice::from_ref(&provider),
+                std::slice::from_ref(upserted)
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_jwks_differences_ignore_order() {
+        let google = |version, kids: &[&str]| {
+            create_provider_jwks(
+                "https://accounts.google.com",
+                version,
+                kids.iter()
+                    .map(|kid| create_test_rsa_jwk(kid, "RS256", "AQAB", "test-modulus"))
+                    .collect(),
+            )
+        };
+        let apple = create_provider_jwks(
+            "https://appleid.apple.com",
+            1,
+            vec![create_test_rsa_jwk(
+                "apple-key",
+                "RS256",
+                "AQAB",
+                "test-modulus",
+            )],
+        );
+
+        // Sorted on chain, unsorted in the input
+        let expected = [google(1, &["b", "a"]), apple.clone()];
+        let actual = [apple.clone(), google(1, &["a", "b"])];
+        assert!(jwks_differences(&expected, &actual).is_empty());
+
+        let actual = [apple, google(2, &["a", "c"])];
+        assert_eq!(
+            jwks_differences(&expected, &actual),
+            vec![
+                "provider https://accounts.google.com has version 2, expected 1",
+                "key b of https://accounts.google.com is missing on chain",
+                "key c of https://accounts.google.com is on chain but not expected",
+            ]
         );
-        
-        verify_jwks(db.clone(), bundle_state.clone(), &jwk_file_path);
-        verify_oidc_providers(db.clone(), bundle_state.clone(), &oidc_file_path);
     }
 
     #[test]
@@ -609,8 +991,7 @@ mod tests {
         // This test would require a real file, so we'll just test the function signature
         // In a real scenario, you would create a temporary file and test with it
         let result = upsert_observed_jwks("nonexistent_file.json");
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Failed to read JWKS file"));
+        assert!(matches!(result, Err(GenesisError::FileRead { .. })));
     }
 
     #[test]
@@ -641,18 +1022,32 @@ mod tests {
         assert_eq!(providers.providers[0].active, true);
         assert_eq!(providers.providers[1].name, "https://test2.com");
         assert_eq!(providers.providers[1].active, false);
+
+        // A bare list keyed by issuer, as the JWK files are
+        let json_content = r#"[
+            {
+                "issuer": "https://test.com",
+                "config_url": "https://test.com/.well-known/openid_configuration"
+            }
+        ]"#;
+        let providers: JsonOIDCProviders =
+            serde_json::from_str::<JsonOIDCProvidersFile>(json_content)
+                .unwrap()
+                .into();
+        assert_eq!(providers.providers.len(), 1);
+        assert_eq!(providers.providers[0].name, "https://test.com");
+        assert_eq!(
+            providers.providers[0].configUrl,
+            "https://test.com/.well-known/openid_configuration"
+        );
+        assert!(providers.providers[0].active);
     }
 
     #[test]
     fn test_upsert_oidc_providers() {
         // This test would require a real file, so we'll just test the function signature
         let result = upsert_oidc_providers("nonexistent_provider_file.json");
-        assert!(result.is_err());
-        assert!(
-            result
-                .unwrap_err()
-                .contains("Failed to read OIDC provider file")
-        );
+        assert!(matches!(result, Err(GenesisError::FileRead { .. })));
     }
 }
 
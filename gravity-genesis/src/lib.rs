@@ -2,4 +2,21 @@ pub mod execute;
 pub mod utils;
 pub mod genesis;
 pub mod post_genesis;
-pub mod jwks;
\ No newline at end of file
+pub mod jwks;
+pub mod error;
+pub mod error_catalog;
+pub mod events;
+pub mod failure;
+pub mod metrics;
+pub mod profile;
+pub mod selftest;
+pub mod report;
+pub mod node_config;
+pub mod scaffold;
+pub mod patch;
+pub mod abi_call;
+pub mod diff;
+pub mod progress;
+pub mod reverts;
+#[cfg(feature = "rpc")]
+pub mod rpc_verify;
\ No newline at end of file
@@ -1,9 +1,22 @@
 use anyhow::Result;
-use clap::Parser;
-use gravity_genesis::{execute, genesis::GenesisConfig, post_genesis};
+use clap::{Parser, Subcommand, ValueEnum};
+use gravity_genesis::{
+    diff,
+    error::GenesisError,
+    error_catalog,
+    execute::{self, ArtifactFormat, DeployMode, OutputFormat},
+    failure::{self, FailurePhase, FailureReport, write_failure_report},
+    genesis::GenesisConfig,
+    metrics, patch,
+    post_genesis::{self, VerifyCheck},
+    profile,
+    progress::ProgressCallback,
+    report, scaffold, selftest, utils,
+};
 use serde_json;
-use std::fs;
+use std::{fs, path::Path, time::Duration};
 use tracing::{Level, info};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
 // Custom guard to ensure proper log flushing
 struct LogGuard {
@@ -39,42 +52,436 @@ impl Drop for LogGuard {
     }
 }
 
+/// How log lines are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// Human readable lines
+    Text,
+    /// One JSON object per line, for log aggregators
+    Json,
+}
+
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Enable debug logging
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     debug: bool,
 
-    /// Byte code directory
-    #[arg(short, long)]
-    byte_code_dir: String,
+    /// Log file path (optional)
+    #[arg(short, long, global = true)]
+    log_file: Option<String>,
+
+    /// Format of the log lines, on the console or in the log file
+    #[arg(long, value_enum, global = true, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
+    /// Print the JSON Schema of genesis_config.json and exit
+    #[cfg(feature = "schema")]
+    #[arg(long, exclusive = true)]
+    print_schema: bool,
+
+    // The flags of `generate`, which also runs when no subcommand is given
+    #[command(flatten)]
+    generate: GenerateArgs,
+}
+
+/// `--output` value that writes the combined genesis to stdout instead of a directory
+const STDOUT_OUTPUT: &str = "-";
+
+impl Args {
+    /// Whether the run writes its artifact to stdout, so logs must stay off it
+    fn writes_to_stdout(&self) -> bool {
+        let generate = match &self.command {
+            Some(Command::Generate(generate)) => generate,
+            None => &self.generate,
+            Some(_) => return false,
+        };
+        generate.output.as_deref() == Some(STDOUT_OUTPUT)
+    }
+}
 
+// Where the genesis config is read from and the overrides applied to it. Plain
+// comments, as clap would take a doc comment for the about text of every command
+// flattening these
+#[derive(clap::Args, Debug)]
+struct ConfigArgs {
     /// Genesis configuration file
     #[arg(short, long, default_value = "generate/genesis_config.json")]
     config_file: String,
 
-    /// Save results to file
-    #[arg(short, long)]
-    output: Option<String>,
+    /// Accept `//` and `/* */` comments and trailing commas in the config file.
+    /// Implied for files ending in `.jsonc`
+    #[arg(long)]
+    jsonc: bool,
 
-    /// Log file path (optional)
-    #[arg(short, long)]
-    log_file: Option<String>,
+    /// Chain id of the EVM env, overriding `chainId` from the config file
+    #[arg(long)]
+    chain_id: Option<u64>,
+
+    /// Hardfork to execute under, such as "cancun", overriding `hardfork` from the
+    /// config file
+    #[arg(long)]
+    spec: Option<String>,
 
+    /// Gas limit of each genesis transaction, overriding `genesisGasLimit` from the
+    /// config file
+    #[arg(long)]
+    gas_limit: Option<u64>,
+
+    /// Merge validators listed more than once under the same address, adding up
+    /// their voting powers, instead of rejecting the config. Entries that differ in
+    /// anything else are still rejected
+    #[arg(long)]
+    allow_duplicate_validators: bool,
+
+    /// Reject configs listing more validators than this, before anything else is
+    /// checked or deployed. No limit by default
+    #[arg(long)]
+    max_validators: Option<usize>,
+}
+
+impl ConfigArgs {
+    /// Read the config file, apply the overrides and validate the result
+    fn load(&self) -> Result<GenesisConfig> {
+        info!("Reading Genesis configuration from: {}", self.config_file);
+        let mut config_content = fs::read_to_string(&self.config_file)?;
+        if self.jsonc || self.config_file.ends_with(".jsonc") {
+            config_content = utils::strip_jsonc(&config_content).map_err(anyhow::Error::msg)?;
+        }
+        let mut config: GenesisConfig = serde_json::from_str(&config_content)?;
+        if let Some(chain_id) = self.chain_id {
+            config.chain_id = Some(chain_id);
+        }
+        if let Some(spec) = &self.spec {
+            config.hardfork = Some(spec.clone());
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            config.genesis_gas_limit = Some(gas_limit);
+        }
+        let config_dir = Path::new(&self.config_file)
+            .parent()
+            .unwrap_or_else(|| Path::new(""));
+        config.load_validators_file(config_dir)?;
+        let count = config.validators.len();
+        if let Some(max) = self.max_validators.filter(|max| count > *max) {
+            anyhow::bail!(
+                "{} lists {} validators, more than --max-validators {}",
+                self.config_file,
+                count,
+                max
+            );
+        }
+        config.resolve_addresses()?;
+        if self.allow_duplicate_validators {
+            config.merge_duplicate_validators()?;
+        }
+        config.validate()?;
+        info!("Genesis configuration loaded successfully");
+        info!("Genesis configuration: {:?}", config);
+        Ok(config)
+    }
+}
+
+// The post-generation checks and the input files they compare the state against
+#[derive(clap::Args, Debug)]
+struct CheckArgs {
     /// JWKs file path (optional)
     #[arg(short, long)]
     jwks_file: Option<String>,
 
-    /// OIDC providers file path (optional)
-    #[arg(short, long)]
+    /// OIDC providers file path (optional). Long form only, `-o` is `--output`
+    #[arg(long, visible_alias = "oidc-providers")]
     oidc_providers_file: Option<String>,
+
+    /// Comma separated post-generation checks to run
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "all")]
+    checks: Vec<VerifyCheck>,
+}
+
+impl CheckArgs {
+    fn validate(&self) -> Result<()> {
+        post_genesis::validate_checks(
+            &self.checks,
+            self.jwks_file.as_deref(),
+            self.oidc_providers_file.as_deref(),
+        )
+        .map_err(anyhow::Error::msg)
+    }
+}
+
+#[derive(clap::Args, Debug)]
+struct GenerateArgs {
+    /// Byte code directory
+    #[arg(short, long, required_unless_present = "check_config")]
+    byte_code_dir: Option<String>,
+
+    #[command(flatten)]
+    config: ConfigArgs,
+
+    /// Save results to file. `-` writes only the combined genesis.json to stdout,
+    /// with the log lines on stderr
+    #[arg(short, long, required_unless_present_any = ["dry_run", "check_config"])]
+    output: Option<String>,
+
+    #[command(flatten)]
+    checks: CheckArgs,
+
+    /// Stream state to disk and drop intermediates early to reduce peak memory
+    #[arg(long)]
+    low_memory: bool,
+
+    /// Deploy and initialize without writing any output file, then log a summary
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Only load and validate the config and log a summary of its validators,
+    /// without reading any byte code or running the EVM
+    #[arg(long, conflicts_with = "dry_run")]
+    check_config: bool,
+
+    /// Also write the ordered genesis transactions to genesis_txns.json for replay
+    #[arg(long)]
+    export_txns: bool,
+
+    /// Also write reverts.json, the account fields and storage slots each
+    /// initialization transaction changed, with the values they had before it
+    #[arg(long)]
+    export_reverts: bool,
+
+    /// How contract code is placed at the system addresses. `construct` runs the
+    /// constructors from the Foundry artifacts found in the byte code directory,
+    /// `inject` places the `.hex` runtime bytecode as is
+    #[arg(long, value_enum, default_value_t = DeployMode::Construct)]
+    deploy_mode: DeployMode,
+
+    /// Where inject mode reads the runtime bytecode from: `hex` for `<Name>.hex`
+    /// files, `foundry` for the `deployedBytecode` of the Foundry artifacts
+    #[arg(long, value_enum, default_value_t = ArtifactFormat::Hex)]
+    artifact_format: ArtifactFormat,
+
+    /// Attach EIP-2930 access lists, derived by a dry run, to the genesis transactions
+    #[arg(long)]
+    access_lists: bool,
+
+    /// Exit non-zero if any post-generation check finds the state differs from the
+    /// config or input files, instead of only logging the difference
+    #[arg(long)]
+    verify: bool,
+
+    /// Fail on suspicious state instead of only logging it
+    #[arg(long)]
+    strict: bool,
+
+    /// Check every transaction of a batch before failing, reporting all that failed
+    /// instead of stopping at the first
+    #[arg(long)]
+    keep_going: bool,
+
+    /// Largest runtime code size in bytes. Raise it above the EIP-170 default for
+    /// clients that accept larger contracts; it is recorded in genesis_txns.json
+    #[arg(long, default_value_t = execute::DEFAULT_MAX_CODE_SIZE)]
+    max_code_size: usize,
+
+    /// Only warn about runtime code larger than --max-code-size instead of failing
+    #[arg(long)]
+    allow_oversized_code: bool,
+
+    /// Also write genesis.json, a single file holding the config, accounts,
+    /// contracts and the chain id and tool version that produced them
+    #[arg(long)]
+    combined: bool,
+
+    /// Print a `[step/total] contract status` line to stderr as each contract is
+    /// deployed
+    #[arg(long)]
+    progress: bool,
+
+    /// Extra state format to write alongside the native output files
+    #[arg(long, value_enum, default_value_t = OutputFormat::Native)]
+    format: OutputFormat,
+
+    /// Also write the generated validator set to node_validators.toml, in the shape
+    /// the node config expects
+    #[arg(long)]
+    export_node_validators: bool,
+
+    /// Sleep this many microseconds on every state read of the genesis
+    /// initialization, to benchmark it under simulated slow storage
+    #[arg(long, default_value_t = 0, hide = true)]
+    sim_latency_us: u64,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Deploy and initialize the system contracts and write the genesis files. Also
+    /// what runs when no subcommand is given
+    Generate(GenerateArgs),
+    /// Run the post-generation checks against the bundle_state.json and
+    /// genesis_accounts.json of an earlier generation, without generating again
+    Verify {
+        /// Output directory of a previous generation, where both files are looked up
+        /// unless given explicitly
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// genesis_accounts.json to verify
+        #[arg(short, long, required_unless_present = "output")]
+        accounts_file: Option<String>,
+
+        /// bundle_state.json of the same generation
+        #[arg(long, required_unless_present = "output")]
+        bundle_state_file: Option<String>,
+
+        #[command(flatten)]
+        config: ConfigArgs,
+
+        #[command(flatten)]
+        checks: CheckArgs,
+    },
+    /// Apply system calls from a JSON file on top of an earlier generation and write
+    /// the updated bundle_state.json, genesis_accounts.json and genesis_contracts.json
+    Patch {
+        /// Output directory of a previous generation, where both input files are
+        /// looked up unless given explicitly
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// genesis_accounts.json to patch
+        #[arg(short, long, required_unless_present = "output")]
+        accounts_file: Option<String>,
+
+        /// bundle_state.json of the same generation
+        #[arg(long, required_unless_present = "output")]
+        bundle_state_file: Option<String>,
+
+        /// JSON array of the calls to apply, each `{"contract", "selector", "args"}`
+        #[arg(long)]
+        calls_file: String,
+
+        /// Directory the patched files are written to, which may be the input one
+        #[arg(long)]
+        patched_output: String,
+
+        #[command(flatten)]
+        config: ConfigArgs,
+    },
+    /// Summarize the contracts and storage of a generated genesis_accounts.json
+    Inspect {
+        /// Generated genesis_accounts.json
+        #[arg(short, long)]
+        accounts_file: String,
+
+        /// Also log every storage slot of each contract
+        #[arg(long)]
+        storage: bool,
+    },
+    /// Report the storage slots and code hashes that differ per address between two
+    /// generated genesis_accounts.json files
+    Diff {
+        /// genesis_accounts.json of the earlier run
+        #[arg(long)]
+        old: String,
+
+        /// genesis_accounts.json of the later run
+        #[arg(long)]
+        new: String,
+
+        /// Also write the report to this file
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// List every custom error defined across the contract ABIs
+    ListErrors {
+        /// Directory containing the contract ABIs or Foundry artifacts
+        #[arg(short, long)]
+        byte_code_dir: String,
+
+        /// Write the error catalog as JSON to this file
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Estimate how long a client takes to load a generated genesis and compute its
+    /// state root. Diagnostic only, not part of generation
+    ProfileLoad {
+        /// Generated genesis_accounts.json
+        #[arg(short, long)]
+        accounts_file: String,
+
+        /// Simulated storage latency in microseconds, applied to every account and
+        /// storage slot read
+        #[arg(long, default_value_t = 0)]
+        latency_us: u64,
+    },
+    /// Encode the genesis and JWK calls from built-in samples, decode them back and
+    /// check they round trip. Needs no input files
+    Selftest,
+    /// Recompute deployment.json and summary.json from the genesis_accounts.json and
+    /// genesis_contracts.json already in an output directory, without running the EVM
+    Report {
+        /// Output directory of a previous generation
+        #[arg(short, long)]
+        output: String,
+    },
+    /// Write a template genesis config with placeholder validators, and notes next
+    /// to it on where each validator field comes from
+    Scaffold {
+        /// Number of validators
+        #[arg(short = 'n', long, required_unless_present = "private_keys_file")]
+        count: Option<usize>,
+
+        /// File of hex secp256k1 private keys, one per validator and line, to derive
+        /// the validator addresses from
+        #[arg(long)]
+        private_keys_file: Option<String>,
+
+        /// Config file to write; the notes go to the same path with a .md extension
+        #[arg(short, long, default_value = "genesis_config.json")]
+        config_file: String,
+    },
+    /// Compare the code and storage a running node serves against the generated
+    /// genesis_contracts.json and genesis_accounts.json
+    #[cfg(feature = "rpc")]
+    VerifyRpc {
+        /// JSON-RPC endpoint of the node, e.g. http://127.0.0.1:8545
+        #[arg(short, long)]
+        endpoint: String,
+
+        /// Directory holding the generated genesis files
+        #[arg(short, long)]
+        output_dir: String,
+
+        /// Block number or tag the state is read at
+        #[arg(long, default_value = "0x0")]
+        block: String,
+
+        /// Storage slots compared per contract, lowest slots first
+        #[arg(long, default_value_t = 16)]
+        max_slots: usize,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
+    #[cfg(feature = "schema")]
+    if args.print_schema {
+        let schema = gravity_genesis::genesis::genesis_config_schema();
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+        return Ok(());
+    }
+
     // Initialize logging
     let level = if args.debug {
         Level::DEBUG
@@ -85,7 +492,7 @@ async fn main() -> Result<()> {
     // Set up logging and create log guard for proper cleanup
     let log_guard = if let Some(log_file_path) = &args.log_file {
         // Create log file directory if it doesn't exist
-        if let Some(parent) = std::path::Path::new(log_file_path).parent() {
+        if let Some(parent) = Path::new(log_file_path).parent() {
             if !parent.exists() {
                 fs::create_dir_all(parent)?;
             }
@@ -95,17 +502,31 @@ async fn main() -> Result<()> {
         let file_appender = tracing_appender::rolling::never("", log_file_path);
         let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
 
-        tracing_subscriber::fmt()
+        let subscriber = tracing_subscriber::fmt()
             .with_max_level(level)
             .with_writer(non_blocking)
-            .with_ansi(false)
-            .init();
+            .with_ansi(false);
+        match args.log_format {
+            LogFormat::Text => subscriber.init(),
+            LogFormat::Json => subscriber.json().init(),
+        }
 
         info!("Logging to file: {}", log_file_path);
         LogGuard::new(Some(guard))
     } else {
-        // Console-only logging
-        tracing_subscriber::fmt().with_max_level(level).init();
+        // Console-only logging, on stderr when stdout carries the genesis
+        let writer = if args.writes_to_stdout() {
+            BoxMakeWriter::new(std::io::stderr)
+        } else {
+            BoxMakeWriter::new(std::io::stdout)
+        };
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_writer(writer);
+        match args.log_format {
+            LogFormat::Text => subscriber.init(),
+            LogFormat::Json => subscriber.json().init(),
+        }
         LogGuard::new(None)
     };
 
@@ -129,7 +550,76 @@ async fn main() -> Result<()> {
     info!("Starting Gravity Genesis Binary");
 
     // Run the main logic
-    let result = run_main_logic(&args).await;
+    let result = match &args.command {
+        Some(Command::Generate(generate)) => run_generate(generate).await,
+        Some(Command::Verify {
+            output,
+            accounts_file,
+            bundle_state_file,
+            config,
+            checks,
+        }) => {
+            // `required_unless_present` ensures `output` is set if either file is not
+            let in_output = |file: &Option<String>, name: &str| {
+                file.clone()
+                    .unwrap_or_else(|| format!("{}/{}", output.as_deref().unwrap(), name))
+            };
+            run_verify(
+                &in_output(accounts_file, "genesis_accounts.json"),
+                &in_output(bundle_state_file, "bundle_state.json"),
+                config,
+                checks,
+            )
+        }
+        Some(Command::Patch {
+            output,
+            accounts_file,
+            bundle_state_file,
+            calls_file,
+            patched_output,
+            config,
+        }) => {
+            let in_output = |file: &Option<String>, name: &str| {
+                file.clone()
+                    .unwrap_or_else(|| format!("{}/{}", output.as_deref().unwrap(), name))
+            };
+            run_patch(
+                &in_output(accounts_file, "genesis_accounts.json"),
+                &in_output(bundle_state_file, "bundle_state.json"),
+                calls_file,
+                patched_output,
+                config,
+            )
+        }
+        Some(Command::Inspect {
+            accounts_file,
+            storage,
+        }) => run_inspect(accounts_file, *storage),
+        Some(Command::Diff { old, new, output }) => run_diff(old, new, output.as_deref()),
+        Some(Command::ListErrors {
+            byte_code_dir,
+            output,
+        }) => run_list_errors(byte_code_dir, output.as_deref()),
+        Some(Command::ProfileLoad {
+            accounts_file,
+            latency_us,
+        }) => run_profile_load(accounts_file, *latency_us),
+        Some(Command::Selftest) => run_selftest(),
+        Some(Command::Report { output }) => run_report(output),
+        Some(Command::Scaffold {
+            count,
+            private_keys_file,
+            config_file,
+        }) => run_scaffold(*count, private_keys_file.as_deref(), config_file),
+        #[cfg(feature = "rpc")]
+        Some(Command::VerifyRpc {
+            endpoint,
+            output_dir,
+            block,
+            max_slots,
+        }) => run_verify_rpc(endpoint, output_dir, block, *max_slots).await,
+        None => run_generate(&args.generate).await,
+    };
 
     // Ensure logs are flushed before exiting
     info!("Main execution completed");
@@ -138,36 +628,390 @@ async fn main() -> Result<()> {
     result
 }
 
-async fn run_main_logic(args: &Args) -> Result<()> {
-    info!("Reading Genesis configuration from: {}", args.config_file);
-    let config_content = fs::read_to_string(&args.config_file)?;
-    let config: GenesisConfig = serde_json::from_str(&config_content)?;
-    info!("Genesis configuration loaded successfully");
-    info!("Genesis configuration: {:?}", config);
+/// Generate in memory and print the combined genesis.json, writing no other file.
+/// Flags that only add output files are rejected rather than silently dropped
+fn run_generate_to_stdout(
+    args: &GenerateArgs,
+    byte_code_dir: &str,
+    config: &GenesisConfig,
+    options: execute::GenerateOptions,
+) -> Result<()> {
+    let file_flags = [
+        ("--low-memory", args.low_memory),
+        ("--export-txns", args.export_txns),
+        ("--export-reverts", args.export_reverts),
+        ("--export-node-validators", args.export_node_validators),
+        ("--format geth", args.format != OutputFormat::Native),
+    ];
+    let conflicts: Vec<&str> = file_flags
+        .iter()
+        .filter(|(_, set)| *set)
+        .map(|(flag, _)| *flag)
+        .collect();
+    if !conflicts.is_empty() {
+        anyhow::bail!(
+            "--output {} writes only genesis.json to stdout, drop {}",
+            STDOUT_OUTPUT,
+            conflicts.join(", ")
+        );
+    }
 
-    if let Some(output_dir) = &args.output {
-        if !fs::metadata(&output_dir).is_ok() {
-            fs::create_dir_all(&output_dir).unwrap();
+    let options = execute::GenerateOptions {
+        combined: true,
+        ..options
+    };
+    let (db, bundle_state, artifacts) = execute::genesis_generate_in_memory(
+        byte_code_dir,
+        config,
+        args.checks.jwks_file.clone(),
+        args.checks.oidc_providers_file.clone(),
+        &options,
+    )?;
+    post_genesis::verify_validator_count(&db, bundle_state.clone(), config)?;
+    if post_genesis::check_enabled(&args.checks.checks, VerifyCheck::StakeConfig) {
+        post_genesis::verify_stake_config(&db, bundle_state.clone(), config)?;
+    }
+    let verified = post_genesis::verify_result(
+        db,
+        bundle_state,
+        config,
+        args.checks.jwks_file.clone(),
+        args.checks.oidc_providers_file.clone(),
+        &args.checks.checks,
+    );
+    if let Err(mismatches) = verified {
+        for mismatch in &mismatches {
+            tracing::error!("Mismatch: {}", mismatch);
+        }
+        if args.verify {
+            anyhow::bail!("{} post-genesis mismatches found", mismatches.len());
         }
-        info!("Output directory: {}", output_dir);
+        tracing::warn!(
+            "{} post-genesis mismatches found, pass --verify to fail on them",
+            mismatches.len()
+        );
+    }
+
+    // `combined` is forced on above
+    println!("{}", artifacts.combined.unwrap());
+    info!("Gravity Genesis Binary completed successfully");
+    Ok(())
+}
+
+fn run_diff(old: &str, new: &str, output: Option<&str>) -> Result<()> {
+    info!("Comparing genesis accounts of {} and {}", old, new);
+    let old = diff::read_accounts(old).map_err(anyhow::Error::msg)?;
+    let new = diff::read_accounts(new).map_err(anyhow::Error::msg)?;
+    let report = diff::render_diff(&diff::diff_accounts(&old, &new));
+    print!("{}", report);
+
+    if let Some(output) = output {
+        fs::write(output, &report)?;
+        info!("Genesis diff written to: {}", output);
+    }
+    Ok(())
+}
+
+fn run_list_errors(byte_code_dir: &str, output: Option<&str>) -> Result<()> {
+    info!("Collecting custom errors from: {}", byte_code_dir);
+    let catalog = error_catalog::collect_errors(byte_code_dir).map_err(anyhow::Error::msg)?;
+    error_catalog::print_error_catalog(&catalog);
+
+    if let Some(output) = output {
+        serde_json::to_writer_pretty(fs::File::create(output)?, &catalog)?;
+        info!("Error catalog written to: {}", output);
     }
+    Ok(())
+}
 
-    let (db, bundle_state) = execute::genesis_generate(
-        &args.byte_code_dir,
-        &args.output.as_ref().unwrap(),
+fn run_profile_load(accounts_file: &str, latency_us: u64) -> Result<()> {
+    info!("Profiling genesis load of: {}", accounts_file);
+    let latency = Duration::from_micros(latency_us);
+    let load_profile = profile::profile_load(accounts_file, latency).map_err(anyhow::Error::msg)?;
+    load_profile.print(latency);
+    Ok(())
+}
+
+fn run_verify(
+    accounts_file: &str,
+    bundle_state_file: &str,
+    config: &ConfigArgs,
+    checks: &CheckArgs,
+) -> Result<()> {
+    let config = config.load()?;
+    checks.validate()?;
+    let db = post_genesis::load_accounts_db(accounts_file)?;
+    let bundle_state = post_genesis::load_bundle_state(bundle_state_file)?;
+
+    post_genesis::verify_validator_count(&db, bundle_state.clone(), &config)?;
+    if post_genesis::check_enabled(&checks.checks, VerifyCheck::StakeConfig) {
+        post_genesis::verify_stake_config(&db, bundle_state.clone(), &config)?;
+    }
+    let verified = post_genesis::verify_result(
+        db,
+        bundle_state,
         &config,
-        args.jwks_file.clone(),
-        args.oidc_providers_file.clone(),
+        checks.jwks_file.clone(),
+        checks.oidc_providers_file.clone(),
+        &checks.checks,
     );
+    if let Err(mismatches) = verified {
+        for mismatch in &mismatches {
+            tracing::error!("Mismatch: {}", mismatch);
+        }
+        anyhow::bail!("{} post-genesis mismatches found", mismatches.len());
+    }
+    info!("Genesis in {} matches the config", accounts_file);
+    Ok(())
+}
 
-    post_genesis::verify_result(
+fn run_inspect(accounts_file: &str, storage: bool) -> Result<()> {
+    info!("Inspecting genesis accounts of: {}", accounts_file);
+    let (db, deployment, summary) =
+        report::inspect_accounts(accounts_file).map_err(anyhow::Error::msg)?;
+    for contract in &deployment {
+        info!(
+            "{} at {:?}: {} bytes of code, {} storage slots, balance {} wei",
+            contract.name.as_deref().unwrap_or("<user contract>"),
+            contract.address,
+            contract.code_size,
+            contract.storage_slots,
+            contract.balance
+        );
+        if storage {
+            let mut slots: Vec<_> = db.accounts[&contract.address].storage.iter().collect();
+            slots.sort();
+            for (slot, value) in slots {
+                info!("  {:#x} = {:#x}", slot, value);
+            }
+        }
+    }
+    info!(
+        "{} accounts, {} contracts, {} storage slots, {} wei in total",
+        summary.accounts, summary.contracts, summary.storage_slots, summary.total_balance
+    );
+    info!("Genesis state root: {:?}", summary.state_root);
+    Ok(())
+}
+
+fn run_patch(
+    accounts_file: &str,
+    bundle_state_file: &str,
+    calls_file: &str,
+    patched_output: &str,
+    config: &ConfigArgs,
+) -> Result<()> {
+    let config = config.load()?;
+    let calls = patch::read_patch_calls(calls_file).map_err(anyhow::Error::msg)?;
+    let db = post_genesis::load_accounts_db(accounts_file)?;
+    let bundle_state = post_genesis::load_bundle_state(bundle_state_file)?;
+
+    let bundle_state =
+        patch::apply_patch(&db, bundle_state, &config, &calls).map_err(anyhow::Error::msg)?;
+    fs::create_dir_all(patched_output)?;
+    patch::write_patched(patched_output, &db, &bundle_state).map_err(anyhow::Error::msg)?;
+    Ok(())
+}
+
+fn run_report(output_dir: &str) -> Result<()> {
+    info!("Regenerating reports in: {}", output_dir);
+    let summary = report::write_reports(output_dir).map_err(anyhow::Error::msg)?;
+    info!("Genesis state root: {:?}", summary.state_root);
+    Ok(())
+}
+
+fn run_scaffold(
+    count: Option<usize>,
+    private_keys_file: Option<&str>,
+    config_file: &str,
+) -> Result<()> {
+    let private_keys = match private_keys_file {
+        Some(path) => scaffold::read_private_keys(path).map_err(anyhow::Error::msg)?,
+        None => Vec::new(),
+    };
+    // `required_unless_present` ensures either the count or the keys are given
+    let count = count.unwrap_or(private_keys.len());
+    let notes =
+        scaffold::write_scaffold(config_file, count, &private_keys).map_err(anyhow::Error::msg)?;
+    info!(
+        "Replace the placeholders in {} as {} explains",
+        config_file, notes
+    );
+    Ok(())
+}
+
+fn run_selftest() -> Result<()> {
+    let checks = selftest::run_selftest();
+    if selftest::print_selftest(&checks) {
+        Ok(())
+    } else {
+        anyhow::bail!("encoder selftest failed")
+    }
+}
+
+#[cfg(feature = "rpc")]
+async fn run_verify_rpc(
+    endpoint: &str,
+    output_dir: &str,
+    block: &str,
+    max_slots: usize,
+) -> Result<()> {
+    use gravity_genesis::rpc_verify;
+
+    info!("Verifying genesis in {} against {}", output_dir, endpoint);
+    let client = rpc_verify::JsonRpcClient::new(endpoint);
+    let divergences = rpc_verify::verify_rpc(&client, output_dir, block, max_slots)
+        .await
+        .map_err(anyhow::Error::msg)?;
+    if divergences.is_empty() {
+        info!("Node state matches the generated genesis");
+        return Ok(());
+    }
+    for divergence in &divergences {
+        tracing::error!("{}", divergence);
+    }
+    anyhow::bail!(
+        "node state diverges from the generated genesis in {} places",
+        divergences.len()
+    )
+}
+
+async fn run_generate(args: &GenerateArgs) -> Result<()> {
+    if args.check_config {
+        let config = args.config.load()?;
+        args.checks.validate()?;
+        config.summarize();
+        return Ok(());
+    }
+    let byte_code_dir = args
+        .byte_code_dir
+        .as_deref()
+        .expect("--byte-code-dir is required");
+    execute::preflight_check(byte_code_dir, args.deploy_mode, args.artifact_format)?;
+
+    let config = args.config.load()?;
+    args.checks.validate()?;
+
+    let options = execute::GenerateOptions {
+        low_memory: args.low_memory,
+        export_transactions: args.export_txns,
+        export_reverts: args.export_reverts,
+        deploy_mode: args.deploy_mode,
+        artifact_format: args.artifact_format,
+        access_lists: args.access_lists,
+        strict: args.strict,
+        max_code_size: Some(args.max_code_size),
+        allow_oversized_code: args.allow_oversized_code,
+        format: args.format,
+        continue_on_failure: args.keep_going,
+        combined: args.combined,
+        sim_latency: Duration::from_micros(args.sim_latency_us),
+        progress: args.progress.then(|| {
+            ProgressCallback::new(|event| {
+                eprintln!(
+                    "[{}/{}] {} {}",
+                    event.step, event.total, event.contract_name, event.status
+                )
+            })
+        }),
+        ..Default::default()
+    };
+    if args.dry_run {
+        let summary = execute::genesis_dry_run(
+            byte_code_dir,
+            &config,
+            args.checks.jwks_file.clone(),
+            args.checks.oidc_providers_file.clone(),
+            &options,
+        )?;
+        info!("=== Dry run summary ===");
+        info!("Contracts deployed: {}", summary.contracts);
+        info!("Accounts in genesis state: {}", summary.accounts);
+        for transaction in &summary.transactions {
+            match transaction.elapsed {
+                Some(elapsed) => info!(
+                    "Transaction {}: {} gas in {:?}",
+                    transaction.index, transaction.gas_used, elapsed
+                ),
+                None => info!(
+                    "Transaction {}: {} gas",
+                    transaction.index, transaction.gas_used
+                ),
+            }
+        }
+        return Ok(());
+    }
+    if args.output.as_deref() == Some(STDOUT_OUTPUT) {
+        return run_generate_to_stdout(args, byte_code_dir, &config, options);
+    }
+
+    // Required by clap unless one of the modes handled above is set
+    let Some(output_dir) = args.output.as_deref() else {
+        anyhow::bail!("--output is required unless --dry-run or --check-config is given");
+    };
+    fs::create_dir_all(output_dir)?;
+    info!("Output directory: {}", output_dir);
+    utils::check_output_dir(output_dir, byte_code_dir).map_err(anyhow::Error::msg)?;
+
+    let (db, bundle_state, tx_reports) = execute::genesis_generate(
+        byte_code_dir,
+        output_dir,
+        &config,
+        args.checks.jwks_file.clone(),
+        args.checks.oidc_providers_file.clone(),
+        &options,
+    )?;
+
+    info!(
+        "{} genesis transactions used {} gas, per transaction in {}/{}",
+        tx_reports.len(),
+        tx_reports.iter().map(|report| report.gas_used).sum::<u64>(),
+        output_dir,
+        metrics::GAS_REPORT_FILE
+    );
+    let fail_verify = |e: GenesisError| {
+        let report = FailureReport::new(FailurePhase::Verify, e.to_string());
+        write_failure_report(output_dir, &report);
+        anyhow::Error::from(report)
+    };
+    post_genesis::verify_validator_count(&db, bundle_state.clone(), &config)
+        .map_err(fail_verify)?;
+    if post_genesis::check_enabled(&args.checks.checks, VerifyCheck::StakeConfig) {
+        post_genesis::verify_stake_config(&db, bundle_state.clone(), &config)
+            .map_err(fail_verify)?;
+    }
+    if args.export_node_validators {
+        post_genesis::export_node_validators(&db, bundle_state.clone(), &config, output_dir)?;
+    }
+
+    let verified = post_genesis::verify_result(
         db,
         bundle_state,
         &config,
-        args.jwks_file.clone(),
-        args.oidc_providers_file.clone(),
+        args.checks.jwks_file.clone(),
+        args.checks.oidc_providers_file.clone(),
+        &args.checks.checks,
     );
+    if let Err(mismatches) = verified {
+        for mismatch in &mismatches {
+            tracing::error!("Mismatch: {}", mismatch);
+        }
+        if args.verify {
+            let mismatches: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+            return Err(fail_verify(GenesisError::Verify(format!(
+                "{} post-genesis mismatches: {}",
+                mismatches.len(),
+                mismatches.join("; ")
+            ))));
+        }
+        tracing::warn!(
+            "{} post-genesis mismatches found, pass --verify to fail on them",
+            mismatches.len()
+        );
+    }
+    report::write_reports(output_dir).map_err(anyhow::Error::msg)?;
 
+    failure::remove_stale_failure_report(output_dir);
     info!("Gravity Genesis Binary completed successfully");
     Ok(())
 }
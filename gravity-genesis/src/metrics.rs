@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use revm_primitives::{Address, ExecutionResult, FixedBytes};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{decode_revert_reason, known_error_name};
+
+pub const CONTRACT_METRICS_FILE: &str = "contract_metrics.csv";
+pub const GAS_REPORT_FILE: &str = "gas_report.json";
+
+/// Size and gas figures of one system contract, one row of `contract_metrics.csv`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractMetrics {
+    pub name: String,
+    pub address: Address,
+    /// Gas used by the creation transaction; `None` when code was injected
+    pub deploy_gas: Option<u64>,
+    /// Gas of the genesis transactions sent directly to this contract. Inner calls
+    /// are attributed to the outermost callee, so `Genesis` carries most of it.
+    pub init_gas: u64,
+    pub runtime_code_size: usize,
+    pub storage_slots: usize,
+}
+
+/// Render the metrics as CSV with a header row
+pub fn contract_metrics_csv(metrics: &[ContractMetrics]) -> String {
+    let mut csv =
+        String::from("contract,address,deploy_gas,init_gas,runtime_code_size,storage_slots\n");
+    for m in metrics {
+        csv.push_str(&format!(
+            "{},{:?},{},{},{},{}\n",
+            m.name,
+            m.address,
+            m.deploy_gas.map(|gas| gas.to_string()).unwrap_or_default(),
+            m.init_gas,
+            m.runtime_code_size,
+            m.storage_slots
+        ));
+    }
+    csv
+}
+
+/// Outcome and gas of one genesis transaction, an entry of `gas_report.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxReport {
+    /// Position of the transaction, as numbered in `genesis_txns.json`
+    pub index: usize,
+    pub success: bool,
+    pub gas_used: u64,
+    /// First four bytes of the revert data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_selector: Option<FixedBytes<4>>,
+    /// A decoded `Error(string)`, `Panic(uint256)` or system contract custom error,
+    /// the bare name of a custom error whose parameters do not decode, or the halt
+    /// reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Wall time of an initialization transaction, `None` for creations. Not written
+    /// to `gas_report.json`, which stays the same between runs
+    #[serde(skip)]
+    pub elapsed: Option<Duration>,
+}
+
+impl TxReport {
+    pub fn from_result(index: usize, result: &ExecutionResult) -> Self {
+        let (revert_selector, revert_reason) = match result {
+            ExecutionResult::Success { .. } => (None, None),
+            ExecutionResult::Revert { output, .. } => {
+                let selector = output.get(0..4).map(FixedBytes::<4>::from_slice);
+                let reason = decode_revert_reason(output).or_else(|| {
+                    selector
+                        .and_then(|selector| known_error_name(selector.as_slice()))
+                        .map(str::to_string)
+                });
+                (selector, reason)
+            }
+            ExecutionResult::Halt { reason, .. } => (None, Some(format!("{:?}", reason))),
+        };
+        Self {
+            index,
+            success: result.is_success(),
+            gas_used: result.gas_used(),
+            revert_selector,
+            revert_reason,
+            elapsed: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::GENESIS_ADDR;
+    use revm_primitives::{Bytes, hex};
+
+    #[test]
+    fn test_contract_metrics_csv() {
+        let csv = contract_metrics_csv(&[ContractMetrics {
+            name: "Genesis".to_string(),
+            address: GENESIS_ADDR,
+            deploy_gas: None,
+            init_gas: 1_234_567,
+            runtime_code_size: 4096,
+            storage_slots: 3,
+        }]);
+        assert_eq!(
+            csv,
+            "contract,address,deploy_gas,init_gas,runtime_code_size,storage_slots\n\
+             Genesis,0x0000000000000000000000000000000000002008,,1234567,4096,3\n"
+        );
+    }
+
+    #[test]
+    fn test_tx_report_from_result() {
+        let report = TxReport::from_result(
+            3,
+            &ExecutionResult::Revert {
+                gas_used: 21_000,
+                output: Bytes::from(hex::decode("49fd36f2").unwrap()),
+            },
+        );
+        assert!(!report.success);
+        assert_eq!(report.gas_used, 21_000);
+        assert_eq!(
+            report.revert_selector,
+            Some(FixedBytes::from([0x49, 0xfd, 0x36, 0xf2]))
+        );
+        assert_eq!(report.revert_reason.as_deref(), Some("OnlySystemCaller"));
+
+        // Error(string) "nope"
+        let output = hex::decode(
+            "08c379a0\
+             0000000000000000000000000000000000000000000000000000000000000020\
+             0000000000000000000000000000000000000000000000000000000000000004\
+             6e6f706500000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        let report = TxReport::from_result(
+            4,
+            &ExecutionResult::Revert {
+                gas_used: 0,
+                output: output.into(),
+            },
+        );
+        assert!(report.revert_reason.unwrap().contains("nope"));
+    }
+}
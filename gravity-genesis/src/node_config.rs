@@ -0,0 +1,94 @@
+use crate::{genesis::IValidatorManager::ValidatorInfo, utils::ChecksumAddress};
+use revm_primitives::hex;
+
+pub const NODE_VALIDATORS_FILE: &str = "node_validators.toml";
+
+/// Render the validator set as the `[[validators]]` tables our node config reads,
+/// one table per validator in validator set order
+///
+/// Keys mirror the genesis config fields. Voting power is in wei and written as a
+/// string, since it does not fit a TOML integer.
+pub fn node_validators_toml(validators: &[ValidatorInfo]) -> Result<String, String> {
+    let mut toml = String::from("# Generated by gravity-genesis from the genesis validator set\n");
+    for (index, validator) in validators.iter().enumerate() {
+        let network_address = |field: &str, bytes: &[u8]| {
+            if bytes.is_empty() {
+                return Ok(String::new());
+            }
+            bcs::from_bytes::<String>(bytes)
+                .map_err(|e| format!("validator {} has an undecodable {}: {}", index, field, e))
+        };
+        let validator_network_address = network_address(
+            "validatorNetworkAddresses",
+            &validator.validatorNetworkAddresses,
+        )?;
+        let fullnode_network_address = network_address(
+            "fullnodeNetworkAddresses",
+            &validator.fullnodeNetworkAddresses,
+        )?;
+        let fields = [
+            ("address", ChecksumAddress(validator.operator).to_string()),
+            (
+                "consensus_public_key",
+                String::from_utf8_lossy(&validator.consensusPublicKey).into_owned(),
+            ),
+            ("voting_power", validator.votingPower.to_string()),
+            ("validator_network_address", validator_network_address),
+            ("fullnode_network_address", fullnode_network_address),
+            ("aptos_address", hex::encode(&validator.aptosAddress)),
+        ];
+
+        toml.push_str("\n[[validators]]\n");
+        for (key, value) in fields {
+            toml.push_str(&format!("{} = {}\n", key, toml_string(&value)));
+        }
+    }
+    Ok(toml)
+}
+
+/// A TOML basic string; JSON string escaping is a subset of TOML's
+fn toml_string(value: &str) -> String {
+    serde_json::to_string(value).expect("strings always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genesis::IValidatorManager::{Commission, ValidatorStatus};
+    use revm_primitives::{Address, Bytes, U256};
+
+    #[test]
+    fn test_node_validators_toml() {
+        let validator = ValidatorInfo {
+            consensusPublicKey: b"851d4193".to_vec().into(),
+            commission: Commission {
+                rate: 0,
+                maxRate: 0,
+                maxChangeRate: 0,
+            },
+            moniker: String::new(),
+            registered: true,
+            stakeCreditAddress: Address::ZERO,
+            status: ValidatorStatus::ACTIVE,
+            votingPower: U256::from(20_000),
+            validatorIndex: U256::ZERO,
+            updateTime: U256::ZERO,
+            operator: Address::repeat_byte(0xab),
+            validatorNetworkAddresses: bcs::to_bytes("/ip4/127.0.0.1/tcp/2024").unwrap().into(),
+            fullnodeNetworkAddresses: Bytes::new(),
+            aptosAddress: vec![0xab; 2].into(),
+        };
+        assert_eq!(
+            node_validators_toml(&[validator]).unwrap(),
+            "# Generated by gravity-genesis from the genesis validator set\n\
+             \n\
+             [[validators]]\n\
+             address = \"0xABaBaBaBABabABabAbAbABAbABabababaBaBABaB\"\n\
+             consensus_public_key = \"851d4193\"\n\
+             voting_power = \"20000\"\n\
+             validator_network_address = \"/ip4/127.0.0.1/tcp/2024\"\n\
+             fullnode_network_address = \"\"\n\
+             aptos_address = \"abab\"\n"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+
+use alloy_json_abi::Function;
+use revm::{
+    InMemoryDB,
+    db::{BundleState, PlainAccount},
+};
+use revm_primitives::{Address, Bytes, U256, hex};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    execute::{prepare_block_env, prepare_env, sorted_accounts},
+    genesis::GenesisConfig,
+    utils::{
+        ChecksumAddress, SYSTEM_CALLER, analyze_txn_result, execute_revm_sequential,
+        new_system_call_txn, with_caller, with_gas_limit,
+    },
+};
+
+/// One system call of a patch file, made from the configured system caller
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchCall {
+    /// System contract name as in `CONTRACTS`, or a `0x` address
+    pub contract: String,
+    /// Function signature such as `setFoo(uint256)`, or a `0x` 4 byte selector
+    pub selector: String,
+    /// ABI encoded arguments following the selector, as hex
+    #[serde(default)]
+    pub args: String,
+}
+
+impl PatchCall {
+    /// Address the call is made to, with system contracts named as in `contracts`
+    pub fn target(&self, contracts: &[(&str, Address)]) -> Result<Address, String> {
+        if let Some((_, address)) = contracts.iter().find(|(name, _)| *name == self.contract) {
+            return Ok(*address);
+        }
+        self.contract.parse().map_err(|_| {
+            format!(
+                "{} is neither a system contract nor an address",
+                self.contract
+            )
+        })
+    }
+
+    /// The selector followed by the arguments
+    pub fn call_data(&self) -> Result<Bytes, String> {
+        let selector: [u8; 4] = if self.selector.contains('(') {
+            Function::parse(&self.selector)
+                .map_err(|e| format!("invalid function signature {}: {}", self.selector, e))?
+                .selector()
+                .0
+        } else {
+            hex::decode(&self.selector)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| {
+                    format!(
+                        "{} is neither a function signature nor a 4 byte selector",
+                        self.selector
+                    )
+                })?
+        };
+        let args = hex::decode(&self.args)
+            .map_err(|e| format!("arguments of {} are not hex: {}", self.selector, e))?;
+        Ok([selector.as_slice(), &args].concat().into())
+    }
+}
+
+/// The calls of a patch file, a JSON array of `PatchCall`
+pub fn read_patch_calls(path: &str) -> Result<Vec<PatchCall>, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("failed to parse {}: {}", path, e))
+}
+
+/// Execute `calls` in order on top of an earlier generation, its accounts loaded as
+/// `db` and its `bundle_state.json`, and return that bundle with their changes
+/// merged in. Every call must succeed
+pub fn apply_patch(
+    db: &InMemoryDB,
+    bundle_state: BundleState,
+    config: &GenesisConfig,
+    calls: &[PatchCall],
+) -> Result<BundleState, String> {
+    let contracts = config.contract_addresses();
+    let txs = calls
+        .iter()
+        .enumerate()
+        .map(|(index, call)| {
+            let tx = call
+                .target(&contracts)
+                .and_then(|target| Ok(new_system_call_txn(target, call.call_data()?)))
+                .map_err(|e| format!("call {}: {}", index, e))?;
+            Ok(with_caller(
+                with_gas_limit(tx, config.gas_limit()),
+                config.system_caller(),
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut env = prepare_env(config.chain_id());
+    env.tx.gas_limit = config.gas_limit();
+    env.tx.caller = config.system_caller();
+    prepare_block_env(&mut env, config).map_err(|e| e.to_string())?;
+    if let Some(timestamp) = config.genesis_timestamp {
+        env.block.timestamp = U256::from(timestamp);
+    }
+    let (results, mut bundle_state) =
+        execute_revm_sequential(db, config.spec_id(), env, &txs, Some(bundle_state))
+            .map_err(|e| format!("EVM error: {:?}", e.map_db_err(|_| "database error")))?;
+    for (index, (call, result)) in calls.iter().zip(&results).enumerate() {
+        if !result.is_success() {
+            return Err(format!(
+                "call {} to {} {} failed: {}",
+                index,
+                call.contract,
+                call.selector,
+                analyze_txn_result(result)
+            ));
+        }
+    }
+    // As in generation, the caller of the system calls is not part of the genesis
+    bundle_state.state.remove(&config.system_caller());
+    info!("Applied {} patch calls", calls.len());
+    Ok(bundle_state)
+}
+
+/// The accounts of `db` with a patched bundle applied on top, as they go into
+/// `genesis_accounts.json`
+pub fn patched_accounts(
+    db: &InMemoryDB,
+    bundle_state: &BundleState,
+) -> BTreeMap<Address, PlainAccount> {
+    let mut state: BTreeMap<Address, PlainAccount> = db
+        .accounts
+        .iter()
+        .filter(|(address, _)| **address != SYSTEM_CALLER)
+        .map(|(address, account)| {
+            let account = PlainAccount {
+                info: account.info.clone(),
+                storage: account.storage.clone(),
+            };
+            (*address, account)
+        })
+        .collect();
+    for (address, account) in &bundle_state.state {
+        let Some(info) = &account.info else {
+            state.remove(address);
+            continue;
+        };
+        let entry = state.entry(*address).or_insert_with(|| PlainAccount {
+            info: info.clone(),
+            storage: Default::default(),
+        });
+        entry.info = info.clone();
+        entry.storage.extend(
+            account
+                .storage
+                .iter()
+                .map(|(slot, value)| (*slot, value.present_value())),
+        );
+    }
+    state
+}
+
+/// Write `bundle_state.json`, `genesis_accounts.json` and `genesis_contracts.json`
+/// of a patched genesis into `output_dir`
+pub fn write_patched(
+    output_dir: &str,
+    db: &InMemoryDB,
+    bundle_state: &BundleState,
+) -> Result<(), String> {
+    let state = patched_accounts(db, bundle_state);
+    let contracts: BTreeMap<ChecksumAddress, &Bytes> = state
+        .iter()
+        .filter_map(|(address, account)| {
+            let code = account.info.code.as_ref()?;
+            Some((ChecksumAddress(*address), code.bytecode()))
+        })
+        .collect();
+    // Going through `Value` sorts the keys of the bundle's hash maps
+    let bundle_state = serde_json::to_value(bundle_state).map_err(|e| e.to_string())?;
+    for (file, json) in [
+        (
+            "bundle_state.json",
+            serde_json::to_string_pretty(&bundle_state),
+        ),
+        (
+            "genesis_accounts.json",
+            serde_json::to_string_pretty(&sorted_accounts(&state)),
+        ),
+        (
+            "genesis_contracts.json",
+            serde_json::to_string_pretty(&contracts),
+        ),
+    ] {
+        let json = json.map_err(|e| format!("failed to serialize {}: {}", file, e))?;
+        std::fs::write(format!("{output_dir}/{file}"), json)
+            .map_err(|e| format!("failed to write {}/{}: {}", output_dir, file, e))?;
+    }
+    info!(
+        "Wrote the patched genesis of {} accounts to {}",
+        state.len(),
+        output_dir
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{BLOCK_ADDR, GENESIS_ADDR, SYSTEM_ACCOUNT_INFO};
+    use revm_primitives::{AccountInfo, Bytecode};
+
+    #[test]
+    fn test_apply_patch() {
+        // Stores its first argument in slot 0
+        let code = Bytecode::new_raw(Bytes::from(hex::decode("60043560005500").unwrap()));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        for address in [GENESIS_ADDR, BLOCK_ADDR] {
+            db.insert_account_info(
+                address,
+                AccountInfo {
+                    code_hash: code.hash_slow(),
+                    code: Some(code.clone()),
+                    ..AccountInfo::default()
+                },
+            );
+        }
+
+        let call = PatchCall {
+            contract: "Genesis".to_string(),
+            selector: "set(uint256)".to_string(),
+            args: hex::encode(U256::from(7).to_be_bytes::<32>()),
+        };
+        assert_eq!(
+            &call.call_data().unwrap()[..4],
+            hex::decode("60fe47b1").unwrap()
+        );
+        let config = GenesisConfig::default();
+        let bundle_state = apply_patch(
+            &db,
+            BundleState::default(),
+            &config,
+            std::slice::from_ref(&call),
+        )
+        .unwrap();
+        assert!(!bundle_state.state.contains_key(&SYSTEM_CALLER));
+
+        // A second patch keeps the changes of the first
+        let block_call = PatchCall {
+            contract: format!("{:?}", BLOCK_ADDR),
+            selector: "0x60fe47b1".to_string(),
+            args: hex::encode(U256::from(9).to_be_bytes::<32>()),
+        };
+        let bundle_state = apply_patch(&db, bundle_state, &config, &[block_call]).unwrap();
+        let state = patched_accounts(&db, &bundle_state);
+        assert!(!state.contains_key(&SYSTEM_CALLER));
+        assert_eq!(state[&GENESIS_ADDR].storage[&U256::ZERO], U256::from(7));
+        assert_eq!(state[&BLOCK_ADDR].storage[&U256::ZERO], U256::from(9));
+        assert!(state[&GENESIS_ADDR].info.code.is_some());
+
+        let unknown = PatchCall {
+            contract: "Nonexistent".to_string(),
+            ..call.clone()
+        };
+        assert!(apply_patch(&db, BundleState::default(), &config, &[unknown]).is_err());
+        let bad_selector = PatchCall {
+            selector: "0x1234".to_string(),
+            ..call
+        };
+        assert!(bad_selector.call_data().is_err());
+    }
+
+    #[test]
+    fn test_apply_patch_block_env() {
+        // Stores BASEFEE in slot 0 and COINBASE in slot 1
+        let code = Bytecode::new_raw(Bytes::from(hex::decode("4860005541600155").unwrap()));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        db.insert_account_info(
+            GENESIS_ADDR,
+            AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..AccountInfo::default()
+            },
+        );
+        let config = GenesisConfig {
+            coinbase: Some(format!("{:?}", BLOCK_ADDR)),
+            base_fee: Some(7),
+            ..GenesisConfig::default()
+        };
+        let call = PatchCall {
+            contract: "Genesis".to_string(),
+            selector: "0x00000000".to_string(),
+            args: String::new(),
+        };
+        // The zero gas price of system calls is not rejected under the base fee
+        let bundle_state = apply_patch(&db, BundleState::default(), &config, &[call]).unwrap();
+        let storage = &patched_accounts(&db, &bundle_state)[&GENESIS_ADDR].storage;
+        assert_eq!(storage[&U256::ZERO], U256::from(7));
+        assert_eq!(
+            storage[&U256::from(1)],
+            U256::from_be_slice(BLOCK_ADDR.as_slice())
+        );
+    }
+}
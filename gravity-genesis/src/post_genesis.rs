@@ -1,20 +1,68 @@
-use revm::{DatabaseRef, InMemoryDB, db::BundleState};
-use revm_primitives::{ExecutionResult, SpecId, TxEnv, hex};
+use std::{collections::HashMap, fmt};
+
+use clap::ValueEnum;
+use rayon::prelude::*;
+use revm::{
+    DatabaseRef, InMemoryDB,
+    db::{BundleState, PlainAccount},
+};
+use revm_primitives::{Address, ExecutionResult, TxEnv, U256, hex};
 use tracing::{error, info};
 
 use crate::{
+    error::GenesisError,
     execute::prepare_env,
     genesis::{
-        GenesisConfig, call_get_current_epoch_info, call_get_validator_set,
-        print_current_epoch_info_result, print_validator_set_result,
+        GenesisConfig, active_validator_count, active_validators, call_get_current_epoch_info,
+        call_get_current_validator_count, call_get_validator_set, print_current_epoch_info_result,
+        print_validator_set_result, tracked_validator_count,
     },
     jwks::{
-        call_get_active_providers, call_get_observed_jwks, print_jwks_result,
-        print_oidc_providers_result,
+        COMPRESSED_GENERATOR_PROOF, call_compress_generator_proof, call_get_active_providers,
+        call_get_keyless_verifier, call_get_observed_jwks, decode_compressed_proof,
+        print_jwks_result, print_keyless_verifier_result, print_oidc_providers_result,
+    },
+    node_config::{NODE_VALIDATORS_FILE, node_validators_toml},
+    utils::{
+        ChecksumAddress, SYSTEM_ACCOUNT_INFO, SYSTEM_CALLER, analyze_txn_result,
+        execute_revm_sequential,
     },
-    utils::execute_revm_sequential,
 };
 
+/// A value read back from the generated state that differs from the config or an
+/// input file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// What was compared, such as `validators[1].votingPower`
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Mismatch {
+    pub fn new(
+        field: impl Into<String>,
+        expected: impl fmt::Display,
+        actual: impl fmt::Display,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, got {}",
+            self.field, self.expected, self.actual
+        )
+    }
+}
+
 /// Generic template for handling execution results
 ///
 /// This function provides a common structure for all print_* functions,
@@ -50,95 +98,632 @@ where
 ///
 /// This function provides a common structure for all verify_* functions,
 /// reducing code duplication and making the codebase more maintainable.
+/// A call that fails to execute, reverts or halts counts as a mismatch.
 fn execute_verification<F>(
     db: impl DatabaseRef,
     bundle_state: BundleState,
     transaction: TxEnv,
+    config: &GenesisConfig,
     verification_name: &str,
     result_handler: F,
-) where
-    F: FnOnce(&ExecutionResult),
+) -> Result<(), Vec<Mismatch>>
+where
+    F: FnOnce(&ExecutionResult) -> Vec<Mismatch>,
 {
-    let env = prepare_env();
-    let r = execute_revm_sequential(db, SpecId::LATEST, env, &[transaction], Some(bundle_state));
-    
-    match r {
-        Ok((result, _)) => {
-            if let Some(execution_result) = result.get(0) {
+    let env = prepare_env(config.chain_id());
+    let transaction = config.retarget_system_call(transaction);
+    let r = execute_revm_sequential(
+        db,
+        config.spec_id(),
+        env,
+        &[transaction],
+        Some(bundle_state),
+    );
+    let call_failed = |reason: String| {
+        vec![Mismatch::new(
+            verification_name,
+            "a successful call",
+            reason,
+        )]
+    };
+
+    let mismatches = match r {
+        Ok((result, _)) => match result.first() {
+            Some(execution_result) if execution_result.is_success() => {
+                result_handler(execution_result)
+            }
+            Some(execution_result) => {
                 result_handler(execution_result);
+                call_failed(analyze_txn_result(execution_result))
             }
-        }
+            None => call_failed("no result".to_string()),
+        },
         Err(e) => {
-            error!(
-                "verify {} error: {:?}",
-                verification_name,
-                e.map_db_err(|_| "Database error".to_string())
-            );
+            let e = format!("{:?}", e.map_db_err(|_| "Database error".to_string()));
+            error!("verify {} error: {}", verification_name, e);
+            call_failed(e)
         }
+    };
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
     }
 }
 
-fn verify_validator_set(db: impl DatabaseRef, bundle_state: BundleState, config: &GenesisConfig) {
+/// Compare the on-chain active validator set (addresses, keys, voting powers,
+/// network addresses and commission rates) with the configured validators
+pub fn verify_validator_set(
+    db: impl DatabaseRef,
+    bundle_state: BundleState,
+    config: &GenesisConfig,
+) -> Result<(), Vec<Mismatch>> {
     let get_validator_set_txn = call_get_validator_set();
     execute_verification(
         db,
         bundle_state,
         get_validator_set_txn,
+        config,
         "validator set",
         |result| print_validator_set_result(result, config),
-    );
+    )
 }
 
-fn verify_epoch_info(db: impl DatabaseRef, bundle_state: BundleState) {
+fn verify_epoch_info(
+    db: impl DatabaseRef,
+    bundle_state: BundleState,
+    config: &GenesisConfig,
+) -> Result<(), Vec<Mismatch>> {
     let get_epoch_info_txn = call_get_current_epoch_info();
     execute_verification(
         db,
         bundle_state,
         get_epoch_info_txn,
+        config,
         "epoch info",
-        |result| print_current_epoch_info_result(result),
-    );
+        print_current_epoch_info_result,
+    )
 }
 
-pub fn verify_jwks(db: impl DatabaseRef, bundle_state: BundleState, jwks_file: &str) {
-    let get_jwks_txn = call_get_observed_jwks();
-    execute_verification(
+/// Ensure the initialize call registered every configured validator, in both
+/// contracts that keep their own validator list
+///
+/// An init that succeeds without reverting but leaves the validator set empty or
+/// short still produces a valid-looking genesis, so this is treated as a hard error.
+/// `ValidatorManager` and `ValidatorPerformanceTracker` are initialized separately by
+/// `Genesis`, so a disagreement between them points at an init ordering or wiring bug.
+pub fn verify_validator_count(
+    db: impl DatabaseRef,
+    bundle_state: BundleState,
+    config: &GenesisConfig,
+) -> Result<(), GenesisError> {
+    let env = prepare_env(config.chain_id());
+    let (results, _) = execute_revm_sequential(
         db,
-        bundle_state,
-        get_jwks_txn,
-        "jwks",
-        |result| print_jwks_result(result, jwks_file),
+        config.spec_id(),
+        env,
+        &[
+            config.retarget_system_call(call_get_validator_set()),
+            config.retarget_system_call(call_get_current_validator_count()),
+        ],
+        Some(bundle_state),
+    )
+    .map_err(GenesisError::revm)?;
+    let [validator_set_result, tracker_result] = results.as_slice() else {
+        return Err(GenesisError::Revm(format!(
+            "expected 2 validator count results, got {}",
+            results.len()
+        )));
+    };
+
+    let expected_count = config.validators.len();
+    let manager_count =
+        active_validator_count(validator_set_result).map_err(GenesisError::Verify)?;
+    let tracker_count = tracked_validator_count(tracker_result).map_err(GenesisError::Verify)?;
+    if manager_count != expected_count || tracker_count != expected_count {
+        return Err(GenesisError::Verify(format!(
+            "Genesis initialize succeeded but validator counts disagree: \
+             config {}, ValidatorManager {} active, ValidatorPerformanceTracker {}",
+            expected_count, manager_count, tracker_count
+        )));
+    }
+    info!(
+        "✅ Active validator count matches config in ValidatorManager and ValidatorPerformanceTracker: {}",
+        manager_count
     );
+    Ok(())
+}
+
+/// Write `node_validators.toml` from the validator set as stored on chain, rather
+/// than from the config, so it reflects what the nodes will actually see
+pub fn export_node_validators(
+    db: impl DatabaseRef,
+    bundle_state: BundleState,
+    config: &GenesisConfig,
+    output_dir: &str,
+) -> Result<(), GenesisError> {
+    let env = prepare_env(config.chain_id());
+    let (results, _) = execute_revm_sequential(
+        db,
+        config.spec_id(),
+        env,
+        &[config.retarget_system_call(call_get_validator_set())],
+        Some(bundle_state),
+    )
+    .map_err(GenesisError::revm)?;
+    let result = results
+        .first()
+        .ok_or_else(|| GenesisError::Revm("getValidatorSet returned no result".to_string()))?;
+    let validators = active_validators(result).map_err(GenesisError::Verify)?;
+    let path = format!("{output_dir}/{NODE_VALIDATORS_FILE}");
+    let toml = node_validators_toml(&validators).map_err(GenesisError::Verify)?;
+    std::fs::write(&path, toml).map_err(|source| GenesisError::FileWrite {
+        path: path.clone(),
+        source,
+    })?;
+    info!("Wrote {} validators to {}", validators.len(), path);
+    Ok(())
+}
+
+/// Read back every configured `expectedStakeConfig` value and compare it with what
+/// initialization stored
+///
+/// A value that silently stayed at the contract default is reported per field and
+/// treated as a hard error, like a short validator set.
+pub fn verify_stake_config(
+    db: impl DatabaseRef,
+    bundle_state: BundleState,
+    config: &GenesisConfig,
+) -> Result<(), GenesisError> {
+    let expected = config
+        .parsed_expected_stake_config()
+        .map_err(|e| GenesisError::Verify(e.to_string()))?;
+    if expected.is_empty() {
+        return Ok(());
+    }
+    let txs: Vec<TxEnv> = expected
+        .iter()
+        .map(|(field, _)| config.retarget_system_call(field.call()))
+        .collect();
+    let env = prepare_env(config.chain_id());
+    let (results, _) = execute_revm_sequential(db, config.spec_id(), env, &txs, Some(bundle_state))
+        .map_err(GenesisError::revm)?;
+
+    let mut mismatches = Vec::new();
+    for ((field, expected), result) in expected.iter().zip(&results) {
+        match field.decode(result) {
+            Ok(actual) if actual == *expected => {
+                info!("✅ {} matches config: {}", field.name(), actual);
+            }
+            Ok(actual) => {
+                error!(
+                    "❌ {} mismatch! Expected: {}, Actual: {}",
+                    field.name(),
+                    expected,
+                    actual
+                );
+                mismatches.push(format!(
+                    "{} expected {}, stored {}",
+                    field.name(),
+                    expected,
+                    actual
+                ));
+            }
+            Err(e) => {
+                error!("❌ {}", e);
+                mismatches.push(e);
+            }
+        }
+    }
+    if !mismatches.is_empty() {
+        return Err(GenesisError::Verify(format!(
+            "Stake config differs from the genesis config: {}",
+            mismatches.join("; ")
+        )));
+    }
+    Ok(())
+}
+
+pub fn verify_jwks(
+    db: impl DatabaseRef,
+    bundle_state: BundleState,
+    config: &GenesisConfig,
+    jwks_file: &str,
+) -> Result<(), Vec<Mismatch>> {
+    let get_jwks_txn = call_get_observed_jwks();
+    execute_verification(db, bundle_state, get_jwks_txn, config, "jwks", |result| {
+        print_jwks_result(result, jwks_file)
+    })
 }
 
 pub fn verify_oidc_providers(
     db: impl DatabaseRef,
     bundle_state: BundleState,
+    config: &GenesisConfig,
     oidc_providers_file: &str,
-) {
+) -> Result<(), Vec<Mismatch>> {
     let get_oidc_providers_txn = call_get_active_providers();
     execute_verification(
         db,
         bundle_state,
         get_oidc_providers_txn,
+        config,
         "oidc providers",
         |result| print_oidc_providers_result(result, oidc_providers_file),
+    )
+}
+
+/// Check that KeylessAccount points at the Groth16 verifier address and that code is
+/// deployed there, otherwise keyless proofs cannot be verified from the first block.
+/// The verifier's `compressProof` view is then called on a fixed proof, as a smoke
+/// test that the deployed code runs
+pub fn verify_keyless_verifier(
+    db: impl DatabaseRef,
+    bundle_state: BundleState,
+    config: &GenesisConfig,
+) -> Result<(), Vec<Mismatch>> {
+    let verifier = config.contract_address("Groth16Verifier");
+    let verifier_deployed = bundle_state
+        .account(&verifier)
+        .and_then(|account| account.info.clone())
+        .or_else(|| db.basic_ref(verifier).ok().flatten())
+        .is_some_and(|info| !info.is_empty_code_hash());
+    let mut mismatches = Vec::new();
+    if verifier_deployed {
+        info!(
+            "✅ Groth16 verifier code found at {}",
+            ChecksumAddress(verifier)
+        );
+    } else {
+        error!(
+            "No Groth16 verifier code at {}; keyless proofs will fail",
+            ChecksumAddress(verifier)
+        );
+        mismatches.push(Mismatch::new(
+            format!("code at {}", ChecksumAddress(verifier)),
+            "the Groth16 verifier",
+            "no code",
+        ));
+    }
+
+    let result = execute_verification(
+        &db,
+        bundle_state.clone(),
+        call_get_keyless_verifier(),
+        config,
+        "keyless verifier",
+        |result| print_keyless_verifier_result(result, verifier),
     );
+    mismatches.extend(result.err().into_iter().flatten());
+    if verifier_deployed {
+        let expected = COMPRESSED_GENERATOR_PROOF.map(U256::from);
+        let result = execute_verification(
+            db,
+            bundle_state,
+            call_compress_generator_proof(),
+            config,
+            "Groth16 verifier compressProof",
+            |result| match decode_compressed_proof(result) {
+                Some(compressed) if compressed == expected => Vec::new(),
+                // A failed call is already reported as such
+                None if !result.is_success() => Vec::new(),
+                compressed => vec![Mismatch::new(
+                    "Groth16Verifier.compressProof",
+                    format!("{:?}", expected),
+                    format!("{:?}", compressed),
+                )],
+            },
+        );
+        mismatches.extend(result.err().into_iter().flatten());
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
 }
 
+/// A post-generation check selectable with `--checks`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VerifyCheck {
+    /// Every check whose inputs are available
+    All,
+    /// `verify_validator_set`
+    Validators,
+    /// `verify_epoch_info`
+    Epoch,
+    /// `verify_jwks`, needs a JWKs file
+    Jwks,
+    /// `verify_oidc_providers`, needs an OIDC providers file
+    Oidc,
+    /// `verify_keyless_verifier`
+    Keyless,
+    /// `verify_stake_config`, a no-op unless the config sets `expectedStakeConfig`
+    StakeConfig,
+}
+
+pub fn check_enabled(checks: &[VerifyCheck], check: VerifyCheck) -> bool {
+    checks.contains(&VerifyCheck::All) || checks.contains(&check)
+}
+
+/// Reject explicitly requested checks whose input file was not given, rather than
+/// silently skipping them
+pub fn validate_checks(
+    checks: &[VerifyCheck],
+    jwks_file: Option<&str>,
+    oidc_providers_file: Option<&str>,
+) -> Result<(), String> {
+    if checks.contains(&VerifyCheck::Jwks) && jwks_file.is_none() {
+        return Err("the jwks check requires --jwks-file".to_string());
+    }
+    if checks.contains(&VerifyCheck::Oidc) && oidc_providers_file.is_none() {
+        return Err("the oidc check requires --oidc-providers-file".to_string());
+    }
+    Ok(())
+}
+
+/// One post-generation check, ready to run on any thread
+type CheckRun<'a> = Box<dyn FnOnce() -> Result<(), Vec<Mismatch>> + Send + 'a>;
+
+/// Run the selected read-back checks, logging each one and returning every
+/// mismatch found across all of them
 pub fn verify_result(
     db: InMemoryDB,
     bundle_state: BundleState,
     config: &GenesisConfig,
     jwks_file: Option<String>,
     oidc_providers_file: Option<String>,
-) {
-    verify_validator_set(db.clone(), bundle_state.clone(), config);
-    verify_epoch_info(db.clone(), bundle_state.clone());
-    if let Some(jwks_file) = jwks_file {
-        verify_jwks(db.clone(), bundle_state.clone(), &jwks_file);
-    }
-    if let Some(oidc_providers_file) = oidc_providers_file {
-        verify_oidc_providers(db.clone(), bundle_state.clone(), &oidc_providers_file);
+    checks: &[VerifyCheck],
+) -> Result<(), Vec<Mismatch>> {
+    let (db, bundle_state) = (&db, &bundle_state);
+    let mut runs: Vec<CheckRun> = Vec::new();
+    if check_enabled(checks, VerifyCheck::Validators) {
+        runs.push(Box::new(move || {
+            verify_validator_set(db.clone(), bundle_state.clone(), config)
+        }));
+    }
+    if check_enabled(checks, VerifyCheck::Epoch) {
+        runs.push(Box::new(move || {
+            verify_epoch_info(db.clone(), bundle_state.clone(), config)
+        }));
+    }
+    if check_enabled(checks, VerifyCheck::Keyless) {
+        runs.push(Box::new(move || {
+            verify_keyless_verifier(db.clone(), bundle_state.clone(), config)
+        }));
+    }
+    if let Some(jwks_file) = jwks_file.filter(|_| check_enabled(checks, VerifyCheck::Jwks)) {
+        runs.push(Box::new(move || {
+            verify_jwks(db.clone(), bundle_state.clone(), config, &jwks_file)
+        }));
+    }
+    if let Some(oidc_providers_file) =
+        oidc_providers_file.filter(|_| check_enabled(checks, VerifyCheck::Oidc))
+    {
+        runs.push(Box::new(move || {
+            verify_oidc_providers(
+                db.clone(),
+                bundle_state.clone(),
+                config,
+                &oidc_providers_file,
+            )
+        }));
+    }
+
+    // Each check executes on its own copy of the state, so they run in parallel.
+    // Their log lines may interleave, but `collect` keeps the mismatches in the order
+    // the checks are listed above
+    let mismatches: Vec<Mismatch> = runs
+        .into_par_iter()
+        .map(|run| run())
+        .filter_map(Result::err)
+        .flatten()
+        .collect();
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
+
+fn read_json_file<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, GenesisError> {
+    let content = std::fs::read_to_string(path).map_err(|source| GenesisError::FileRead {
+        path: path.to_string(),
+        source,
+    })?;
+    serde_json::from_str(&content).map_err(|source| GenesisError::JsonParse {
+        path: path.to_string(),
+        source,
+    })
+}
+
+/// Deserialize the `bundle_state.json` of an earlier generation, to run the checks
+/// above on its result without generating again
+pub fn load_bundle_state(path: &str) -> Result<BundleState, GenesisError> {
+    let bundle_state: BundleState = read_json_file(path)?;
+    info!(
+        "Loaded {} bundle accounts from {}",
+        bundle_state.state.len(),
+        path
+    );
+    Ok(bundle_state)
+}
+
+/// Rebuild the database the checks run against from a `genesis_accounts.json`,
+/// the state `load_bundle_state` is then applied on top of
+///
+/// `SYSTEM_CALLER`, which every check calls from, is added back since generation
+/// leaves it out of the output.
+pub fn load_accounts_db(path: &str) -> Result<InMemoryDB, GenesisError> {
+    let accounts: HashMap<Address, PlainAccount> = read_json_file(path)?;
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+    for (address, account) in accounts {
+        db.insert_account_info(address, account.info);
+        for (slot, value) in account.storage {
+            db.insert_account_storage(address, slot, value)
+                .expect("InMemoryDB storage insert is infallible");
+        }
+    }
+    info!("Loaded {} accounts from {}", db.accounts.len() - 1, path);
+    Ok(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        jwks::{OIDCProvider, getActiveProvidersCall},
+        utils::{GROTH16_VERIFIER_ADDR, JWK_MANAGER_ADDR, KEYLESS_ACCOUNT_ADDR},
+    };
+    use alloy_sol_types::{SolCall, SolValue};
+    use revm::db::InMemoryDB;
+    use revm_primitives::{AccountInfo, Bytecode, Bytes, U256};
+
+    /// Runtime code that returns `data` to every call
+    fn returning(data: &[u8]) -> Bytecode {
+        let [high, low] = u16::try_from(data.len()).unwrap().to_be_bytes();
+        // CODECOPY the data behind these 14 bytes to memory 0, then RETURN it
+        let mut code = vec![
+            0x61, high, low, 0x60, 0x0e, 0x60, 0x00, 0x39, 0x61, high, low, 0x60, 0x00, 0xf3,
+        ];
+        code.extend_from_slice(data);
+        Bytecode::new_raw(Bytes::from(code))
+    }
+
+    /// A DB with `code` at each address
+    fn db_with_code(contracts: &[(Address, Bytecode)]) -> InMemoryDB {
+        let mut db = InMemoryDB::default();
+        for (address, code) in contracts {
+            db.insert_account_info(
+                *address,
+                AccountInfo {
+                    code: Some(code.clone()),
+                    ..AccountInfo::default()
+                },
+            );
+        }
+        db
+    }
+
+    #[test]
+    fn test_verify_keyless_verifier_mismatch() {
+        let config = GenesisConfig::default();
+        let wrong_verifier = Address::repeat_byte(0x11);
+        let db = db_with_code(&[
+            (
+                KEYLESS_ACCOUNT_ADDR,
+                returning(&wrong_verifier.abi_encode()),
+            ),
+            (GROTH16_VERIFIER_ADDR, returning(&[])),
+        ]);
+
+        let Err(mismatches) = verify_keyless_verifier(&db, BundleState::default(), &config) else {
+            panic!("expected the wrong verifier to be reported");
+        };
+        assert!(
+            mismatches.contains(&Mismatch::new(
+                "KeylessAccount.verifier",
+                GROTH16_VERIFIER_ADDR,
+                wrong_verifier
+            )),
+            "{:?}",
+            mismatches
+        );
+    }
+
+    #[test]
+    fn test_verify_oidc_providers_mismatch() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path();
+        std::fs::write(
+            &path,
+            r#"{"providers": [
+                {"name": "https://accounts.google.com", "configUrl": "https://accounts.google.com/.well-known/openid-configuration"},
+                {"name": "https://appleid.apple.com", "configUrl": "https://appleid.apple.com/.well-known/openid-configuration"}
+            ]}"#,
+        )
+        .unwrap();
+        // Google with the wrong config URL, Apple missing
+        let active = vec![OIDCProvider {
+            name: "https://accounts.google.com".to_string(),
+            configUrl: "https://evil.example.com".to_string(),
+            active: true,
+            onchain_block_number: 0,
+        }];
+        let db = db_with_code(&[(
+            JWK_MANAGER_ADDR,
+            returning(&getActiveProvidersCall::abi_encode_returns(&(active,))),
+        )]);
+
+        let result = verify_oidc_providers(
+            &db,
+            BundleState::default(),
+            &GenesisConfig::default(),
+            path.to_str().unwrap(),
+        );
+        assert_eq!(
+            result,
+            Err(vec![
+                Mismatch::new(
+                    "OIDC provider https://accounts.google.com.configUrl",
+                    "https://accounts.google.com/.well-known/openid-configuration",
+                    "https://evil.example.com"
+                ),
+                Mismatch::new(
+                    "OIDC provider https://appleid.apple.com",
+                    "an active provider",
+                    "no such active provider"
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_load_generated_state() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let accounts_file = dir.join("genesis_accounts.json");
+        let bundle_state_file = dir.join("bundle_state.json");
+        let accounts_file = accounts_file.to_str().unwrap();
+        let bundle_state_file = bundle_state_file.to_str().unwrap();
+        assert!(matches!(
+            load_accounts_db(accounts_file),
+            Err(GenesisError::FileRead { .. })
+        ));
+
+        let address = GROTH16_VERIFIER_ADDR;
+        let account = PlainAccount {
+            info: AccountInfo {
+                balance: U256::from(7),
+                ..AccountInfo::default()
+            },
+            storage: [(U256::from(1), U256::from(2))].into_iter().collect(),
+        };
+        std::fs::write(
+            accounts_file,
+            serde_json::to_string(&HashMap::from([(address, account)])).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(bundle_state_file, "[]").unwrap();
+        assert!(matches!(
+            load_bundle_state(bundle_state_file),
+            Err(GenesisError::JsonParse { .. })
+        ));
+        std::fs::write(
+            bundle_state_file,
+            serde_json::to_string(&BundleState::default()).unwrap(),
+        )
+        .unwrap();
+
+        let db = load_accounts_db(accounts_file).unwrap();
+        let bundle_state = load_bundle_state(bundle_state_file).unwrap();
+        assert!(db.accounts.contains_key(&SYSTEM_CALLER));
+        assert_eq!(db.accounts[&address].info.balance, U256::from(7));
+        assert_eq!(
+            db.storage_ref(address, U256::from(1)).unwrap(),
+            U256::from(2)
+        );
+        assert!(bundle_state.state.is_empty());
     }
 }
@@ -0,0 +1,225 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use alloy_trie::{HashBuilder, Nibbles, TrieAccount};
+use revm::{DatabaseRef, InMemoryDB, db::PlainAccount};
+use revm_primitives::{AccountInfo, Address, B256, Bytecode, U256, keccak256};
+use tracing::info;
+
+/// Timings of loading a generated genesis the way a client would
+#[derive(Debug, Clone)]
+pub struct LoadProfile {
+    pub accounts: usize,
+    pub storage_slots: usize,
+    /// Parsing `genesis_accounts.json` and inserting it into an `InMemoryDB`
+    pub load_time: Duration,
+    /// Hashing every account and slot into the state trie, including simulated latency
+    pub state_root_time: Duration,
+    pub state_root: B256,
+}
+
+impl LoadProfile {
+    pub fn total_time(&self) -> Duration {
+        self.load_time + self.state_root_time
+    }
+
+    pub fn print(&self, latency: Duration) {
+        info!("=== Genesis load profile ===");
+        info!("Accounts: {}", self.accounts);
+        info!("Storage slots: {}", self.storage_slots);
+        info!("Simulated read latency: {:?}", latency);
+        info!("Load time: {:?}", self.load_time);
+        info!("State root time: {:?}", self.state_root_time);
+        info!("State root: {:?}", self.state_root);
+        info!("Estimated client load cost: {:?}", self.total_time());
+    }
+}
+
+/// Load `genesis_accounts.json` into an `InMemoryDB` and compute its state root,
+/// sleeping `latency` on every account and slot read to stand in for disk access
+pub fn profile_load(accounts_file: &str, latency: Duration) -> Result<LoadProfile, String> {
+    let start = Instant::now();
+    let db = load_accounts(accounts_file)?;
+    let load_time = start.elapsed();
+
+    let start = Instant::now();
+    let state_root = state_root(&db, latency);
+    let state_root_time = start.elapsed();
+
+    Ok(LoadProfile {
+        accounts: db.accounts.len(),
+        storage_slots: db
+            .accounts
+            .values()
+            .map(|account| account.storage.len())
+            .sum(),
+        load_time,
+        state_root_time,
+        state_root,
+    })
+}
+
+/// Parse a `genesis_accounts.json` into an `InMemoryDB`
+pub fn load_accounts(accounts_file: &str) -> Result<InMemoryDB, String> {
+    let content = std::fs::read_to_string(accounts_file)
+        .map_err(|e| format!("failed to read {}: {}", accounts_file, e))?;
+    let accounts: HashMap<Address, PlainAccount> = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to parse {}: {}", accounts_file, e))?;
+    let mut db = InMemoryDB::default();
+    for (address, account) in accounts {
+        db.insert_account_info(address, account.info);
+        for (slot, value) in account.storage {
+            db.insert_account_storage(address, slot, value)
+                .map_err(|e| format!("failed to insert storage of {:?}: {:?}", address, e))?;
+        }
+    }
+    Ok(db)
+}
+
+/// Ethereum state root of every account in `db`
+pub fn state_root(db: &InMemoryDB, latency: Duration) -> B256 {
+    let accounts = db
+        .accounts
+        .iter()
+        .map(|(address, account)| {
+            simulate_read(latency);
+            let storage_root = trie_root(
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(slot, value)| {
+                        simulate_read(latency);
+                        (keccak256(B256::from(*slot)), alloy_rlp::encode(value))
+                    })
+                    .collect(),
+            );
+            let account = TrieAccount {
+                nonce: account.info.nonce,
+                balance: account.info.balance,
+                storage_root,
+                code_hash: account.info.code_hash,
+            };
+            (keccak256(address), alloy_rlp::encode(account))
+        })
+        .collect();
+    trie_root(accounts)
+}
+
+fn simulate_read(latency: Duration) {
+    if !latency.is_zero() {
+        std::thread::sleep(latency);
+    }
+}
+
+/// A `DatabaseRef` sleeping `latency` on every account, storage and code read of
+/// `db`, for benchmarking genesis execution against slow storage
+pub struct LatencyDB<DB> {
+    db: DB,
+    latency: Duration,
+}
+
+impl<DB> LatencyDB<DB> {
+    pub fn new(db: DB, latency: Duration) -> Self {
+        Self { db, latency }
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for LatencyDB<DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        simulate_read(self.latency);
+        self.db.basic_ref(address)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        simulate_read(self.latency);
+        self.db.code_by_hash_ref(code_hash)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        simulate_read(self.latency);
+        self.db.storage_ref(address, index)
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        self.db.block_hash_ref(number)
+    }
+}
+
+/// Root hash of a Merkle Patricia trie holding `entries`, keyed by hash
+fn trie_root(mut entries: Vec<(B256, Vec<u8>)>) -> B256 {
+    entries.sort_unstable_by_key(|(key, _)| *key);
+    let mut builder = HashBuilder::default();
+    for (key, value) in entries {
+        builder.add_leaf(Nibbles::unpack(key), &value);
+    }
+    builder.root()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm_primitives::b256;
+
+    #[test]
+    fn test_state_root() {
+        let mut db = InMemoryDB::default();
+        assert_eq!(
+            state_root(&db, Duration::ZERO),
+            b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+        );
+
+        db.insert_account_info(
+            Address::repeat_byte(0x11),
+            AccountInfo {
+                balance: U256::from(10).pow(U256::from(18)),
+                nonce: 1,
+                ..AccountInfo::default()
+            },
+        );
+        let code = Bytecode::new_raw(vec![0x60, 0x00].into());
+        let contract = Address::repeat_byte(0x22);
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..AccountInfo::default()
+            },
+        );
+        for slot in [0u64, 1, 7, 1000] {
+            db.insert_account_storage(contract, U256::from(slot), U256::from(slot * 3 + 1))
+                .unwrap();
+        }
+        // Zero slots are not part of the storage trie
+        db.insert_account_storage(contract, U256::from(5), U256::ZERO)
+            .unwrap();
+        assert_eq!(
+            state_root(&db, Duration::ZERO),
+            b256!("7d9f0b387a11f66a94b9cb05b8fa3508bfbc64892076af89532b3d41ea9fb712")
+        );
+    }
+
+    #[test]
+    fn test_latency_db_sleeps_on_reads() {
+        let db = InMemoryDB::default();
+        let latency = Duration::from_millis(5);
+        let latency_db = LatencyDB::new(&db, latency);
+
+        let start = Instant::now();
+        latency_db.basic_ref(Address::ZERO).unwrap();
+        latency_db.storage_ref(Address::ZERO, U256::ZERO).unwrap();
+        latency_db.code_by_hash_ref(B256::ZERO).unwrap();
+        assert!(start.elapsed() >= latency * 3);
+
+        let start = Instant::now();
+        LatencyDB::new(&db, Duration::ZERO)
+            .basic_ref(Address::ZERO)
+            .unwrap();
+        assert!(start.elapsed() < latency);
+    }
+}
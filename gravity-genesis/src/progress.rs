@@ -0,0 +1,71 @@
+use std::{fmt, sync::Arc};
+
+/// How the deployment of one contract ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeployStatus {
+    /// Runtime code placed at its address without running a constructor
+    Placed,
+    /// Constructor executed and the runtime code it returned placed at the address
+    Constructed,
+    /// Constructor reverted or halted, with the decoded reason
+    Failed(String),
+}
+
+impl fmt::Display for DeployStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeployStatus::Placed => write!(f, "placed"),
+            DeployStatus::Constructed => write!(f, "constructed"),
+            DeployStatus::Failed(reason) => write!(f, "failed: {}", reason),
+        }
+    }
+}
+
+/// One contract of the deployment done
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployProgress {
+    /// Position of the contract counting from 1, system contracts before extra contracts
+    pub step: usize,
+    /// Number of system contracts plus configured extra contracts
+    pub total: usize,
+    pub contract_name: String,
+    pub status: DeployStatus,
+}
+
+/// Receives a `DeployProgress` as each contract is deployed, so a caller can render
+/// progress without parsing log lines. Events arrive in deployment order, on the
+/// thread running the generation
+#[derive(Clone)]
+pub struct ProgressCallback(pub Arc<dyn Fn(DeployProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn new(callback: impl Fn(DeployProgress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback(..)")
+    }
+}
+
+/// The optional callback of a deployment together with its contract count
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DeployReporter<'a> {
+    pub callback: Option<&'a ProgressCallback>,
+    pub total: usize,
+}
+
+impl DeployReporter<'_> {
+    pub fn report(&self, step: usize, contract_name: &str, status: DeployStatus) {
+        if let Some(callback) = self.callback {
+            (callback.0)(DeployProgress {
+                step,
+                total: self.total,
+                contract_name: contract_name.to_string(),
+                status,
+            });
+        }
+    }
+}
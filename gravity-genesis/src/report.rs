@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use revm::InMemoryDB;
+use revm_primitives::{Address, B256, Bytes, U256};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{profile, utils::CONTRACTS};
+
+pub const DEPLOYMENT_FILE: &str = "deployment.json";
+pub const SUMMARY_FILE: &str = "summary.json";
+
+/// One contract of the generated genesis, an entry of `deployment.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployedContract {
+    /// Name of the system contract at `address`, unset for user contracts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub address: Address,
+    pub code_hash: B256,
+    pub code_size: usize,
+    pub storage_slots: usize,
+    pub balance: U256,
+}
+
+/// Totals over the generated genesis, written to `summary.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisSummary {
+    pub accounts: usize,
+    pub contracts: usize,
+    pub storage_slots: usize,
+    /// Sum of every genesis balance in wei, including any burned amount
+    pub total_balance: U256,
+    pub state_root: B256,
+}
+
+/// Build the manifest and summary of a genesis state, listing the contracts of
+/// `genesis_contracts.json` in address order
+pub fn build_report(
+    db: &InMemoryDB,
+    contracts: &HashMap<Address, Bytes>,
+) -> (Vec<DeployedContract>, GenesisSummary) {
+    let mut addresses: Vec<Address> = contracts.keys().copied().collect();
+    addresses.sort();
+    let deployment = addresses
+        .into_iter()
+        .filter_map(|address| {
+            let account = db.accounts.get(&address)?;
+            Some(DeployedContract {
+                name: CONTRACTS
+                    .iter()
+                    .find(|(_, contract)| *contract == address)
+                    .map(|(name, _)| name.to_string()),
+                address,
+                code_hash: account.info.code_hash,
+                code_size: account
+                    .info
+                    .code
+                    .as_ref()
+                    .map_or(0, |code| code.original_bytes().len()),
+                storage_slots: account.storage.len(),
+                balance: account.info.balance,
+            })
+        })
+        .collect();
+
+    let summary = GenesisSummary {
+        accounts: db.accounts.len(),
+        contracts: contracts.len(),
+        storage_slots: db
+            .accounts
+            .values()
+            .map(|account| account.storage.len())
+            .sum(),
+        total_balance: db.accounts.values().fold(U256::ZERO, |total, account| {
+            total.saturating_add(account.info.balance)
+        }),
+        state_root: profile::state_root(db, std::time::Duration::ZERO),
+    };
+    (deployment, summary)
+}
+
+/// Manifest and summary of a `genesis_accounts.json` on its own, counting every
+/// account with code as a contract. The loaded state is returned for callers that
+/// also want to look at the storage
+pub fn inspect_accounts(
+    accounts_file: &str,
+) -> Result<(InMemoryDB, Vec<DeployedContract>, GenesisSummary), String> {
+    let db = profile::load_accounts(accounts_file)?;
+    let contracts: HashMap<Address, Bytes> = db
+        .accounts
+        .iter()
+        .filter_map(|(address, account)| {
+            let code = account.info.code.as_ref().filter(|code| !code.is_empty())?;
+            Some((*address, code.original_bytes()))
+        })
+        .collect();
+    let (deployment, summary) = build_report(&db, &contracts);
+    Ok((db, deployment, summary))
+}
+
+/// Recompute `deployment.json` and `summary.json` in `output_dir` from its
+/// `genesis_accounts.json` and `genesis_contracts.json`, without running the EVM
+pub fn write_reports(output_dir: &str) -> Result<GenesisSummary, String> {
+    let db = profile::load_accounts(&format!("{output_dir}/genesis_accounts.json"))?;
+    let contracts_file = format!("{output_dir}/genesis_contracts.json");
+    let content = std::fs::read_to_string(&contracts_file)
+        .map_err(|e| format!("failed to read {}: {}", contracts_file, e))?;
+    let contracts: HashMap<Address, Bytes> = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to parse {}: {}", contracts_file, e))?;
+
+    let (deployment, summary) = build_report(&db, &contracts);
+    for (file, json) in [
+        (DEPLOYMENT_FILE, serde_json::to_string_pretty(&deployment)),
+        (SUMMARY_FILE, serde_json::to_string_pretty(&summary)),
+    ] {
+        let json = json.map_err(|e| format!("failed to serialize {}: {}", file, e))?;
+        std::fs::write(format!("{output_dir}/{file}"), json)
+            .map_err(|e| format!("failed to write {}/{}: {}", output_dir, file, e))?;
+    }
+    info!(
+        "Wrote {} and {} for {} contracts to {}",
+        DEPLOYMENT_FILE,
+        SUMMARY_FILE,
+        deployment.len(),
+        output_dir
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::GENESIS_ADDR;
+    use revm_primitives::{AccountInfo, Bytecode, address};
+
+    #[test]
+    fn test_build_report() {
+        let user = address!("000000000000000000000000000000000000fa00");
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            GENESIS_ADDR,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from(vec![0x60, 0x00]))),
+                ..AccountInfo::default()
+            },
+        );
+        db.insert_account_storage(GENESIS_ADDR, U256::from(1), U256::from(2))
+            .unwrap();
+        db.insert_account_info(
+            user,
+            AccountInfo {
+                balance: U256::from(5),
+                ..AccountInfo::default()
+            },
+        );
+        let contracts = HashMap::from([(GENESIS_ADDR, Bytes::from(vec![0x60, 0x00]))]);
+
+        let (deployment, summary) = build_report(&db, &contracts);
+        assert_eq!(deployment.len(), 1);
+        assert_eq!(deployment[0].name.as_deref(), Some("Genesis"));
+        assert_eq!(deployment[0].code_size, 2);
+        assert_eq!(deployment[0].storage_slots, 1);
+        assert_eq!(summary.accounts, 2);
+        assert_eq!(summary.contracts, 1);
+        assert_eq!(summary.storage_slots, 1);
+        assert_eq!(summary.total_balance, U256::from(5));
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+use revm::db::{
+    BundleState,
+    states::reverts::{AccountInfoRevert, RevertToSlot},
+};
+use revm_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::CONTRACTS;
+
+pub const REVERTS_FILE: &str = "reverts.json";
+
+/// Account fields a revert restores, leaving out the code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviousAccount {
+    pub balance: U256,
+    pub nonce: u64,
+    pub code_hash: B256,
+}
+
+/// What undoing a transaction does to the account fields
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InfoRevert {
+    /// The transaction left balance, nonce and code alone
+    Unchanged,
+    /// The transaction created the account, which a revert removes
+    Created,
+    /// The account as it was before the transaction
+    Previous(PreviousAccount),
+}
+
+/// How undoing one transaction restores an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountReverts {
+    pub address: Address,
+    /// Name of the system contract at the address, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub info: InfoRevert,
+    /// Value of each slot the transaction wrote before it ran, in slot order
+    pub storage: BTreeMap<U256, U256>,
+    /// The transaction destroyed the account, so a revert restores its storage
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wipe_storage: bool,
+}
+
+/// The reverts of one initialization transaction, an entry of `reverts.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionReverts {
+    /// Index of the transaction, numbered as in `genesis_txns.json`
+    pub transaction: usize,
+    /// Touched accounts in address order
+    pub accounts: Vec<AccountReverts>,
+}
+
+/// One entry per transaction of `bundle_state`, which must hold a revert per
+/// transaction as `execute_revm_sequential` keeps them, numbered from `first_index`.
+/// `skip` is left out, as it is of `bundle_state.json`
+pub fn collect_reverts(
+    first_index: usize,
+    bundle_state: &BundleState,
+    skip: Address,
+) -> Vec<TransactionReverts> {
+    bundle_state
+        .reverts
+        .iter()
+        .enumerate()
+        .map(|(index, reverts)| {
+            let mut accounts: Vec<AccountReverts> = reverts
+                .iter()
+                .filter(|(address, _)| *address != skip)
+                .map(|(address, revert)| AccountReverts {
+                    address: *address,
+                    name: CONTRACTS
+                        .iter()
+                        .find(|(_, contract)| contract == address)
+                        .map(|(name, _)| name.to_string()),
+                    info: match &revert.account {
+                        AccountInfoRevert::DoNothing => InfoRevert::Unchanged,
+                        AccountInfoRevert::DeleteIt => InfoRevert::Created,
+                        AccountInfoRevert::RevertTo(info) => {
+                            InfoRevert::Previous(PreviousAccount {
+                                balance: info.balance,
+                                nonce: info.nonce,
+                                code_hash: info.code_hash,
+                            })
+                        }
+                    },
+                    storage: revert
+                        .storage
+                        .iter()
+                        .map(|(slot, previous)| (*slot, RevertToSlot::to_previous_value(*previous)))
+                        .collect(),
+                    wipe_storage: revert.wipe_storage,
+                })
+                .collect();
+            accounts.sort_by_key(|account| account.address);
+            TransactionReverts {
+                transaction: first_index + index,
+                accounts,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        execute::prepare_env,
+        utils::{
+            GENESIS_ADDR, SYSTEM_ACCOUNT_INFO, SYSTEM_CALLER, execute_revm_sequential,
+            new_system_call_txn,
+        },
+    };
+    use revm::InMemoryDB;
+    use revm_primitives::{AccountInfo, Bytecode, Bytes, SpecId, bytes};
+
+    #[test]
+    fn test_collect_reverts() {
+        // Stores the first word of calldata in slot 0
+        let code = Bytecode::new_raw(bytes!("60003560005500"));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        db.insert_account_info(
+            GENESIS_ADDR,
+            AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..AccountInfo::default()
+            },
+        );
+        let store = |value: u64| {
+            new_system_call_txn(
+                GENESIS_ADDR,
+                Bytes::from(U256::from(value).to_be_bytes::<32>()),
+            )
+        };
+        let (_, bundle_state) = execute_revm_sequential(
+            &db,
+            SpecId::LATEST,
+            prepare_env(1),
+            &[store(1), store(2)],
+            None,
+        )
+        .unwrap();
+
+        let reverts = collect_reverts(5, &bundle_state, SYSTEM_CALLER);
+        assert_eq!(reverts.len(), 2);
+        assert_eq!(reverts[1].transaction, 6);
+        // The second store would restore the value the first one wrote
+        let genesis = &reverts[1].accounts[0];
+        assert_eq!(genesis.address, GENESIS_ADDR);
+        assert_eq!(genesis.name.as_deref(), Some("Genesis"));
+        assert_eq!(genesis.info, InfoRevert::Unchanged);
+        assert_eq!(
+            genesis.storage,
+            BTreeMap::from([(U256::ZERO, U256::from(1))])
+        );
+        assert_eq!(reverts[0].accounts[0].storage[&U256::ZERO], U256::ZERO);
+        assert!(reverts.iter().all(|tx| {
+            tx.accounts
+                .iter()
+                .all(|account| account.address != SYSTEM_CALLER)
+        }));
+    }
+}
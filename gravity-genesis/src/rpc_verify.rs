@@ -0,0 +1,252 @@
+use std::{collections::HashMap, fmt};
+
+use revm::db::PlainAccount;
+use revm_primitives::{Address, B256, Bytes, U256, keccak256};
+use serde_json::{Value, json};
+use tracing::info;
+
+use crate::utils::ChecksumAddress;
+
+/// A difference between the generated genesis and the state a node serves
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcDivergence {
+    /// `genesis_contracts.json` lists a contract `genesis_accounts.json` has no account for
+    MissingAccount { address: Address },
+    /// The code at `address` differs, identified by its keccak hash
+    Code {
+        address: Address,
+        expected: B256,
+        actual: B256,
+    },
+    /// A storage slot of `address` holds a different value
+    Storage {
+        address: Address,
+        slot: U256,
+        expected: U256,
+        actual: U256,
+    },
+}
+
+impl fmt::Display for RpcDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcDivergence::MissingAccount { address } => write!(
+                f,
+                "{} is in genesis_contracts.json but not in genesis_accounts.json",
+                ChecksumAddress(*address)
+            ),
+            RpcDivergence::Code {
+                address,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "code of {} has hash {:?}, expected {:?}",
+                ChecksumAddress(*address),
+                actual,
+                expected
+            ),
+            RpcDivergence::Storage {
+                address,
+                slot,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "slot {:#x} of {} is {:#x}, expected {:#x}",
+                slot,
+                ChecksumAddress(*address),
+                actual,
+                expected
+            ),
+        }
+    }
+}
+
+/// Minimal JSON-RPC client for the `eth_` reads the verification needs
+pub struct JsonRpcClient {
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl JsonRpcClient {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        let response: Value = self
+            .client
+            .post(&self.endpoint)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("{} request to {} failed: {}", method, self.endpoint, e))?
+            .json()
+            .await
+            .map_err(|e| {
+                format!(
+                    "{} response from {} is not JSON: {}",
+                    method, self.endpoint, e
+                )
+            })?;
+        rpc_result(method, response)
+    }
+
+    pub async fn get_code(&self, address: Address, block: &str) -> Result<Bytes, String> {
+        let result = self.request("eth_getCode", json!([address, block])).await?;
+        result
+            .as_str()
+            .and_then(|code| code.parse::<Bytes>().ok())
+            .ok_or_else(|| {
+                format!(
+                    "eth_getCode returned {} for {}",
+                    result,
+                    ChecksumAddress(address)
+                )
+            })
+    }
+
+    pub async fn get_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        block: &str,
+    ) -> Result<U256, String> {
+        let result = self
+            .request("eth_getStorageAt", json!([address, slot, block]))
+            .await?;
+        result
+            .as_str()
+            .and_then(|value| value.parse::<U256>().ok())
+            .ok_or_else(|| {
+                format!(
+                    "eth_getStorageAt returned {} for slot {:#x} of {}",
+                    result,
+                    slot,
+                    ChecksumAddress(address)
+                )
+            })
+    }
+}
+
+/// The `result` of a JSON-RPC response, or its `error` as a message
+fn rpc_result(method: &str, mut response: Value) -> Result<Value, String> {
+    if let Some(error) = response.get("error") {
+        return Err(format!("{} failed: {}", method, error));
+    }
+    match response.get_mut("result") {
+        Some(result) => Ok(result.take()),
+        None => Err(format!("{} response has no result: {}", method, response)),
+    }
+}
+
+/// Compare the code of every contract in `genesis_contracts.json` and up to
+/// `max_slots` of its storage slots from `genesis_accounts.json` against what the
+/// node serves at `block`
+pub async fn verify_rpc(
+    client: &JsonRpcClient,
+    output_dir: &str,
+    block: &str,
+    max_slots: usize,
+) -> Result<Vec<RpcDivergence>, String> {
+    let contracts: HashMap<Address, Bytes> =
+        read_json(&format!("{output_dir}/genesis_contracts.json"))?;
+    let accounts: HashMap<Address, PlainAccount> =
+        read_json(&format!("{output_dir}/genesis_accounts.json"))?;
+
+    let mut addresses: Vec<Address> = contracts.into_keys().collect();
+    addresses.sort();
+
+    let mut divergences = Vec::new();
+    let mut checked_slots = 0;
+    for address in &addresses {
+        let address = *address;
+        let Some(account) = accounts.get(&address) else {
+            divergences.push(RpcDivergence::MissingAccount { address });
+            continue;
+        };
+
+        let expected_code = account
+            .info
+            .code
+            .as_ref()
+            .map(|code| code.original_bytes())
+            .unwrap_or_default();
+        let actual_code = client.get_code(address, block).await?;
+        if actual_code != expected_code {
+            divergences.push(RpcDivergence::Code {
+                address,
+                expected: keccak256(&expected_code),
+                actual: keccak256(&actual_code),
+            });
+        }
+
+        // Lowest slots first, so repeated runs read the same ones
+        let mut slots: Vec<(U256, U256)> = account
+            .storage
+            .iter()
+            .map(|(slot, value)| (*slot, *value))
+            .collect();
+        slots.sort();
+        for (slot, expected) in slots.into_iter().take(max_slots) {
+            let actual = client.get_storage_at(address, slot, block).await?;
+            checked_slots += 1;
+            if actual != expected {
+                divergences.push(RpcDivergence::Storage {
+                    address,
+                    slot,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+
+    info!(
+        "Checked code of {} contracts and {} storage slots at block {}",
+        addresses.len(),
+        checked_slots,
+        block
+    );
+    Ok(divergences)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("failed to parse {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_result() {
+        assert_eq!(
+            rpc_result(
+                "eth_getCode",
+                json!({"jsonrpc": "2.0", "id": 1, "result": "0x6001"})
+            ),
+            Ok(json!("0x6001"))
+        );
+
+        let err = rpc_result(
+            "eth_getStorageAt",
+            json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "missing trie node"}}),
+        )
+        .unwrap_err();
+        assert!(err.contains("missing trie node"));
+
+        assert!(rpc_result("eth_getCode", json!({"jsonrpc": "2.0", "id": 1})).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use tracing::info;
+
+use crate::{
+    genesis::{GenesisConfig, ValidatorSpec},
+    utils::derive_address,
+};
+
+const ADDRESS_PLACEHOLDER: &str = "<validator address>";
+const CONSENSUS_KEY_PLACEHOLDER: &str = "<consensus public key>";
+const NETWORK_ADDRESS_PLACEHOLDER: &str =
+    "/ip4/<ip>/tcp/<port>/noise-ik/<network public key>/handshake/0";
+const APTOS_ADDRESS_PLACEHOLDER: &str = "<aptos address>";
+
+/// A config with `count` placeholder validators, taking the address of each from
+/// `private_keys` when given. The placeholders fail validation until replaced
+pub fn scaffold_config(count: usize, private_keys: &[String]) -> Result<GenesisConfig, String> {
+    if count == 0 {
+        return Err("a genesis needs at least one validator".to_string());
+    }
+    if !private_keys.is_empty() && private_keys.len() != count {
+        return Err(format!(
+            "{} private keys given for {} validators",
+            private_keys.len(),
+            count
+        ));
+    }
+    let validators = (0..count)
+        .map(|i| {
+            let address = match private_keys.get(i) {
+                Some(key) => derive_address(key)
+                    .map(|address| address.to_string())
+                    .map_err(|e| format!("private key {}: {}", i, e))?,
+                None => ADDRESS_PLACEHOLDER.to_string(),
+            };
+            Ok(ValidatorSpec {
+                address,
+                consensus_public_key: CONSENSUS_KEY_PLACEHOLDER.to_string(),
+                voting_power: "1".to_string(),
+                validator_network_address: NETWORK_ADDRESS_PLACEHOLDER.to_string(),
+                fullnode_network_address: NETWORK_ADDRESS_PLACEHOLDER.to_string(),
+                aptos_address: APTOS_ADDRESS_PLACEHOLDER.to_string(),
+                commission_rate: None,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(GenesisConfig {
+        validators,
+        ..Default::default()
+    })
+}
+
+/// Markdown notes on where each validator field of a scaffolded config comes from
+pub fn scaffold_notes(config_file: &str, derived_addresses: bool) -> String {
+    let address_source = if derived_addresses {
+        "Derived from the given private keys, in the same order."
+    } else {
+        "Replace `<validator address>` with the address of the validator's account."
+    };
+    format!(
+        r#"# Filling in {config_file}
+
+Every entry of `validators` describes one validator. Each field also has a legacy
+form, an array holding that field of every validator in order, named below in
+parentheses. The arrays must all be the same length; a config may use either form
+but not both.
+
+## `address` (`validatorAddresses`)
+The EVM account of the validator, a 20 byte hex address. It is the keccak256 of
+the secp256k1 public key of the validator's private key, the same derivation as
+any Ethereum account, and is the address that owns the validator's stake.
+{address_source}
+
+## `consensusPublicKey` (`consensusPublicKeys`)
+The BLS12-381 public key the node signs consensus messages with, as hex of 48
+bytes compressed or 96 bytes uncompressed. It comes from the node's consensus key,
+not from the EVM private key above.
+
+## `votingPower` (`votingPowers`)
+Stake of the validator in ether, converted to wei when initializing.
+
+## `validatorNetworkAddress` and `fullnodeNetworkAddress` (`validatorNetworkAddresses`, `fullnodeNetworkAddresses`)
+Where other nodes reach the validator and its fullnode, as
+`/ip4/<ip>/tcp/<port>/noise-ik/<network public key>/handshake/0`. The network
+public key is the x25519 key of the node's network identity.
+
+## `aptosAddress` (`aptosAddresses`)
+The 32 byte hex account address of the validator on the consensus layer, the
+identity block proposers are matched against.
+
+Once every placeholder is replaced, run generation with `--dry-run` to validate
+the config without writing any output.
+"#
+    )
+}
+
+/// Write a scaffolded config and its notes, as a sibling `.md` file. Existing files
+/// are not overwritten. Returns the path of the notes
+pub fn write_scaffold(
+    config_file: &str,
+    count: usize,
+    private_keys: &[String],
+) -> Result<String, String> {
+    let config_path = Path::new(config_file);
+    let notes_path = config_path.with_extension("md");
+    for path in [config_path, notes_path.as_path()] {
+        if path.exists() {
+            return Err(format!("{} already exists", path.display()));
+        }
+    }
+
+    let config = scaffold_config(count, private_keys)?;
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("failed to serialize {}: {}", config_file, e))?;
+    std::fs::write(config_path, json)
+        .map_err(|e| format!("failed to write {}: {}", config_file, e))?;
+
+    let file_name = config_path
+        .file_name()
+        .map_or(config_file.into(), |name| name.to_string_lossy());
+    let notes = scaffold_notes(&file_name, !private_keys.is_empty());
+    std::fs::write(&notes_path, notes)
+        .map_err(|e| format!("failed to write {}: {}", notes_path.display(), e))?;
+    info!(
+        "Wrote {} validators to {} with notes in {}",
+        count,
+        config_file,
+        notes_path.display()
+    );
+    Ok(notes_path.display().to_string())
+}
+
+/// Private keys from a file holding one hex key per line, skipping blank lines
+pub fn read_private_keys(path: &str) -> Result<Vec<String>, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm_primitives::address;
+
+    #[test]
+    fn test_scaffold_config() {
+        // The first Anvil/Hardhat development account
+        let key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string();
+        assert_eq!(
+            derive_address(&key),
+            Ok(address!("f39fd6e51aad88f6f4ce6ab8827279cfffb92266"))
+        );
+
+        let config = scaffold_config(1, &[key]).unwrap();
+        assert_eq!(config.validators.len(), 1);
+        assert_eq!(
+            config.validators[0].address,
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        // The placeholders must be replaced before the config is accepted
+        assert!(config.validate().is_err());
+
+        let config = scaffold_config(3, &[]).unwrap();
+        assert_eq!(config.validators.len(), 3);
+        assert_eq!(config.validators[2].address, ADDRESS_PLACEHOLDER);
+
+        assert!(scaffold_config(0, &[]).is_err());
+        assert!(scaffold_config(2, &["01".repeat(32)]).is_err());
+        assert!(derive_address("0x1234").is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use alloy_sol_types::{SolCall, SolValue};
+use revm_primitives::Bytes;
+use tracing::{error, info};
+
+use crate::{
+    genesis::{
+        Genesis, GenesisConfig, ValidatorSpec, call_genesis_initialize, parse_genesis_config,
+    },
+    jwks::{
+        ProviderJWKs, RSATestJWK, call_upsert_observed_jwks, call_upsert_oidc_provider,
+        create_provider_jwks, create_test_rsa_jwk, upsertOIDCProviderCall, upsertObservedJWKsCall,
+    },
+    utils::GENESIS_ADDR,
+};
+
+/// Outcome of one encode/decode round trip
+#[derive(Debug)]
+pub struct SelftestCheck {
+    pub name: &'static str,
+    pub result: Result<(), String>,
+}
+
+type RoundTrip = fn() -> Result<(), String>;
+
+/// Encode every call genesis makes from built-in sample inputs and decode it back,
+/// so changes to the `sol!` definitions can be checked without a full run
+pub fn run_selftest() -> Vec<SelftestCheck> {
+    let checks: [(&'static str, RoundTrip); 3] = [
+        ("Genesis.initialize", check_genesis_initialize),
+        ("JWKManager.upsertObservedJWKs", check_upsert_observed_jwks),
+        ("JWKManager.upsertOIDCProvider", check_upsert_oidc_provider),
+    ];
+    checks
+        .into_iter()
+        .map(|(name, check)| SelftestCheck {
+            name,
+            result: check(),
+        })
+        .collect()
+}
+
+/// Log every check and return whether all of them passed
+pub fn print_selftest(checks: &[SelftestCheck]) -> bool {
+    info!("=== Encoder selftest ===");
+    for check in checks {
+        match &check.result {
+            Ok(()) => info!("PASS {}", check.name),
+            Err(e) => error!("FAIL {}: {}", check.name, e),
+        }
+    }
+    let failed = checks.iter().filter(|check| check.result.is_err()).count();
+    info!("{} passed, {} failed", checks.len() - failed, failed);
+    failed == 0
+}
+
+fn sample_config() -> GenesisConfig {
+    let validators = [
+        ("0x6e2021ee24e2430da0f5bb9c2ae6c586bf3e0a0f", "20000", 2024, "500"),
+        ("0xedde7f05ae91961d0804ec634d7535969b7d171f", "30000", 2025, "1000"),
+    ]
+    .into_iter()
+    .map(|(address, voting_power, port, commission_rate)| {
+        let network_address = format!(
+            "/ip4/127.0.0.1/tcp/{}/noise-ik/{}/handshake/0",
+            port,
+            address.trim_start_matches("0x")
+        );
+        ValidatorSpec {
+            address: address.to_string(),
+            consensus_public_key: "851d41932d866f5fabed6673898e15473e6a0adcf5033d2c93816c6b115c85ad3451e0bac61d570d5ed9f23e1e7f77c4".to_string(),
+            voting_power: voting_power.to_string(),
+            validator_network_address: network_address.clone(),
+            fullnode_network_address: network_address,
+            aptos_address: format!("{:0>64}", address.trim_start_matches("0x")),
+            commission_rate: Some(commission_rate.to_string()),
+        }
+    })
+    .collect();
+    GenesisConfig {
+        validators,
+        ..Default::default()
+    }
+}
+
+fn sample_provider_jwks() -> ProviderJWKs {
+    create_provider_jwks(
+        "https://accounts.google.com",
+        1,
+        vec![create_test_rsa_jwk(
+            "test-kid",
+            "RS256",
+            "AQAB",
+            "sample-modulus",
+        )],
+    )
+}
+
+fn expect_eq<T: PartialEq + std::fmt::Debug>(
+    field: &str,
+    decoded: T,
+    expected: T,
+) -> Result<(), String> {
+    if decoded == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} decoded as {:?}, expected {:?}",
+            field, decoded, expected
+        ))
+    }
+}
+
+fn check_genesis_initialize() -> Result<(), String> {
+    let config = sample_config();
+    let expected = parse_genesis_config(&config).map_err(|e| e.to_string())?;
+    let txn = call_genesis_initialize(GENESIS_ADDR, &config).map_err(|e| e.to_string())?;
+    let call = Genesis::initializeCall::abi_decode(&txn.data, true)
+        .map_err(|e| format!("failed to decode call data: {}", e))?;
+
+    expect_eq(
+        "validatorAddresses",
+        call.validatorAddresses,
+        expected.validator_addresses,
+    )?;
+    expect_eq(
+        "consensusPublicKeys",
+        call.consensusPublicKeys,
+        expected.consensus_public_keys,
+    )?;
+    expect_eq("votingPowers", call.votingPowers, expected.voting_powers)?;
+    expect_eq(
+        "validatorNetworkAddresses",
+        call.validatorNetworkAddresses,
+        expected.validator_network_addresses,
+    )?;
+    expect_eq(
+        "fullnodeNetworkAddresses",
+        call.fullnodeNetworkAddresses,
+        expected.fullnode_network_addresses,
+    )?;
+    expect_eq(
+        "aptosAddresses",
+        call.aptosAddresses,
+        expected.aptos_addresses,
+    )?;
+    expect_eq(
+        "commissionRates",
+        call.commissionRates,
+        expected.commission_rates,
+    )
+}
+
+fn check_upsert_observed_jwks() -> Result<(), String> {
+    let expected = sample_provider_jwks();
+    let txn = call_upsert_observed_jwks(vec![sample_provider_jwks()]);
+    let call = upsertObservedJWKsCall::abi_decode(&txn.data, true)
+        .map_err(|e| format!("failed to decode call data: {}", e))?;
+
+    let [provider] = call.providerJWKsArray.as_slice() else {
+        return Err(format!(
+            "decoded {} providers, expected 1",
+            call.providerJWKsArray.len()
+        ));
+    };
+    expect_eq("issuer", &provider.issuer, &expected.issuer)?;
+    expect_eq("version", provider.version, expected.version)?;
+    expect_eq("jwks", provider.jwks.len(), expected.jwks.len())?;
+    for (jwk, expected_jwk) in provider.jwks.iter().zip(&expected.jwks) {
+        expect_eq("jwk variant", jwk.variant, expected_jwk.variant)?;
+        expect_eq::<&Bytes>("jwk data", &jwk.data, &expected_jwk.data)?;
+        let rsa_jwk = RSATestJWK::abi_decode(&jwk.data, true)
+            .map_err(|e| format!("failed to decode RSA JWK: {}", e))?;
+        expect_eq("jwk kid", rsa_jwk.kid.as_str(), "test-kid")?;
+        expect_eq("jwk n", rsa_jwk.n.as_str(), "sample-modulus")?;
+    }
+    Ok(())
+}
+
+fn check_upsert_oidc_provider() -> Result<(), String> {
+    let name = "https://accounts.google.com";
+    let config_url = "https://accounts.google.com/.well-known/openid-configuration";
+    let txn = call_upsert_oidc_provider(name.to_string(), config_url.to_string());
+    let call = upsertOIDCProviderCall::abi_decode(&txn.data, true)
+        .map_err(|e| format!("failed to decode call data: {}", e))?;
+
+    expect_eq("name", call.name.as_str(), name)?;
+    expect_eq("configUrl", call.configUrl.as_str(), config_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selftest_passes() {
+        for check in run_selftest() {
+            assert_eq!(check.result, Ok(()), "{}", check.name);
+        }
+    }
+}
@@ -1,16 +1,26 @@
-use alloy_primitives::address;
+use alloy_primitives::{address, hex as hex_literal};
 
 use alloy_sol_macro::sol;
-use alloy_sol_types::SolEvent;
+use alloy_sol_types::{Panic, PanicKind, Revert, SolError, SolEvent};
+use k256::ecdsa::SigningKey;
 use revm::{
     DatabaseCommit, DatabaseRef, EvmBuilder, StateBuilder,
     db::{BundleState, states::bundle_state::BundleRetention},
     primitives::{Address, EVMError, Env, ExecutionResult, SpecId, TxEnv, U256},
 };
-use revm_primitives::{AccountInfo, Bytes, KECCAK_EMPTY, TxKind, hex, uint};
-use std::u64;
+use revm_primitives::{
+    AccessListItem, AccountInfo, B256, Bytes, KECCAK_EMPTY, TxKind, hex, keccak256, uint,
+};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::LazyLock,
+    time::{Duration, Instant},
+    u64,
+};
 use tracing::info;
 
+use crate::{error::GenesisError, events::describe_log, execute::DEFAULT_GAS_LIMIT};
+
 pub const DEAD_ADDRESS: Address = address!("000000000000000000000000000000000000dEaD");
 pub const GENESIS_ADDR: Address = address!("0000000000000000000000000000000000002008");
 pub const SYSTEM_CONTRACT_ADDRESS: Address = address!("00000000000000000000000000000000000020FF");
@@ -27,6 +37,8 @@ pub const BLOCK_ADDR: Address = address!("00000000000000000000000000000000000020
 pub const TIMESTAMP_ADDR: Address = address!("0000000000000000000000000000000000002017");
 pub const JWK_MANAGER_ADDR: Address = address!("0000000000000000000000000000000000002018");
 pub const KEYLESS_ACCOUNT_ADDR: Address = address!("0000000000000000000000000000000000002019");
+/// Where `KeylessAccount.initialize` expects the predeployed `Groth16Verifier`
+pub const GROTH16_VERIFIER_ADDR: Address = address!("0000000000000000000000000000000000001010");
 pub const SYSTEM_REWARD_ADDR: Address = address!("000000000000000000000000000000000000201a");
 pub const GOV_HUB_ADDR: Address = address!("000000000000000000000000000000000000201b");
 pub const STAKE_CREDIT_ADDR: Address = address!("000000000000000000000000000000000000201c");
@@ -37,7 +49,21 @@ pub const TIMELOCK_ADDR: Address = address!("00000000000000000000000000000000000
 // this address is used to call evm. It's not used for gravity pre compile contract
 pub const SYSTEM_CALLER: Address = address!("0000000000000000000000000000000000002000");
 
-pub const CONTRACTS: [(&str, Address); 18] = [
+/// The widely deployed deterministic deployment proxy, through which the system
+/// contracts are constructed when the config sets `create2`
+pub const CREATE2_DEPLOYER: Address = address!("4e59b44847b379578588920ca78fbf26c0b4956c");
+/// Runtime code of `CREATE2_DEPLOYER`: called with a 32 byte salt followed by init
+/// code, it runs CREATE2 and returns the 20 byte created address. A reverting
+/// constructor makes it revert without data
+pub const CREATE2_DEPLOYER_CODE: &[u8] = &hex_literal!(
+    "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf3"
+);
+
+/// System contracts in deployment order, each with the fixed address it is placed at
+///
+/// Both deploy modes iterate this table, reading `<name>.hex` or the `<name>.json`
+/// artifact from the bytecode directory, so adding a contract is one entry here.
+pub const CONTRACTS: [(&str, Address); 19] = [
     ("System", SYSTEM_CONTRACT_ADDRESS),
     ("SystemReward", SYSTEM_REWARD_ADDR),
     ("StakeConfig", STAKE_CONFIG_ADDR),
@@ -59,8 +85,45 @@ pub const CONTRACTS: [(&str, Address); 18] = [
     ("StakeCredit", STAKE_CREDIT_ADDR),
     ("Delegation", DELEGATION_ADDR),
     ("GovHub", GOV_HUB_ADDR),
+    ("Groth16Verifier", GROTH16_VERIFIER_ADDR),
 ];
 
+/// Contracts under `src/` that are deliberately not placed at a system address:
+/// `Bytes` and `JWKUtils` only have internal functions, so they are inlined into
+/// the contracts using them and leave no link placeholders, and the abstract
+/// `Protectable` has no code. Every other contract must have an entry in `CONTRACTS`
+pub const UNMAPPED_CONTRACTS: [&str; 3] = ["Bytes", "JWKUtils", "Protectable"];
+
+/// Fixed addresses outside `CONTRACTS` that no system contract may be placed at
+pub const RESERVED_ADDRESSES: [(&str, Address); 4] = [
+    ("SYSTEM_CALLER", SYSTEM_CALLER),
+    ("DEAD_ADDRESS", DEAD_ADDRESS),
+    ("CREATE2_DEPLOYER", CREATE2_DEPLOYER),
+    ("PERFORMANCE_TRACKER_ADDR", PERFORMANCE_TRACKER_ADDR),
+];
+
+/// Check that `contracts`, the system contracts as `CONTRACTS` or a genesis config
+/// lays them out, and `RESERVED_ADDRESSES` never assign one address twice, which
+/// would let one contract silently overwrite another in the genesis state
+pub fn check_unique_addresses(contracts: &[(&str, Address)]) -> Result<(), String> {
+    find_address_collision(contracts.iter().chain(&RESERVED_ADDRESSES))
+}
+
+fn find_address_collision<'a>(
+    entries: impl Iterator<Item = &'a (&'a str, Address)>,
+) -> Result<(), String> {
+    let mut seen: HashMap<Address, &str> = HashMap::new();
+    for (name, address) in entries {
+        if let Some(first) = seen.insert(*address, name) {
+            return Err(format!(
+                "{} and {} share the address {:?}",
+                first, name, address
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub const SYSTEM_ACCOUNT_INFO: AccountInfo = AccountInfo {
     balance: uint!(1_000_000_000_000_000_000_U256),
     nonce: 1,
@@ -73,6 +136,196 @@ sol! {
     event Log(string message, uint256 value);
 }
 
+type ErrorDecoder = fn(&[u8]) -> Option<String>;
+
+fn decode_custom_error<E: SolError + std::fmt::Debug>(output: &[u8]) -> Option<String> {
+    E::abi_decode(output, true)
+        .ok()
+        .map(|error| format!("{:?}", error))
+}
+
+/// Declare the custom errors of the system contracts and a registry of them by
+/// selector, so each is only spelled out once
+macro_rules! system_errors {
+    ($($name:ident($($ty:ident $arg:ident),*);)*) => {
+        sol! {
+            #[derive(Debug)]
+            interface SystemErrors {
+                $(error $name($($ty $arg),*);)*
+            }
+        }
+
+        fn system_error_registry() -> HashMap<[u8; 4], (&'static str, ErrorDecoder)> {
+            HashMap::from([$((
+                SystemErrors::$name::SELECTOR,
+                (
+                    stringify!($name),
+                    decode_custom_error::<SystemErrors::$name> as ErrorDecoder,
+                ),
+            )),*])
+        }
+    };
+}
+
+// Mirrors the `error` declarations under `src/`. `NotAuthorized()` of JWKManager and
+// KeylessAccount is left out, as it overloads EpochManager's `NotAuthorized(address)`
+system_errors! {
+    // System
+    InvalidValue(string key, bytes value);
+    OnlyCoinbase();
+    OnlySystemCaller(address errorAddress);
+    OnlySystemContract(address systemContract);
+    OnlyZeroGasPrice();
+    UnknownParam(string key, bytes value);
+    // Protectable
+    AlreadyPaused();
+    InBlackList();
+    NotPaused();
+    // Genesis
+    GenesisAlreadyCompleted();
+    InvalidInitialValidators();
+    // Block
+    InvalidProposer(bytes proposer);
+    // Delegation
+    Delegation__LessThanMinDelegationChange();
+    Delegation__OnlySelfDelegationToJailedValidator();
+    Delegation__SameValidator();
+    Delegation__TransferFailed();
+    Delegation__ValidatorNotRegistered(address validator);
+    Delegation__ZeroShares();
+    InvalidValidator();
+    // EpochManager
+    EpochManager__ParameterNotFound(string param);
+    InvalidEpochDuration();
+    NotAuthorized(address caller);
+    // GovToken
+    ApproveNotAllowed();
+    BurnNotAllowed();
+    ERC20InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+    ERC20InsufficientBalance(address sender, uint256 balance, uint256 needed);
+    ERC20InvalidApprover(address approver);
+    ERC20InvalidReceiver(address receiver);
+    ERC20InvalidSender(address sender);
+    ERC20InvalidSpender(address spender);
+    TransferNotAllowed();
+    // GravityGovernor
+    NotWhitelisted();
+    OneLiveProposalPerProposer();
+    TotalSupplyNotEnough();
+    // Groth16Verifier
+    ProofInvalid();
+    PublicInputNotInField();
+    // JWKManager and JWKUtils
+    DuplicateProvider();
+    EmptyExponent();
+    EmptyKid();
+    EmptyModulus();
+    FederatedJWKsTooLarge();
+    InvalidJWKFormat();
+    InvalidJWKType();
+    InvalidJWKVariant(uint8 variant);
+    InvalidJWKVersion(uint64 expected, uint64 actual);
+    InvalidOIDCProvider();
+    InvalidRSAJWK();
+    IssuerNotFound();
+    JWKManager__ParameterNotFound(string key);
+    JWKNotFound();
+    UnknownJWKVariant();
+    UnknownPatchVariant();
+    // KeylessAccount
+    AccountCreationFailed();
+    ExceededMaxExpHorizon();
+    ExceededMaxSignaturesPerTxn();
+    InvalidProof();
+    InvalidSignature();
+    InvalidTrainingWheelsPK();
+    JWTVerificationFailed();
+    KeylessAccount__ParameterNotFound(string key);
+    // StakeConfig
+    StakeConfig__DenominatorMustBePositive();
+    StakeConfig__InvalidCommissionRate(uint256 rate, uint256 maxRate);
+    StakeConfig__InvalidLockAmount(uint256 providedAmount);
+    StakeConfig__InvalidStakeRange(uint256 minStake, uint256 maxStake);
+    StakeConfig__InvalidVotingPowerIncreaseLimit(uint256 actualValue, uint256 maxValue);
+    StakeConfig__ParameterNotFound(string paramName);
+    StakeConfig__RecurringLockupDurationMustBePositive();
+    StakeConfig__RewardsRateCannotExceedLimit(uint256 rewardsRate, uint256 denominator);
+    StakeConfig__StakeLimitsMustBePositive();
+    // StakeCredit
+    InsufficientActiveStake();
+    InsufficientBalance();
+    NoWithdrawableAmount();
+    StakeCredit__NoClaimableRequest();
+    StakeCredit__NoUnlockRequest();
+    StakeCredit__RequestExists();
+    StakeCredit__UnauthorizedCaller();
+    StakeCredit__WrongInitContext(uint256 initialAmount, uint256 lockAmount, address validator);
+    TransferFailed();
+    ZeroAmount();
+    ZeroShares();
+    ZeroTotalPooledTokens();
+    ZeroTotalShares();
+    // Timestamp
+    TimestampMustAdvance(uint64 providedTimestamp, uint64 currentTimestamp);
+    TimestampMustEqual(uint64 providedTimestamp, uint64 currentTimestamp);
+    // ValidatorManager
+    AddressAlreadyInUse(address addr, address currentValidator);
+    AlreadyInitialized();
+    ArrayLengthMismatch();
+    DuplicateConsensusAddress(bytes consensusAddress);
+    DuplicateMoniker(string moniker);
+    DuplicateVoteAddress(bytes voteAddress);
+    InvalidAddress(address addr);
+    InvalidCommission();
+    InvalidCommissionRate(uint64 rate, uint64 maxRate);
+    InvalidMoniker(string moniker);
+    InvalidStakeAmount(uint256 provided, uint256 required);
+    InvalidVoteAddress();
+    InvalidVotingPower(uint256 votingPower);
+    LastValidatorCannotLeave();
+    NewOperatorIsValidatorSelf();
+    NotValidator(address caller, address validator);
+    StakeExceedsMaximum(uint256 provided, uint256 maximum);
+    UnauthorizedCaller(address caller, address validator);
+    UpdateTooFrequently();
+    ValidatorAlreadyExists(address validator);
+    ValidatorNotActive(address validator);
+    ValidatorNotExists(address validator);
+    ValidatorNotInactive(address validator);
+    ValidatorSetChangeDisabled();
+    ValidatorSetReachedMax(uint256 current, uint256 max);
+    VotingPowerIncreaseExceedsLimit();
+    // ValidatorPerformanceTracker
+    DuplicateValidator(address validator);
+    EmptyActiveValidatorSet();
+    InvalidValidatorIndex(uint256 index, uint256 maxIndex);
+}
+
+static SYSTEM_ERRORS: LazyLock<HashMap<[u8; 4], (&'static str, ErrorDecoder)>> =
+    LazyLock::new(system_error_registry);
+
+/// Name of the system contract custom error with this selector
+pub fn known_error_name(selector: &[u8]) -> Option<&'static str> {
+    let selector: [u8; 4] = selector.try_into().ok()?;
+    SYSTEM_ERRORS.get(&selector).map(|(name, _)| *name)
+}
+
+/// Revert data as `revert: <reason>` for `Error(string)` and system contract custom
+/// errors, the latter with their decoded parameters, or as `panic: <code> (<kind>)`
+/// for `Panic(uint256)`. `None` if the data is none of these or does not decode
+pub fn decode_revert_reason(output: &[u8]) -> Option<String> {
+    if let Ok(revert) = Revert::abi_decode(output, true) {
+        return Some(format!("revert: {}", revert.reason));
+    }
+    if let Ok(panic) = Panic::abi_decode(output, true) {
+        let kind = panic.kind().map_or("unknown code", PanicKind::as_str);
+        return Some(format!("panic: {:#x} ({})", panic.code, kind));
+    }
+    let selector: [u8; 4] = output.get(0..4)?.try_into().ok()?;
+    let (_, decode) = SYSTEM_ERRORS.get(&selector)?;
+    decode(output).map(|error| format!("revert: {}", error))
+}
+
 pub fn analyze_txn_result(result: &ExecutionResult) -> String {
     match result {
         ExecutionResult::Revert { gas_used, output } => {
@@ -80,16 +333,14 @@ pub fn analyze_txn_result(result: &ExecutionResult) -> String {
 
             if let Some(selector) = output.get(0..4) {
                 reason.push_str(&format!("\nFunction selector: 0x{}", hex::encode(selector)));
-
-                match selector {
-                    [0x49, 0xfd, 0x36, 0xf2] => reason.push_str(" (OnlySystemCaller)"),
-                    [0x97, 0xb8, 0x83, 0x54] => reason.push_str(" (UnknownParam)"),
-                    [0x0a, 0x5a, 0x60, 0x41] => reason.push_str(" (InvalidValue)"),
-                    [0x11, 0x6c, 0x64, 0xa8] => reason.push_str(" (OnlyCoinbase)"),
-                    [0x83, 0xf1, 0xb1, 0xd3] => reason.push_str(" (OnlyZeroGasPrice)"),
-                    [0xf2, 0x2c, 0x43, 0x90] => reason.push_str(" (OnlySystemContract)"),
-                    _ => reason.push_str(" (Unknown error selector)"),
+                if let Some(decoded) = decode_revert_reason(output) {
+                    reason.push_str(&format!(" ({})", decoded));
+                    return reason;
                 }
+                reason.push_str(&format!(
+                    " ({})",
+                    known_error_name(selector).unwrap_or("Unknown error selector")
+                ));
             }
 
             if output.len() > 4 {
@@ -109,6 +360,8 @@ pub fn analyze_txn_result(result: &ExecutionResult) -> String {
                         "txn event Log: {:?}, {:?}.",
                         parsed.message, parsed.value
                     ));
+                } else {
+                    log_msg.push_str(&format!("txn event {}.", describe_log(log)));
                 }
             }
             format!("Success with gas used: {}, {}", gas_used, log_msg)
@@ -132,9 +385,31 @@ pub(crate) fn execute_revm_sequential<DB>(
 where
     DB: DatabaseRef,
 {
+    execute_revm_sequential_timed(db, spec_id, env, txs, pre_bundle)
+        .map(|(results, _, bundle_state)| (results, bundle_state))
+}
+
+/// Results, per transaction wall times and bundle of `execute_revm_sequential_timed`
+pub(crate) type TimedExecution = (Vec<ExecutionResult>, Vec<Duration>, BundleState);
+
+/// `execute_revm_sequential`, also returning the wall time each transaction took to
+/// execute, logging and committing its state left out
+pub(crate) fn execute_revm_sequential_timed<DB>(
+    db: DB,
+    spec_id: SpecId,
+    env: Env,
+    txs: &[TxEnv],
+    pre_bundle: Option<BundleState>,
+) -> Result<TimedExecution, EVMError<DB::Error>>
+where
+    DB: DatabaseRef,
+{
+    // Without `with_bundle_update` a prestate bundle is returned as is, dropping the
+    // changes of `txs`
     let db = if let Some(pre_bundle) = pre_bundle {
         StateBuilder::new()
             .with_bundle_prestate(pre_bundle)
+            .with_bundle_update()
             .with_database_ref(db)
             .build()
     } else {
@@ -150,6 +425,7 @@ where
         .build();
 
     let mut results = Vec::with_capacity(txs.len());
+    let mut timings = Vec::with_capacity(txs.len());
     for (i, tx) in txs.iter().enumerate() {
         info!("=== Executing transaction {} ===", i + 1);
         info!("Transaction details:");
@@ -162,9 +438,13 @@ where
 
         *evm.tx_mut() = tx.clone();
 
+        let start = Instant::now();
         let result_and_state = evm.transact()?;
+        timings.push(start.elapsed());
         info!("transaction evm state {:?}", result_and_state.state);
         evm.db_mut().commit(result_and_state.state);
+        // One revert per transaction, so what each one changed can be told apart
+        evm.db_mut().merge_transitions(BundleRetention::Reverts);
 
         info!(
             "Transaction result: {}",
@@ -173,15 +453,74 @@ where
         results.push(result_and_state.result);
         info!("=== Transaction {} completed ===", i + 1);
     }
-    evm.db_mut().merge_transitions(BundleRetention::Reverts);
 
-    Ok((results, evm.db_mut().take_bundle()))
+    Ok((results, timings, evm.db_mut().take_bundle()))
+}
+
+/// Access list of the accounts and storage slots each transaction touches, found by
+/// executing `txs` in order against a scratch state on top of `db`
+///
+/// The caller and coinbase are left out, as is the call target unless its storage
+/// is accessed, since those are warm regardless. Accounts only loaded by the EVM
+/// itself, without being touched or having slots read, are skipped too.
+pub(crate) fn derive_access_lists<DB>(
+    db: DB,
+    spec_id: SpecId,
+    env: Env,
+    txs: &[TxEnv],
+) -> Result<Vec<Vec<AccessListItem>>, EVMError<DB::Error>>
+where
+    DB: DatabaseRef,
+{
+    let coinbase = env.block.coinbase;
+    let db = StateBuilder::new().with_database_ref(db).build();
+    let mut evm = EvmBuilder::default()
+        .with_db(db)
+        .with_spec_id(spec_id)
+        .with_env(Box::new(env))
+        .build();
+
+    let mut access_lists = Vec::with_capacity(txs.len());
+    for tx in txs {
+        *evm.tx_mut() = tx.clone();
+        let result_and_state = evm.transact()?;
+
+        let mut access_list: Vec<AccessListItem> = result_and_state
+            .state
+            .iter()
+            .filter(|(address, _)| **address != tx.caller && **address != coinbase)
+            .map(|(address, account)| {
+                let mut storage_keys: Vec<B256> = account
+                    .storage
+                    .keys()
+                    .map(|slot| B256::from(*slot))
+                    .collect();
+                storage_keys.sort();
+                let item = AccessListItem {
+                    address: *address,
+                    storage_keys,
+                };
+                (item, account.is_touched())
+            })
+            .filter(|(item, touched)| {
+                !item.storage_keys.is_empty()
+                    || (*touched && tx.transact_to.to() != Some(&item.address))
+            })
+            .map(|(item, _)| item)
+            .collect();
+        access_list.sort_by_key(|item| item.address);
+
+        evm.db_mut().commit(result_and_state.state);
+        access_lists.push(access_list);
+    }
+    Ok(access_lists)
 }
 
+/// System call from `SYSTEM_CALLER`, with an empty access list
 pub fn new_system_call_txn(contract: Address, input: Bytes) -> TxEnv {
     TxEnv {
         caller: SYSTEM_CALLER,
-        gas_limit: u64::MAX,
+        gas_limit: DEFAULT_GAS_LIMIT,
         gas_price: U256::ZERO,
         transact_to: TxKind::Call(contract),
         value: U256::ZERO,
@@ -190,12 +529,19 @@ pub fn new_system_call_txn(contract: Address, input: Bytes) -> TxEnv {
     }
 }
 
+/// Contract creation from `SYSTEM_CALLER`, with an empty access list
 pub fn new_system_create_txn(hex_code: &str, args: Bytes) -> TxEnv {
-    let mut data = hex::decode(hex_code).expect("Invalid hex string");
+    new_system_create_txn_from_bytes(hex::decode(hex_code).expect("Invalid hex string"), args)
+}
+
+/// Contract creation from `SYSTEM_CALLER` of already decoded creation bytecode,
+/// which becomes the transaction data without another copy
+pub fn new_system_create_txn_from_bytes(creation_bytecode: Vec<u8>, args: Bytes) -> TxEnv {
+    let mut data = creation_bytecode;
     data.extend_from_slice(&args);
     TxEnv {
         caller: SYSTEM_CALLER,
-        gas_limit: u64::MAX,
+        gas_limit: DEFAULT_GAS_LIMIT,
         gas_price: U256::ZERO,
         transact_to: TxKind::Create,
         value: U256::ZERO,
@@ -204,6 +550,773 @@ pub fn new_system_create_txn(hex_code: &str, args: Bytes) -> TxEnv {
     }
 }
 
+/// `tx` with its gas limit set to `gas_limit`, for system transactions built for a
+/// genesis with a configured limit
+pub fn with_gas_limit(tx: TxEnv, gas_limit: u64) -> TxEnv {
+    TxEnv { gas_limit, ..tx }
+}
+
+/// `tx` sent from `caller`, for system transactions built for a genesis with a
+/// configured system caller
+pub fn with_caller(tx: TxEnv, caller: Address) -> TxEnv {
+    TxEnv { caller, ..tx }
+}
+
+/// `tx` sent to the address `contracts`, in `CONTRACTS` order, gives the system
+/// contract it calls, for system calls built for a genesis that overrides the
+/// constant addresses
+pub fn with_contract_addresses(tx: TxEnv, contracts: &[(&str, Address)]) -> TxEnv {
+    let TxKind::Call(to) = tx.transact_to else {
+        return tx;
+    };
+    match CONTRACTS
+        .iter()
+        .zip(contracts)
+        .find(|((_, constant), _)| *constant == to)
+    {
+        Some((_, (_, address))) => TxEnv {
+            transact_to: TxKind::Call(*address),
+            ..tx
+        },
+        None => tx,
+    }
+}
+
+/// Address of the account controlled by a secp256k1 private key, the keccak256 of
+/// its uncompressed public key as Ethereum derives it. Errors never include the key
+pub fn derive_address(private_key: &str) -> Result<Address, String> {
+    let bytes = hex::decode(private_key.trim()).map_err(|_| "private key is not hex")?;
+    let signing_key = SigningKey::from_slice(&bytes)
+        .map_err(|_| "private key is not a valid 32 byte secp256k1 key")?;
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    // Skip the 0x04 tag of the uncompressed encoding
+    Ok(Address::from_raw_public_key(&public_key.as_bytes()[1..]))
+}
+
+/// Peak resident set size of the current process in kB, read from `/proc/self/status`
+///
+/// Returns `None` on platforms without procfs.
+pub fn peak_rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+}
+
+/// An address displayed and serialized with its EIP-55 checksum. It orders like the
+/// address itself, so maps keyed by it stay in address order. Input is still parsed
+/// as a plain `Address`, which accepts any case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChecksumAddress(pub Address);
+
+impl std::fmt::Display for ChecksumAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.to_checksum(None))
+    }
+}
+
+impl serde::Serialize for ChecksumAddress {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Path of the Foundry artifact for `name`, i.e. `<dir>/<name>.sol/<name>.json`
+pub fn artifact_path(dir: &str, name: &str) -> String {
+    format!("{}/{}.sol/{}.json", dir, name, name)
+}
+
+/// Load the Foundry artifact for `name` from the compiler output directory
+pub fn read_artifact(dir: &str, name: &str) -> Result<serde_json::Value, String> {
+    let path = artifact_path(dir, name);
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path, e))
+}
+
+/// Creation bytecode (`bytecode.object`, constructor included) of a contract,
+/// taken from its Foundry artifact and linked against `contracts`, as a hex string
+/// without `0x` prefix
+pub fn read_bytecode_from_artifact(
+    dir: &str,
+    name: &str,
+    contracts: &[(&str, Address)],
+) -> Result<String, String> {
+    read_linked_artifact_code(dir, name, "bytecode", contracts)
+}
+
+/// Runtime bytecode (`deployedBytecode.object`) of a contract, taken from its
+/// Foundry artifact and linked against `contracts`, as a hex string without `0x`
+/// prefix
+pub fn read_deployed_bytecode_from_artifact(
+    dir: &str,
+    name: &str,
+    contracts: &[(&str, Address)],
+) -> Result<String, String> {
+    read_linked_artifact_code(dir, name, "deployedBytecode", contracts)
+}
+
+fn read_linked_artifact_code(
+    dir: &str,
+    name: &str,
+    field: &str,
+    contracts: &[(&str, Address)],
+) -> Result<String, String> {
+    let artifact = read_artifact(dir, name)?;
+    let object = artifact
+        .pointer(&format!("/{}/object", field))
+        .and_then(|object| object.as_str())
+        .ok_or_else(|| format!("No {}.object in artifact of {}", field, name))?;
+    let linked = link_system_libraries(&artifact, field, name, object, contracts)?;
+    Ok(linked.trim_start_matches("0x").to_string())
+}
+
+/// Link the `bytecode` or `deployedBytecode` hex of a Foundry artifact against the
+/// addresses `contracts` gives the libraries its `linkReferences` name
+pub fn link_system_libraries(
+    artifact: &serde_json::Value,
+    field: &str,
+    name: &str,
+    hex_code: &str,
+    contracts: &[(&str, Address)],
+) -> Result<String, String> {
+    let mut libraries = HashMap::new();
+    let link_references = artifact
+        .pointer(&format!("/{}/linkReferences", field))
+        .and_then(|references| references.as_object());
+    for (source, references) in link_references.into_iter().flatten() {
+        for library in references
+            .as_object()
+            .into_iter()
+            .flat_map(|names| names.keys())
+        {
+            if let Some((_, address)) = contracts.iter().find(|(contract, _)| contract == library) {
+                libraries.insert(format!("{}:{}", source, library), *address);
+            }
+        }
+    }
+    link_libraries(name, hex_code, &libraries)
+}
+
+/// Replace the `__$<hash>$__` placeholder of every library in `libraries`, keyed by
+/// its fully qualified `<source>:<name>`, with the library's address
+///
+/// The placeholder hash is the first 17 bytes of the keccak of the fully qualified
+/// name, so the 40 character placeholder is exactly as long as the address.
+pub fn link_libraries(
+    name: &str,
+    hex_code: &str,
+    libraries: &HashMap<String, Address>,
+) -> Result<String, String> {
+    let mut linked = hex_code.to_string();
+    for (library, address) in libraries {
+        let placeholder = format!("__${}$__", hex::encode(&keccak256(library)[..17]));
+        linked = linked.replace(&placeholder, &hex::encode(address));
+    }
+    check_linked(name, &linked)?;
+    Ok(linked)
+}
+
+/// Reject bytecode still holding `__$<hash>$__` placeholders after linking, from a
+/// library that has no entry in `CONTRACTS` to be deployed and linked at
+pub fn check_linked(name: &str, hex_code: &str) -> Result<(), String> {
+    if hex_code.contains("__$") {
+        return Err(format!(
+            "bytecode of {} has unlinked library placeholders",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `address` is where a system contract lives or could: an entry of
+/// `contracts`, as the config lays them out, or the `0x2001`-`0x20ff` block
+/// `System.sol` assigns its addresses from. The system caller at `0x2000` sends
+/// transactions and never has code
+pub fn is_system_address(address: &Address, contracts: &[(&str, Address)]) -> bool {
+    let value = U256::from_be_slice(address.as_slice());
+    contracts.iter().any(|(_, contract)| contract == address)
+        || (U256::from(0x2001)..=U256::from(0x20ff)).contains(&value)
+}
+
+/// System addresses, per `is_system_address` with `contracts`, runtime `code`
+/// pushes onto the stack, such as a library it `DELEGATECALL`s. Linked library
+/// addresses are 20 byte pushes, while the compiler shortens address constants to
+/// their significant bytes. A shorter push whose value is a `JUMPDEST` in `code` is
+/// taken to be a jump target instead
+pub fn referenced_system_addresses(
+    code: &[u8],
+    contracts: &[(&str, Address)],
+) -> BTreeSet<Address> {
+    const JUMPDEST: u8 = 0x5b;
+    const PUSH1: u8 = 0x60;
+    const PUSH32: u8 = 0x7f;
+
+    // Immediates of every push, with the opcode positions outside them
+    let mut pushes = Vec::new();
+    let mut jumpdests = HashSet::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        if (PUSH1..=PUSH32).contains(&opcode) {
+            let width = (opcode - PUSH1 + 1) as usize;
+            let immediate = &code[(pc + 1).min(code.len())..(pc + 1 + width).min(code.len())];
+            pushes.push((width, immediate));
+            pc += width;
+        } else if opcode == JUMPDEST {
+            jumpdests.insert(pc);
+        }
+        pc += 1;
+    }
+
+    pushes
+        .into_iter()
+        .filter(|(width, immediate)| *width <= 20 && immediate.len() == *width)
+        .filter_map(|(width, immediate)| {
+            let mut address = Address::ZERO;
+            address.0[20 - width..].copy_from_slice(immediate);
+            let value = U256::from_be_slice(immediate);
+            let jump_target = width < 20 && jumpdests.contains(&value.saturating_to::<usize>());
+            (is_system_address(&address, contracts) && !jump_target).then_some(address)
+        })
+        .collect()
+}
+
 pub fn read_hex_from_file(path: &str) -> String {
     std::fs::read_to_string(path).expect(&format!("Failed to open {}", path))
 }
+
+/// Decode a hex value of the config or an input file, given with or without a `0x`
+/// prefix. `field` names the value in the error
+pub fn parse_hex_field(field: &str, value: &str) -> Result<Vec<u8>, GenesisError> {
+    let value = value.trim();
+    hex::decode(value.strip_prefix("0x").unwrap_or(value)).map_err(|source| {
+        GenesisError::HexDecode {
+            field: field.to_string(),
+            source,
+        }
+    })
+}
+
+/// Decode a hex file as it is read, so only the decoded bytes are held in memory
+/// rather than the text as well. Whitespace and a leading `0x` are skipped
+pub fn read_hex_bytes_from_file(path: &str) -> Result<Vec<u8>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let size_hint = file
+        .metadata()
+        .map_or(0, |metadata| metadata.len() as usize);
+    decode_hex_stream(file, size_hint).map_err(|e| format!("Invalid hex in {}: {}", path, e))
+}
+
+/// Decode the hex text of `reader` in fixed size chunks, `size_hint` being the
+/// expected length of the text
+pub fn decode_hex_stream<R: std::io::Read>(
+    mut reader: R,
+    size_hint: usize,
+) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::with_capacity(size_hint / 2);
+    let mut chunk = [0u8; 8192];
+    let mut offset = 0;
+    // Hex digits seen so far, and the high nibble of a byte still missing its low one
+    let mut digits = 0;
+    let mut high = None;
+    loop {
+        let read = reader.read(&mut chunk).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        for &c in &chunk[..read] {
+            offset += 1;
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+            // The `0` of a `0x` prefix was taken for a digit, drop it again
+            if matches!(c, b'x' | b'X') && digits == 1 && high == Some(0) {
+                digits = 0;
+                high = None;
+                continue;
+            }
+            let nibble = (c as char).to_digit(16).ok_or_else(|| {
+                format!("invalid character {:?} at offset {}", c as char, offset - 1)
+            })? as u8;
+            digits += 1;
+            match high.take() {
+                Some(high) => decoded.push(high << 4 | nibble),
+                None => high = Some(nibble),
+            }
+        }
+    }
+    if high.is_some() {
+        return Err("odd number of hex digits".to_string());
+    }
+    Ok(decoded)
+}
+
+/// Reject an output directory that resolves to the byte code directory, so the
+/// generated files never land among (or overwrite) the contract artifacts
+///
+/// Both paths are canonicalized, so they must exist; relative paths and symlinks
+/// are compared by what they point to.
+pub fn check_output_dir(output_dir: &str, byte_code_dir: &str) -> Result<(), String> {
+    let canonical = |path: &str| {
+        std::fs::canonicalize(path).map_err(|e| format!("Failed to resolve {}: {}", path, e))
+    };
+    let resolved = canonical(output_dir)?;
+    if resolved == canonical(byte_code_dir)? {
+        return Err(format!(
+            "--output {} and --byte-code-dir {} are the same directory ({})",
+            output_dir,
+            byte_code_dir,
+            resolved.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Output files written under a temporary name next to their final path, and only
+/// renamed into place by `commit` once every one of them has been written, so a
+/// consumer never reads a half-written artifact. Files still staged when this is
+/// dropped, after a failed write or without a commit, are removed
+#[derive(Debug, Default)]
+pub struct StagedFiles {
+    /// Final path of every staged file, in staging order
+    paths: Vec<String>,
+}
+
+impl StagedFiles {
+    fn temp_path(path: &str) -> String {
+        format!("{}.tmp", path)
+    }
+
+    /// Create the temporary file for `path`, to be written by the caller
+    pub fn create(&mut self, path: String) -> std::io::Result<std::fs::File> {
+        let file = std::fs::File::create(Self::temp_path(&path));
+        self.paths.push(path);
+        file
+    }
+
+    /// Write `contents` to the temporary file for `path`
+    pub fn write(&mut self, path: String, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+        let written = std::fs::write(Self::temp_path(&path), contents);
+        self.paths.push(path);
+        written
+    }
+
+    /// Rename every staged file to its final path. A rename fails only if the
+    /// directory changed underneath, in which case the rest are removed on drop
+    pub fn commit(mut self) -> std::io::Result<()> {
+        for path in &self.paths {
+            std::fs::rename(Self::temp_path(path), path)?;
+        }
+        self.paths.clear();
+        Ok(())
+    }
+}
+
+impl Drop for StagedFiles {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = std::fs::remove_file(Self::temp_path(path));
+        }
+    }
+}
+
+/// Turn JSONC (JSON with `//` and `/* */` comments and trailing commas) into plain
+/// JSON, leaving string contents untouched
+///
+/// Comments are replaced by whitespace, so line numbers in later parse errors still
+/// match the original file.
+pub fn strip_jsonc(input: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push(c);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut closed = false;
+                while let Some(c) = chars.next() {
+                    if c == '*' && chars.peek() == Some(&'/') {
+                        chars.next();
+                        closed = true;
+                        break;
+                    }
+                    if c == '\n' {
+                        output.push(c);
+                    }
+                }
+                if !closed {
+                    return Err("unterminated /* comment".to_string());
+                }
+                output.push(' ');
+            }
+            _ => output.push(c),
+        }
+    }
+
+    Ok(remove_trailing_commas(&output))
+}
+
+/// Drop commas directly followed (ignoring whitespace) by `}` or `]`, outside strings
+fn remove_trailing_commas(input: &str) -> String {
+    let chars: Vec<char> = input.chars().collect();
+    let mut output = String::with_capacity(input.len());
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.get(i + 1) {
+                    output.push(*escaped);
+                    i += 1;
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            output.push(c);
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if !matches!(next, Some('}') | Some(']')) {
+                output.push(c);
+            }
+        } else {
+            output.push(c);
+        }
+        i += 1;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{InMemoryDB, primitives::Bytecode};
+
+    #[test]
+    fn test_checksum_address() {
+        let address = ChecksumAddress(DEAD_ADDRESS);
+        assert_eq!(
+            address.to_string(),
+            "0x000000000000000000000000000000000000dEaD"
+        );
+        assert_eq!(
+            serde_json::to_string(&address).unwrap(),
+            "\"0x000000000000000000000000000000000000dEaD\""
+        );
+        // Parsing stays case insensitive
+        let lowercase: Address = serde_json::from_str(&format!("\"{:?}\"", DEAD_ADDRESS)).unwrap();
+        assert_eq!(lowercase, DEAD_ADDRESS);
+    }
+
+    #[test]
+    fn test_referenced_system_addresses() {
+        // PUSH20 of ValidatorManagerUtils, DELEGATECALL, then PUSH2 0x200f, PUSH2 0x2010
+        // and PUSH2 0x2000
+        let mut code =
+            hex::decode("730000000000000000000000000000000000002014f461200f612010612000").unwrap();
+        // A JUMPDEST at 0x2010 makes that push a jump target
+        code.resize(0x2010, 0x00);
+        code.push(0x5b);
+
+        let referenced = referenced_system_addresses(&code, &CONTRACTS);
+        assert!(referenced.contains(&VALIDATOR_MANAGER_UTILS_ADDR));
+        assert!(referenced.contains(&PERFORMANCE_TRACKER_ADDR));
+        assert!(!referenced.contains(&EPOCH_MANAGER_ADDR));
+        assert!(!referenced.contains(&SYSTEM_CALLER));
+        // A push cut off by the end of the code is ignored
+        assert!(referenced_system_addresses(&[0x73, 0x20, 0x14], &CONTRACTS).is_empty());
+
+        // A contract the config moved out of the System.sol block is still found
+        let moved = address!("00000000000000000000000000000000000a2014");
+        let mut code = vec![0x73];
+        code.extend_from_slice(moved.as_slice());
+        assert!(referenced_system_addresses(&code, &CONTRACTS).is_empty());
+        let contracts = [("ValidatorManagerUtils", moved)];
+        assert_eq!(
+            referenced_system_addresses(&code, &contracts),
+            BTreeSet::from([moved])
+        );
+    }
+
+    #[test]
+    fn test_unique_addresses() {
+        assert_eq!(check_unique_addresses(&CONTRACTS), Ok(()));
+
+        let entries = [
+            ("Block", BLOCK_ADDR),
+            ("Timestamp", TIMESTAMP_ADDR),
+            ("NewContract", BLOCK_ADDR),
+        ];
+        assert_eq!(
+            find_address_collision(entries.iter()),
+            Err(format!(
+                "Block and NewContract share the address {:?}",
+                BLOCK_ADDR
+            ))
+        );
+    }
+
+    #[test]
+    fn test_link_libraries() {
+        assert_eq!(check_linked("Block", "0x6080604052"), Ok(()));
+
+        let library = "src/lib/ValidatorManagerUtils.sol:ValidatorManagerUtils";
+        let placeholder = format!("__${}$__", hex::encode(&keccak256(library)[..17]));
+        let unlinked = format!("0x73{}6080", placeholder);
+        let libraries = HashMap::from([(library.to_string(), VALIDATOR_MANAGER_UTILS_ADDR)]);
+        assert_eq!(
+            link_libraries("ValidatorManager", &unlinked, &libraries),
+            Ok(format!(
+                "0x73{}6080",
+                hex::encode(VALIDATOR_MANAGER_UTILS_ADDR)
+            ))
+        );
+
+        let err = link_libraries("ValidatorManager", &unlinked, &HashMap::new()).unwrap_err();
+        assert!(err.contains("placeholders"), "{}", err);
+    }
+
+    #[test]
+    fn test_contracts_cover_sources() {
+        let mut sources = Vec::new();
+        let mut dirs = vec![std::path::PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../src"
+        ))];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    if !path.ends_with("interfaces") {
+                        dirs.push(path);
+                    }
+                } else if path.extension().is_some_and(|extension| extension == "sol") {
+                    sources.push(path.file_stem().unwrap().to_string_lossy().into_owned());
+                }
+            }
+        }
+        for source in &sources {
+            let mapped = CONTRACTS.iter().any(|(name, _)| name == source);
+            let unmapped = UNMAPPED_CONTRACTS.contains(&source.as_str());
+            assert!(
+                mapped != unmapped,
+                "{} must be in exactly one of CONTRACTS and UNMAPPED_CONTRACTS",
+                source
+            );
+        }
+        for (name, _) in CONTRACTS {
+            assert!(
+                sources.iter().any(|source| source == name),
+                "no source for {}",
+                name
+            );
+        }
+        // Address uniqueness is `check_unique_addresses`, see `test_unique_addresses`
+    }
+
+    #[test]
+    fn test_decode_revert_reason() {
+        for (selector, name) in [
+            ([0x49, 0xfd, 0x36, 0xf2], "OnlySystemCaller"),
+            ([0x97, 0xb8, 0x83, 0x54], "UnknownParam"),
+            ([0x0a, 0x5a, 0x60, 0x41], "InvalidValue"),
+            ([0x11, 0x6c, 0x64, 0xa8], "OnlyCoinbase"),
+            ([0x83, 0xf1, 0xb1, 0xd3], "OnlyZeroGasPrice"),
+            ([0xf2, 0x2c, 0x43, 0x90], "OnlySystemContract"),
+        ] {
+            assert_eq!(known_error_name(&selector), Some(name));
+        }
+
+        let revert = Revert::from("OnlySystemContract").abi_encode();
+        assert_eq!(
+            decode_revert_reason(&revert).as_deref(),
+            Some("revert: OnlySystemContract")
+        );
+        let panic = Panic::from(PanicKind::UnderOverflow).abi_encode();
+        assert_eq!(
+            decode_revert_reason(&panic).as_deref(),
+            Some("panic: 0x11 (arithmetic underflow or overflow)")
+        );
+        let custom = SystemErrors::InvalidJWKVersion {
+            expected: 2,
+            actual: 1,
+        }
+        .abi_encode();
+        assert_eq!(
+            decode_revert_reason(&custom).as_deref(),
+            Some("revert: InvalidJWKVersion { expected: 2, actual: 1 }")
+        );
+        assert_eq!(decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]), None);
+    }
+
+    #[test]
+    fn test_derive_access_lists_records_touched_slots() {
+        let contract = address!("0000000000000000000000000000000000001234");
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+        // PUSH1 0x01 SLOAD STOP
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from(vec![0x60, 0x01, 0x54, 0x00]))),
+                ..AccountInfo::default()
+            },
+        );
+
+        let txs = [
+            new_system_call_txn(contract, Bytes::new()),
+            new_system_call_txn(DEAD_ADDRESS, Bytes::new()),
+        ];
+        let access_lists = derive_access_lists(&db, SpecId::LATEST, Env::default(), &txs).unwrap();
+
+        assert_eq!(
+            access_lists[0],
+            vec![AccessListItem {
+                address: contract,
+                storage_keys: vec![B256::from(U256::from(1))],
+            }]
+        );
+        // A plain call to an account without storage needs no access list
+        assert!(access_lists[1].is_empty());
+    }
+
+    #[test]
+    fn test_decode_hex_stream() {
+        let hex_code = "6080604052".repeat(4000);
+        // With three characters before the digits the first chunk ends mid byte
+        assert_eq!(
+            decode_hex_stream(format!(" 0x{}\n", hex_code).as_bytes(), 0),
+            Ok(hex::decode(&hex_code).unwrap())
+        );
+        assert_eq!(
+            decode_hex_stream(" 0XdeadBEEF ".as_bytes(), 12),
+            Ok(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert!(decode_hex_stream("60__$1234$__".as_bytes(), 0).is_err());
+        assert!(decode_hex_stream("0x608".as_bytes(), 0).is_err());
+        assert!(decode_hex_stream("60x0".as_bytes(), 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_field() {
+        assert_eq!(
+            parse_hex_field("data", "0xdeadBEEF").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(
+            parse_hex_field("data", " deadbeef\n").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(parse_hex_field("data", "0x").unwrap(), Vec::<u8>::new());
+        let error = parse_hex_field("data", "0xzz").unwrap_err();
+        assert!(matches!(&error, GenesisError::HexDecode { field, .. } if field == "data"));
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        let jsonc = r#"{
+            // validator 1: the bootstrap node
+            "validatorAddresses": ["0x01", /* second */ "0x02",],
+            "url": "https://example.com/a//b/*c*/", /* trailing
+            block */
+            "quote": "say \"hi\", // not a comment",
+        }"#;
+        let json = strip_jsonc(jsonc).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["validatorAddresses"][1], "0x02");
+        assert_eq!(value["url"], "https://example.com/a//b/*c*/");
+        assert_eq!(value["quote"], "say \"hi\", // not a comment");
+        // Comments are blanked, not removed, so line numbers are kept
+        assert_eq!(json.lines().count(), jsonc.lines().count());
+
+        assert!(strip_jsonc("{ /* open").is_err());
+    }
+
+    #[test]
+    fn test_check_output_dir_rejects_same_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let byte_code_dir = root.join("bytecode");
+        let output_dir = root.join("output");
+        std::fs::create_dir_all(&byte_code_dir).unwrap();
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let path = |path: &std::path::Path| path.to_str().unwrap().to_string();
+
+        assert_eq!(
+            check_output_dir(&path(&output_dir), &path(&byte_code_dir)),
+            Ok(())
+        );
+        let dotted = path(&output_dir.join("../bytecode"));
+        assert!(check_output_dir(&dotted, &path(&byte_code_dir)).is_err());
+        #[cfg(unix)]
+        {
+            let link = root.join("link");
+            std::os::unix::fs::symlink(&byte_code_dir, &link).unwrap();
+            assert!(check_output_dir(&path(&link), &path(&byte_code_dir)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_staged_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        std::fs::write(path("accounts.json"), "old").unwrap();
+
+        // Dropped without a commit: the old file stays and nothing is left behind
+        let mut files = StagedFiles::default();
+        files.write(path("accounts.json"), "new").unwrap();
+        files.write(path("contracts.json"), "new").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path("accounts.json")).unwrap(),
+            "old"
+        );
+        drop(files);
+        let names = |dir: &std::path::Path| {
+            let mut names: Vec<String> = std::fs::read_dir(dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&dir), vec!["accounts.json"]);
+
+        let mut files = StagedFiles::default();
+        files.write(path("accounts.json"), "new").unwrap();
+        files.write(path("contracts.json"), "new").unwrap();
+        files.commit().unwrap();
+        assert_eq!(names(&dir), vec!["accounts.json", "contracts.json"]);
+        assert_eq!(
+            std::fs::read_to_string(path("accounts.json")).unwrap(),
+            "new"
+        );
+    }
+}
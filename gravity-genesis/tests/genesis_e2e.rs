@@ -0,0 +1,230 @@
+//! Full generation against Foundry artifacts: every system contract is deployed,
+//! `Genesis.initialize` runs, and the validator set and the Groth16 verifier are
+//! read back from the result.
+//!
+//! `test_genesis_e2e` runs against the `forge build` output committed in
+//! `tests/fixtures/forge`, which `make e2e-fixtures` refreshes from `../out`.
+//! `GRAVITY_GENESIS_BYTE_CODE_DIR` points it at another compiler output directory.
+//!
+//! `test_genesis_e2e_stubs` runs the same checks against the stub artifacts in
+//! `tests/fixtures/stubs`, whose contracts only return the answers the checks
+//! expect for `genesis_config.json`. It exercises the deployment pipeline in a
+//! plain `cargo test`, but says nothing about the real contracts. The stubs are
+//! regenerated with `GRAVITY_GENESIS_UPDATE_FIXTURES=1`.
+
+use std::path::PathBuf;
+
+use alloy_sol_types::{SolCall, SolValue};
+use gravity_genesis::{
+    execute::{self, GenerateOptions},
+    genesis::{GenesisConfig, IValidatorManager, parse_genesis_config},
+    jwks::COMPRESSED_GENERATOR_PROOF,
+    post_genesis,
+    utils::{CONTRACTS, ChecksumAddress, SYSTEM_ACCOUNT_INFO, SYSTEM_CALLER, artifact_path},
+};
+use revm::{InMemoryDB, db::BundleState};
+use revm_primitives::{Address, U256, hex};
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn stub_dir() -> PathBuf {
+    manifest_dir().join("tests/fixtures/stubs")
+}
+
+fn forge_fixture_dir() -> PathBuf {
+    manifest_dir().join("tests/fixtures/forge")
+}
+
+fn byte_code_dir() -> String {
+    std::env::var("GRAVITY_GENESIS_BYTE_CODE_DIR")
+        .unwrap_or_else(|_| forge_fixture_dir().to_string_lossy().into_owned())
+}
+
+/// Two validators with unequal voting power, everything else left at its default
+fn fixture_config() -> GenesisConfig {
+    let path = manifest_dir().join("tests/fixtures/genesis_config.json");
+    let config: GenesisConfig =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    config.validate().unwrap();
+    config
+}
+
+/// Code that copies `data`, stored right behind it, to memory and returns it,
+/// whatever the calldata
+fn returning(data: &[u8]) -> Vec<u8> {
+    let [high, low] = u16::try_from(data.len()).unwrap().to_be_bytes();
+    let mut code = vec![
+        0x61, high, low, 0x80, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+    ];
+    code.extend_from_slice(data);
+    code
+}
+
+/// Runtime code of the stub for `name`: the getters the checks call answer as the
+/// real contracts would after `Genesis.initialize` with `config`, everything else
+/// just stops
+fn stub_runtime_code(name: &str, config: &GenesisConfig) -> Vec<u8> {
+    match name {
+        "ValidatorManager" => {
+            let param = parse_genesis_config(config).unwrap();
+            let active_validators: Vec<_> = (0..config.validators.len())
+                .map(|i| IValidatorManager::ValidatorInfo {
+                    consensusPublicKey: param.consensus_public_keys[i].clone(),
+                    commission: IValidatorManager::Commission {
+                        rate: param.commission_rates[i],
+                        maxRate: 5_000,
+                        maxChangeRate: 500,
+                    },
+                    moniker: String::new(),
+                    registered: true,
+                    stakeCreditAddress: Address::ZERO,
+                    status: IValidatorManager::ValidatorStatus::ACTIVE,
+                    votingPower: param.voting_powers[i],
+                    validatorIndex: U256::from(i),
+                    updateTime: U256::ZERO,
+                    operator: param.validator_addresses[i],
+                    validatorNetworkAddresses: param.validator_network_addresses[i].clone(),
+                    fullnodeNetworkAddresses: param.fullnode_network_addresses[i].clone(),
+                    aptosAddress: param.aptos_addresses[i].clone(),
+                })
+                .collect();
+            let validator_set = IValidatorManager::ValidatorSet {
+                totalVotingPower: param.voting_powers.iter().sum(),
+                activeValidators: active_validators,
+                pendingInactive: Vec::new(),
+                pendingActive: Vec::new(),
+                totalJoiningPower: U256::ZERO,
+            };
+            returning(&IValidatorManager::getValidatorSetCall::abi_encode_returns(
+                &(validator_set,),
+            ))
+        }
+        "ValidatorPerformanceTracker" => {
+            returning(&U256::from(config.validators.len()).abi_encode())
+        }
+        "KeylessAccount" => returning(&config.contract_address("Groth16Verifier").abi_encode()),
+        "Groth16Verifier" => returning(&COMPRESSED_GENERATOR_PROOF.map(U256::from).abi_encode()),
+        _ => vec![0x00],
+    }
+}
+
+/// Foundry artifact of the stub for `name`, whose constructor returns its runtime code
+fn stub_artifact(name: &str, config: &GenesisConfig) -> String {
+    let runtime_code = stub_runtime_code(name, config);
+    let artifact = serde_json::json!({
+        "abi": [],
+        "bytecode": { "object": format!("0x{}", hex::encode(returning(&runtime_code))) },
+        "deployedBytecode": { "object": format!("0x{}", hex::encode(&runtime_code)) },
+    });
+    serde_json::to_string_pretty(&artifact).unwrap() + "\n"
+}
+
+/// The committed stubs must still answer for the fixture config; set
+/// `GRAVITY_GENESIS_UPDATE_FIXTURES` to rewrite them after changing either
+#[test]
+fn test_stub_artifacts_up_to_date() {
+    let config = fixture_config();
+    let update = std::env::var_os("GRAVITY_GENESIS_UPDATE_FIXTURES").is_some();
+    for (name, _) in CONTRACTS {
+        let path = PathBuf::from(artifact_path(&stub_dir().to_string_lossy(), name));
+        let expected = stub_artifact(name, &config);
+        if update {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, expected).unwrap();
+        } else {
+            let committed = std::fs::read_to_string(&path).unwrap_or_default();
+            assert!(
+                committed == expected,
+                "{} is stale, rerun with GRAVITY_GENESIS_UPDATE_FIXTURES=1",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Copy the artifacts of every system contract from `GRAVITY_GENESIS_BYTE_CODE_DIR`
+/// into `tests/fixtures/forge`, keeping only what generation reads from them
+#[test]
+#[ignore = "run by `make e2e-fixtures` after `forge build`"]
+fn update_forge_fixtures() {
+    let out_dir = std::env::var("GRAVITY_GENESIS_BYTE_CODE_DIR")
+        .expect("GRAVITY_GENESIS_BYTE_CODE_DIR names the forge output directory");
+    for (name, _) in CONTRACTS {
+        let artifact: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(artifact_path(&out_dir, name)).unwrap())
+                .unwrap();
+        let fixture = serde_json::json!({
+            "abi": artifact["abi"],
+            "bytecode": artifact["bytecode"],
+            "deployedBytecode": artifact["deployedBytecode"],
+        });
+        let path = PathBuf::from(artifact_path(&forge_fixture_dir().to_string_lossy(), name));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            serde_json::to_string_pretty(&fixture).unwrap() + "\n",
+        )
+        .unwrap();
+    }
+}
+
+/// Generate the genesis of the fixture config from the artifacts in `byte_code_dir`
+/// and check it the way a node operator would
+fn check_genesis(byte_code_dir: &str) {
+    let config = fixture_config();
+    let state = execute::compute_genesis_state(
+        byte_code_dir,
+        &config,
+        None,
+        None,
+        &GenerateOptions::default(),
+    )
+    .unwrap_or_else(|report| panic!("{}", report));
+
+    // Constructed runtime code, not creation code or nothing, at every address
+    for (name, address) in CONTRACTS {
+        let code = state
+            .get(&address)
+            .and_then(|account| account.info.code.as_ref());
+        assert!(
+            code.is_some_and(|code| !code.original_bytes().is_empty()),
+            "{} has no code at {}",
+            name,
+            ChecksumAddress(address)
+        );
+    }
+
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(SYSTEM_CALLER, SYSTEM_ACCOUNT_INFO);
+    for (address, account) in state {
+        db.insert_account_info(address, account.info);
+        for (slot, value) in account.storage {
+            db.insert_account_storage(address, slot, value).unwrap();
+        }
+    }
+    post_genesis::verify_validator_count(&db, BundleState::default(), &config).unwrap();
+    if let Err(mismatches) =
+        post_genesis::verify_validator_set(&db, BundleState::default(), &config)
+    {
+        panic!("validator set differs from the config: {:?}", mismatches);
+    }
+    // KeylessAccount is wired to the verifier, and the verifier's code runs
+    if let Err(mismatches) =
+        post_genesis::verify_keyless_verifier(&db, BundleState::default(), &config)
+    {
+        panic!("Groth16 verifier is not usable: {:?}", mismatches);
+    }
+}
+
+#[test]
+#[ignore = "needs the forge build output in tests/fixtures/forge, written by `make e2e-fixtures`"]
+fn test_genesis_e2e() {
+    check_genesis(&byte_code_dir());
+}
+
+#[test]
+fn test_genesis_e2e_stubs() {
+    check_genesis(&stub_dir().to_string_lossy());
+}
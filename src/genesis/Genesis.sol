@@ -38,7 +38,8 @@ contract Genesis is System {
         uint256[] calldata votingPowers,
         bytes[] calldata validatorNetworkAddresses,
         bytes[] calldata fullnodeNetworkAddresses,
-        bytes[] calldata aptosAddresses
+        bytes[] calldata aptosAddresses,
+        uint64[] calldata commissionRates
     ) external onlySystemCaller {
         if (genesisCompleted) revert GenesisAlreadyCompleted();
         if (consensusPublicKeys.length == 0) revert InvalidInitialValidators();
@@ -50,7 +51,8 @@ contract Genesis is System {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // 2. Initialize epoch module
@@ -84,7 +86,8 @@ contract Genesis is System {
         uint256[] calldata votingPowers,
         bytes[] calldata validatorNetworkAddresses,
         bytes[] calldata fullnodeNetworkAddresses,
-        bytes[] calldata aptosAddresses
+        bytes[] calldata aptosAddresses,
+        uint64[] calldata commissionRates
     ) internal {
         // Initialize StakeConfig
         IStakeConfig(STAKE_CONFIG_ADDR).initialize();
@@ -96,7 +99,8 @@ contract Genesis is System {
             votingPowers: votingPowers,
             validatorNetworkAddresses: validatorNetworkAddresses,
             fullnodeNetworkAddresses: fullnodeNetworkAddresses,
-            aptosAddresses: aptosAddresses
+            aptosAddresses: aptosAddresses,
+            commissionRates: commissionRates
         });
 
         IValidatorManager(VALIDATOR_MANAGER_ADDR).initialize(initParams);
@@ -144,6 +144,7 @@ interface IValidatorManager is IReconfigurableModule {
         bytes[] validatorNetworkAddresses;
         bytes[] fullnodeNetworkAddresses;
         bytes[] aptosAddresses;
+        uint64[] commissionRates; // initial commission rate per validator (10000 is 100%)
     }
 
     /**
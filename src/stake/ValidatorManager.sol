@@ -112,6 +112,7 @@ contract ValidatorManager is System, ReentrancyGuard, Protectable, IValidatorMan
                 || params.validatorAddresses.length != params.votingPowers.length
                 || params.validatorAddresses.length != params.validatorNetworkAddresses.length
                 || params.validatorAddresses.length != params.fullnodeNetworkAddresses.length
+                || params.validatorAddresses.length != params.commissionRates.length
         ) revert ArrayLengthMismatch();
 
         initialized = true;
@@ -130,6 +131,9 @@ contract ValidatorManager is System, ReentrancyGuard, Protectable, IValidatorMan
 
             if (votingPower == 0) revert InvalidVotingPower(votingPower);
 
+            uint64 commissionRate = params.commissionRates[i];
+            if (commissionRate > 10000) revert InvalidCommissionRate(commissionRate, 10000);
+
             // deploy StakeCredit contract for initial validator
             address stakeCreditAddress = _deployStakeCreditWithValue(
                 validator, string(abi.encodePacked("VAL", uint256(i))), validator, votingPower
@@ -139,8 +143,9 @@ contract ValidatorManager is System, ReentrancyGuard, Protectable, IValidatorMan
             validatorInfos[validator] = ValidatorInfo({
                 consensusPublicKey: consensusPublicKey,
                 commission: Commission({
-                    rate: 0,
-                    maxRate: 5000, // default max commission rate 50%
+                    rate: commissionRate,
+                    // default max commission rate 50%, raised to a higher initial rate
+                    maxRate: commissionRate > 5000 ? commissionRate : 5000,
                     maxChangeRate: 500 // default max daily change rate 5%
                 }),
                 moniker: string(abi.encodePacked("VAL", uint256(i))), // generate default name
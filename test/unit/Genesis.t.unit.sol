@@ -28,6 +28,7 @@ contract GenesisTest is Test, TestConstants {
     bytes[] public validatorNetworkAddresses;
     bytes[] public fullnodeNetworkAddresses;
     bytes[] public aptosAddresses;
+    uint64[] public commissionRates;
 
     function setUp() public {
         // Deploy Genesis contract
@@ -48,6 +49,7 @@ contract GenesisTest is Test, TestConstants {
         validatorNetworkAddresses = new bytes[](3);
         fullnodeNetworkAddresses = new bytes[](3);
         aptosAddresses = new bytes[](0);
+        commissionRates = new uint64[](3);
 
         validatorAddresses[0] = address(0x1111);
         validatorAddresses[1] = address(0x2222);
@@ -68,6 +70,10 @@ contract GenesisTest is Test, TestConstants {
         fullnodeNetworkAddresses[0] = abi.encodePacked(bytes32(uint256(0xaaaa)));
         fullnodeNetworkAddresses[1] = abi.encodePacked(bytes32(uint256(0xbbbb)));
         fullnodeNetworkAddresses[2] = abi.encodePacked(bytes32(uint256(0xcccc)));
+
+        commissionRates[0] = 0;
+        commissionRates[1] = DEFAULT_COMMISSION_RATE;
+        commissionRates[2] = 7500;
     }
 
     function _deployMockContracts() internal {
@@ -102,7 +108,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -121,7 +128,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert - Check that all subsystems were initialized
@@ -141,7 +149,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -165,7 +174,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
     }
 
@@ -178,7 +188,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         assertTrue(genesis.isGenesisCompleted());
@@ -195,7 +206,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
         assertTrue(genesis.isGenesisCompleted());
 
@@ -208,7 +220,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
     }
 
@@ -232,7 +245,8 @@ contract GenesisTest is Test, TestConstants {
             emptyPowers,
             emptyValidatorNetworkAddresses,
             emptyFullnodeNetworkAddresses,
-            emptyAptosAddresses
+            emptyAptosAddresses,
+            commissionRates
         );
     }
 
@@ -259,7 +273,8 @@ contract GenesisTest is Test, TestConstants {
             singlePower,
             singleValidatorNetworkAddresses,
             singleFullnodeNetworkAddresses,
-            singleAptosAddresses
+            singleAptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -294,7 +309,8 @@ contract GenesisTest is Test, TestConstants {
             largeVotingPowers,
             largeValidatorNetworkAddresses,
             largeFullnodeNetworkAddresses,
-            largeAptosAddresses
+            largeAptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -315,7 +331,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -336,7 +353,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -352,7 +370,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -375,7 +394,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -399,7 +419,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
     }
 
@@ -415,7 +436,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert - Verify final state
@@ -430,7 +452,8 @@ contract GenesisTest is Test, TestConstants {
             votingPowers,
             validatorNetworkAddresses,
             fullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
     }
 
@@ -484,7 +507,8 @@ contract GenesisTest is Test, TestConstants {
             realisticPowers,
             realisticValidatorNetworkAddresses,
             realisticFullnodeNetworkAddresses,
-            aptosAddresses
+            aptosAddresses,
+            commissionRates
         );
 
         // Assert
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.30;
+
+import "forge-std/Test.sol";
+import "@src/stake/ValidatorManager.sol";
+import "@src/stake/StakeCredit.sol";
+import "@test/mocks/TimestampMock.sol";
+import "@test/utils/TestConstants.sol";
+import { IValidatorManager } from "@src/interfaces/IValidatorManager.sol";
+
+contract ValidatorManagerTest is Test, TestConstants {
+    ValidatorManager validatorManager;
+
+    uint256 constant VOTING_POWER = 1 ether;
+
+    function setUp() public {
+        validatorManager = new ValidatorManager();
+
+        // Each initial validator gets a StakeCredit proxy funded with its voting power
+        vm.etch(STAKE_CREDIT_ADDR, address(new StakeCredit()).code);
+        vm.etch(TIMESTAMP_ADDR, address(new TimestampMock()).code);
+        vm.deal(address(validatorManager), VOTING_POWER);
+    }
+
+    function _initializationParams(
+        uint64 commissionRate
+    ) internal pure returns (IValidatorManager.InitializationParams memory params) {
+        params.validatorAddresses = new address[](1);
+        params.consensusPublicKeys = new bytes[](1);
+        params.votingPowers = new uint256[](1);
+        params.validatorNetworkAddresses = new bytes[](1);
+        params.fullnodeNetworkAddresses = new bytes[](1);
+        params.aptosAddresses = new bytes[](1);
+        params.commissionRates = new uint64[](1);
+
+        params.validatorAddresses[0] = TEST_VALIDATOR_1;
+        params.consensusPublicKeys[0] = abi.encodePacked(bytes32(uint256(0x4444)));
+        params.votingPowers[0] = VOTING_POWER;
+        params.validatorNetworkAddresses[0] = abi.encodePacked(bytes32(uint256(0x7777)));
+        params.fullnodeNetworkAddresses[0] = abi.encodePacked(bytes32(uint256(0xaaaa)));
+        params.aptosAddresses[0] = abi.encodePacked(bytes32(uint256(uint160(TEST_VALIDATOR_1))));
+        params.commissionRates[0] = commissionRate;
+    }
+
+    function _initialize(
+        uint64 commissionRate
+    ) internal returns (IValidatorManager.Commission memory) {
+        vm.prank(GENESIS_ADDR);
+        validatorManager.initialize(_initializationParams(commissionRate));
+        return validatorManager.getValidatorInfo(TEST_VALIDATOR_1).commission;
+    }
+
+    function test_initialize_zeroCommissionRate_shouldKeepDefaultMaxRate() public {
+        // Act
+        IValidatorManager.Commission memory commission = _initialize(0);
+
+        // Assert
+        assertEq(commission.rate, 0);
+        assertEq(commission.maxRate, MAX_COMMISSION_RATE);
+        assertEq(commission.maxChangeRate, 500);
+    }
+
+    function test_initialize_commissionRateAboveDefaultMax_shouldRaiseMaxRate() public {
+        // Act
+        IValidatorManager.Commission memory commission = _initialize(7500);
+
+        // Assert
+        assertEq(commission.rate, 7500);
+        assertEq(commission.maxRate, 7500);
+        assertEq(commission.maxChangeRate, 500);
+    }
+
+    function test_initialize_commissionRateAbove100Percent_shouldRevert() public {
+        // Arrange
+        IValidatorManager.InitializationParams memory params = _initializationParams(10001);
+
+        // Act & Assert
+        vm.prank(GENESIS_ADDR);
+        vm.expectRevert(abi.encodeWithSelector(IValidatorManager.InvalidCommissionRate.selector, 10001, 10000));
+        validatorManager.initialize(params);
+    }
+}